@@ -0,0 +1,198 @@
+// Impressions and clicks per hour over the last two days, from the leader's event aggregates,
+// and the latest of those aggregates as a feed
+use super::{duration, types, Msg};
+
+use chrono::{DateTime, Utc};
+use seed::prelude::*;
+use std::collections::BTreeMap;
+use types::{ChannelActivity, EventAggregate, EventAggregates};
+
+pub const HOURS: i64 = 48;
+const HOUR_MS: i64 = 60 * 60 * 1000;
+const CHART_HEIGHT: u64 = 80;
+const BAR_WIDTH: u64 = 8;
+const FEED_LEN: usize = 10;
+
+// Counts of `event_type` per hour, oldest first; the last bucket is the hour up to `now`
+// Aggregates from the future (our clock being behind) go in the last bucket, older ones are dropped
+pub fn hourly(aggregates: &EventAggregates, event_type: &str, now: &DateTime<Utc>) -> Vec<u64> {
+    let mut buckets = vec![0u64; HOURS as usize];
+    for aggregate in &aggregates.events {
+        let hours_ago = ((now.timestamp_millis() - aggregate.created.timestamp_millis()) / HOUR_MS).max(0);
+        if hours_ago >= HOURS {
+            continue;
+        }
+        let count: u64 = aggregate
+            .events
+            .get(event_type)
+            .map(|counts| {
+                counts
+                    .event_counts
+                    .values()
+                    .map(|count| count.to_u64().unwrap_or(0))
+                    .sum()
+            })
+            .unwrap_or(0);
+        let bucket = &mut buckets[(HOURS - 1 - hours_ago) as usize];
+        *bucket = bucket.saturating_add(count);
+    }
+    buckets
+}
+
+// Bar heights out of `height`, the largest value getting all of it
+// Any value above zero gets at least 1, so a quiet hour doesn't look like no activity at all
+pub fn bar_heights(values: &[u64], height: u64) -> Vec<u64> {
+    let max = values.iter().cloned().max().unwrap_or(0);
+    if max == 0 {
+        return vec![0; values.len()];
+    }
+    values
+        .iter()
+        .map(|&value| match value {
+            0 => 0,
+            value => ((u128::from(value) * u128::from(height) / u128::from(max)) as u64).max(1),
+        })
+        .collect()
+}
+
+// The newest aggregates that counted anything, newest first, with the count of each event type
+pub fn recent(aggregates: &EventAggregates, len: usize) -> Vec<(&DateTime<Utc>, BTreeMap<&str, u64>)> {
+    let mut recent = aggregates
+        .events
+        .iter()
+        .map(|aggregate| (&aggregate.created, counts(aggregate)))
+        .filter(|(_, counts)| counts.values().any(|&count| count > 0))
+        .collect::<Vec<_>>();
+    recent.sort_by(|a, b| b.0.cmp(a.0));
+    recent.truncate(len);
+    recent
+}
+
+fn counts(aggregate: &EventAggregate) -> BTreeMap<&str, u64> {
+    aggregate
+        .events
+        .iter()
+        .map(|(event_type, counts)| {
+            let count = counts
+                .event_counts
+                .values()
+                .map(|count| count.to_u64().unwrap_or(0))
+                .fold(0u64, u64::saturating_add);
+            (event_type.as_str(), count)
+        })
+        .collect()
+}
+
+pub fn activity_view(channel_id: &str, activity: Option<&ChannelActivity>, now: &DateTime<Utc>) -> Node<Msg> {
+    div![
+        class!["activity"],
+        h3![format!("Activity over the last {} hours", HOURS)],
+        match activity {
+            None => button![
+                "Load activity",
+                simple_ev(Ev::Click, Msg::LoadActivity(channel_id.to_owned()))
+            ],
+            Some(ChannelActivity::Loading) => p!["Asking the leader..."],
+            Some(ChannelActivity::Unavailable(reason)) => p![class!["note"], reason.as_str()],
+            Some(ChannelActivity::Loaded(aggregates)) => {
+                let impressions = hourly(aggregates, "IMPRESSION", now);
+                let clicks = hourly(aggregates, "CLICK", now);
+                div![
+                    p![format!(
+                        "{} impressions, {} clicks",
+                        impressions.iter().sum::<u64>(),
+                        clicks.iter().sum::<u64>()
+                    )],
+                    label!["Impressions per hour, oldest first"],
+                    bar_chart(&impressions, "impressions"),
+                    if clicks.iter().any(|&count| count > 0) {
+                        vec![label!["Clicks per hour"], bar_chart(&clicks, "clicks")]
+                    } else {
+                        vec![]
+                    },
+                    event_feed(aggregates, now)
+                ]
+            }
+        }
+    ]
+}
+
+fn event_feed(aggregates: &EventAggregates, now: &DateTime<Utc>) -> Node<Msg> {
+    let recent = recent(aggregates, FEED_LEN);
+    if recent.is_empty() {
+        return seed::empty();
+    }
+    div![
+        class!["event-feed"],
+        h4!["Latest events"],
+        ul![recent
+            .iter()
+            .map(|(created, counts)| li![
+                span![
+                    class!["event-time"],
+                    attrs! { At::Title => created.to_rfc3339() },
+                    duration::humanize_past(now.signed_duration_since(**created))
+                ],
+                counts
+                    .iter()
+                    .filter(|(_, count)| **count > 0)
+                    .map(|(event_type, count)| format!(" · {} {}", count, event_type.to_lowercase()))
+                    .collect::<String>()
+            ])
+            .collect::<Vec<_>>()]
+    ]
+}
+
+fn bar_chart(values: &[u64], series: &str) -> Node<Msg> {
+    let width = BAR_WIDTH * values.len() as u64;
+    svg![
+        class!["activity-chart", series],
+        attrs! {
+            At::Width => format!("{}px", width);
+            At::Height => format!("{}px", CHART_HEIGHT);
+            At::ViewBox => format!("0 0 {} {}", width, CHART_HEIGHT);
+        },
+        bar_heights(values, CHART_HEIGHT)
+            .iter()
+            .enumerate()
+            .map(|(i, &bar)| {
+                rect![attrs! {
+                    At::Custom("x".into()) => (i as u64 * BAR_WIDTH).to_string();
+                    At::Custom("y".into()) => (CHART_HEIGHT - bar).to_string();
+                    At::Width => (BAR_WIDTH - 1).to_string();
+                    At::Height => bar.to_string();
+                }]
+            })
+            .collect::<Vec<_>>()
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bar_heights_scale_to_the_largest_value() {
+        assert_eq!(bar_heights(&[0, 5, 10, 20], 80), vec![0, 20, 40, 80]);
+        // Rounded down
+        assert_eq!(bar_heights(&[1, 2, 3], 10), vec![3, 6, 10]);
+    }
+
+    #[test]
+    fn bar_heights_keep_small_values_visible() {
+        assert_eq!(bar_heights(&[1, 0, 1000], 80), vec![1, 0, 80]);
+    }
+
+    #[test]
+    fn bar_heights_without_activity() {
+        assert_eq!(bar_heights(&[0, 0, 0], 80), vec![0, 0, 0]);
+        assert_eq!(bar_heights(&[], 80), Vec::<u64>::new());
+    }
+
+    #[test]
+    fn bar_heights_do_not_overflow() {
+        let max = u64::max_value();
+        assert_eq!(bar_heights(&[max, max / 2, 1], 80), vec![80, 39, 1]);
+        assert_eq!(bar_heights(&[max], max), vec![max]);
+    }
+}
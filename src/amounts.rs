@@ -0,0 +1,168 @@
+// Which unit the table shows amounts in; wei and Gwei are there for checking numbers against
+// what's on chain, USD for people who think in dollars
+use super::{bignum, dai_readable};
+
+use adex_domain::BigNum;
+use num_format::Locale;
+use serde::{Deserialize, Serialize};
+
+const GWEI_DECIMALS: u32 = 9;
+const DAI_DECIMALS: u32 = 18;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum AmountUnit {
+    Wei,
+    Gwei,
+    Dai,
+    // Every one of the 18 decimals, for reconciling against on-chain data
+    DaiExact,
+    // Scaled from each channel's own `usd_estimate`, so only approximate; see dai_cell
+    Usd,
+}
+
+impl Default for AmountUnit {
+    fn default() -> Self {
+        AmountUnit::Dai
+    }
+}
+
+impl AmountUnit {
+    pub fn name(self) -> &'static str {
+        match self {
+            AmountUnit::Wei => "wei",
+            AmountUnit::Gwei => "gwei",
+            AmountUnit::Dai => "dai",
+            AmountUnit::DaiExact => "dai-exact",
+            AmountUnit::Usd => "usd",
+        }
+    }
+}
+
+impl From<String> for AmountUnit {
+    fn from(name: String) -> Self {
+        match name.as_ref() {
+            "wei" => AmountUnit::Wei,
+            "gwei" => AmountUnit::Gwei,
+            "usd" => AmountUnit::Usd,
+            "dai-exact" => AmountUnit::DaiExact,
+            _ => AmountUnit::Dai,
+        }
+    }
+}
+
+// `precision` is the number of decimals for Gwei (at most 9) and DAI; wei are always whole, with
+// the locale's thousands separator since they run to 20 digits and more
+// A bare amount can't be put in USD without the channel it belongs to, so USD comes out in DAI,
+// labelled as such; amount_text in lib.rs has the dollars for when the channel is known
+pub fn format_amount(bal: &BigNum, unit: AmountUnit, precision: u8, locale: &Locale) -> String {
+    match unit {
+        AmountUnit::Wei => format!("{} wei", format_units(bal, 0, locale)),
+        AmountUnit::Gwei => {
+            let decimals = u32::from(precision).min(GWEI_DECIMALS);
+            match bignum::ratio_string(bal, &BigNum::from(10u64.pow(GWEI_DECIMALS)), decimals) {
+                Some(gwei) => format!("{} Gwei", gwei),
+                None => ">max".to_owned(),
+            }
+        }
+        AmountUnit::DaiExact => format!("{} DAI", format_units(bal, DAI_DECIMALS, locale)),
+        AmountUnit::Dai | AmountUnit::Usd => dai_readable(bal, precision),
+    }
+}
+
+// The exact amount in wei, for tooltips next to rounded figures
+pub fn exact_wei(bal: &BigNum) -> String {
+    format_amount(bal, AmountUnit::Wei, 0, &Locale::en)
+}
+
+// `bal` in a unit with `decimals` decimals, with all of them and nothing rounded, e.g.
+// "1,234.500000000000000000" for 1234.5 DAI; the integer part gets the locale's separators
+pub fn format_units(bal: &BigNum, decimals: u32, locale: &Locale) -> String {
+    let digits = bignum::to_decimal_string(bal);
+    let decimals = decimals as usize;
+    // Zero-padded so there's at least one digit before the point
+    let digits = format!("{:0>width$}", digits, width = decimals + 1);
+    let (whole, fraction) = digits.split_at(digits.len() - decimals);
+    if fraction.is_empty() {
+        group_digits(whole, locale)
+    } else {
+        format!("{}.{}", group_digits(whole, locale), fraction)
+    }
+}
+
+fn group_digits(digits: &str, locale: &Locale) -> String {
+    let len = digits.len();
+    digits
+        .chars()
+        .enumerate()
+        .fold(String::new(), |mut grouped, (i, digit)| {
+            if i > 0 && (len - i) % 3 == 0 {
+                grouped.push_str(locale.separator());
+            }
+            grouped.push(digit);
+            grouped
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::big;
+
+    // 1234.5 DAI
+    fn amount() -> BigNum {
+        big("1234500000000000000000")
+    }
+
+    #[test]
+    fn format_amount_in_each_unit() {
+        let cases = [
+            (AmountUnit::Wei, "1,234,500,000,000,000,000,000 wei"),
+            (AmountUnit::Gwei, "1234500000000.00 Gwei"),
+            (AmountUnit::Dai, "1234.50 DAI"),
+            (AmountUnit::DaiExact, "1,234.500000000000000000 DAI"),
+            (AmountUnit::Usd, "1234.50 DAI"),
+        ];
+        for (unit, expected) in cases.iter() {
+            assert_eq!(format_amount(&amount(), *unit, 2, &Locale::en), *expected, "{:?}", unit);
+        }
+    }
+
+    #[test]
+    fn format_amount_precision_only_applies_to_rounded_units() {
+        let amount = big("1500000001");
+        assert_eq!(format_amount(&amount, AmountUnit::Wei, 6, &Locale::en), "1,500,000,001 wei");
+        assert_eq!(format_amount(&amount, AmountUnit::Gwei, 0, &Locale::en), "2 Gwei");
+        // Gwei only have 9 decimals to show
+        assert_eq!(format_amount(&amount, AmountUnit::Gwei, 18, &Locale::en), "1.500000001 Gwei");
+        assert_eq!(format_amount(&amount, AmountUnit::Dai, 0, &Locale::en), "0 DAI");
+        assert_eq!(
+            format_amount(&amount, AmountUnit::DaiExact, 0, &Locale::en),
+            "0.000000001500000001 DAI"
+        );
+    }
+
+    #[test]
+    fn format_amount_uses_the_locale_separator() {
+        assert_eq!(
+            format_amount(&amount(), AmountUnit::Wei, 0, &Locale::de),
+            "1.234.500.000.000.000.000.000 wei"
+        );
+        assert_eq!(exact_wei(&big("0")), "0 wei");
+    }
+
+    #[test]
+    fn unit_names_round_trip() {
+        for unit in [
+            AmountUnit::Wei,
+            AmountUnit::Gwei,
+            AmountUnit::Dai,
+            AmountUnit::DaiExact,
+            AmountUnit::Usd,
+        ]
+        .iter()
+        {
+            assert_eq!(AmountUnit::from(unit.name().to_owned()), *unit);
+        }
+        assert_eq!(AmountUnit::from("eth".to_owned()), AmountUnit::Dai);
+    }
+}
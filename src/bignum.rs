@@ -0,0 +1,240 @@
+use adex_domain::BigNum;
+use num::BigUint;
+use std::borrow::Borrow;
+
+// `a - b`, clamped at zero instead of underflowing
+// The flag is set when the result had to be clamped
+pub fn saturating_sub(a: &BigNum, b: &BigNum) -> (BigNum, bool) {
+    if a >= b {
+        (a - b, false)
+    } else {
+        (BigNum::from(0), true)
+    }
+}
+
+// Like `Iterator::sum`, without a BigNum allocation for every addition: amounts below 2^128
+// (every balance there's ever been) are added up in a u128, and only the others, or the u128 total
+// whenever it would overflow, go through BigNum
+pub fn sum<I>(amounts: I) -> BigNum
+where
+    I: IntoIterator,
+    I::Item: Borrow<BigNum>,
+{
+    let mut big = BigNum::from(0);
+    let mut small: u128 = 0;
+    for amount in amounts {
+        let amount = amount.borrow();
+        match to_u128(amount) {
+            Some(n) => match small.checked_add(n) {
+                Some(total) => small = total,
+                None => {
+                    big = &big + &from_u128(small);
+                    small = n;
+                }
+            },
+            None => big = &big + amount,
+        }
+    }
+    &big + &from_u128(small)
+}
+
+// None from 2^128 up
+fn to_u128(amount: &BigNum) -> Option<u128> {
+    let bytes = amount.to_bytes_le();
+    if bytes.len() > 16 {
+        return None;
+    }
+    let mut le = [0u8; 16];
+    le[..bytes.len()].copy_from_slice(&bytes);
+    Some(u128::from_le_bytes(le))
+}
+
+fn from_u128(n: u128) -> BigNum {
+    BigNum::from(BigUint::from_bytes_le(&n.to_le_bytes()))
+}
+
+// `a - b` as a magnitude and whether it went up; None when they're equal
+pub fn diff(a: &BigNum, b: &BigNum) -> Option<(BigNum, bool)> {
+    if a > b {
+        Some((a - b, true))
+    } else if a < b {
+        Some((b - a, false))
+    } else {
+        None
+    }
+}
+
+// `numerator / denominator` as a decimal string with exactly `decimals` digits after the point,
+// rounded half-up; integer math only, so it stays exact however large the values are
+// None when dividing by zero or when the integer part doesn't fit in a u64
+pub fn ratio_string(numerator: &BigNum, denominator: &BigNum, decimals: u32) -> Option<String> {
+    if *denominator == BigNum::from(0) {
+        return None;
+    }
+    let scale = 10u64.pow(decimals);
+    let two = BigNum::from(2);
+    // floor((2 * n * scale + d) / (2 * d)) == round_half_up(n * scale / d)
+    let doubled = &(&(numerator * &BigNum::from(scale)) * &two) + denominator;
+    let scaled = doubled.div_floor(&(denominator * &two));
+    let whole = scaled.div_floor(&BigNum::from(scale)).to_u64()?;
+    let fraction = (&scaled - &(&BigNum::from(whole) * &BigNum::from(scale))).to_u64()?;
+    if decimals == 0 {
+        Some(whole.to_string())
+    } else {
+        Some(format!(
+            "{}.{:0width$}",
+            whole,
+            fraction,
+            width = decimals as usize
+        ))
+    }
+}
+
+// The exact integer, however many digits it has
+pub fn to_decimal_string(n: &BigNum) -> String {
+    // 18 digits at a time, which always fits in a u64
+    let chunk = BigNum::from(10u64.pow(18));
+    let zero = BigNum::from(0);
+    let mut chunks = Vec::<u64>::new();
+    let mut rest = n.clone();
+    loop {
+        let high = rest.div_floor(&chunk);
+        chunks.push((&rest - &(&high * &chunk)).to_u64().unwrap_or(0));
+        if high == zero {
+            break;
+        }
+        rest = high;
+    }
+    let mut chunks = chunks.iter().rev();
+    let first = chunks.next().map_or("0".to_owned(), u64::to_string);
+    chunks.fold(first, |digits, chunk| format!("{}{:018}", digits, chunk))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::big;
+
+    #[test]
+    fn saturating_sub_clamps_at_zero() {
+        assert_eq!(saturating_sub(&big("10"), &big("3")), (big("7"), false));
+        assert_eq!(saturating_sub(&big("10"), &big("10")), (big("0"), false));
+        assert_eq!(saturating_sub(&big("3"), &big("10")), (big("0"), true));
+        assert_eq!(
+            saturating_sub(&big("1000000000000000000000000"), &big("1")),
+            (big("999999999999999999999999"), false)
+        );
+    }
+
+    #[test]
+    fn diff_is_a_magnitude_and_a_direction() {
+        assert_eq!(diff(&big("10"), &big("3")), Some((big("7"), true)));
+        assert_eq!(diff(&big("3"), &big("10")), Some((big("7"), false)));
+        assert_eq!(diff(&big("5"), &big("5")), None);
+        assert_eq!(diff(&big("0"), &big("0")), None);
+    }
+
+    const ONE_DAI: &str = "1000000000000000000";
+    // A million DAI, in wei
+    const E24: &str = "1000000000000000000000000";
+
+    #[test]
+    fn ratio_string_of_large_amounts() {
+        assert_eq!(ratio_string(&big(E24), &big(ONE_DAI), 2), Some("1000000.00".to_owned()));
+        assert_eq!(
+            ratio_string(&big("999999999999999999999999"), &big(ONE_DAI), 2),
+            Some("1000000.00".to_owned())
+        );
+        assert_eq!(
+            ratio_string(&big("999999994999999999999999"), &big(ONE_DAI), 2),
+            Some("999999.99".to_owned())
+        );
+        assert_eq!(
+            ratio_string(&big("1000000000000000000000001"), &big(E24), 18),
+            Some("1.000000000000000000".to_owned())
+        );
+        assert_eq!(ratio_string(&big(ONE_DAI), &big(E24), 6), Some("0.000001".to_owned()));
+    }
+
+    #[test]
+    fn ratio_string_rounds_half_up() {
+        assert_eq!(ratio_string(&big("1"), &big("3"), 2), Some("0.33".to_owned()));
+        assert_eq!(ratio_string(&big("2"), &big("3"), 2), Some("0.67".to_owned()));
+        assert_eq!(ratio_string(&big("1"), &big("8"), 2), Some("0.13".to_owned()));
+        assert_eq!(ratio_string(&big("1"), &big("2"), 0), Some("1".to_owned()));
+        assert_eq!(ratio_string(&big("0"), &big("7"), 3), Some("0.000".to_owned()));
+    }
+
+    #[test]
+    fn ratio_string_without_a_result() {
+        assert_eq!(ratio_string(&big("1"), &big("0"), 2), None);
+        // The integer part is past a u64
+        assert_eq!(ratio_string(&big(E24), &big("1"), 2), None);
+    }
+
+    #[test]
+    fn to_decimal_string_keeps_every_digit() {
+        assert_eq!(to_decimal_string(&big("0")), "0");
+        assert_eq!(to_decimal_string(&big("7")), "7");
+        assert_eq!(to_decimal_string(&big(ONE_DAI)), ONE_DAI);
+        assert_eq!(to_decimal_string(&big("999999999999999999")), "999999999999999999");
+        assert_eq!(to_decimal_string(&big(E24)), E24);
+        assert_eq!(
+            to_decimal_string(&big("1000000000000000000000000000000000000001")),
+            "1000000000000000000000000000000000000001"
+        );
+        // Zeroes inside a chunk aren't dropped
+        assert_eq!(
+            to_decimal_string(&big("5000000000000000000000000000000000000")),
+            "5000000000000000000000000000000000000"
+        );
+    }
+
+    // 2^128, the first amount that doesn't fit in a u128
+    const U128_END: &str = "340282366920938463463374607431768211456";
+
+    #[test]
+    fn u128_conversions_stop_at_2_pow_128() {
+        assert_eq!(to_u128(&big("0")), Some(0));
+        assert_eq!(to_u128(&big(&u128::MAX.to_string())), Some(u128::MAX));
+        assert_eq!(to_u128(&big(U128_END)), None);
+        for &n in [0, 1, u128::from(u64::MAX) + 1, u128::MAX].iter() {
+            assert_eq!(from_u128(n), big(&n.to_string()));
+        }
+    }
+
+    #[test]
+    fn sum_across_the_u128_boundary() {
+        let max = u128::MAX.to_string();
+        assert_eq!(sum(vec![big(&max), big("1")]), big(U128_END));
+        assert_eq!(
+            sum(vec![big(&max), big(&max)]),
+            big("680564733841876926926749214863536422910")
+        );
+        // One past the boundary on its own, with small amounts either side
+        assert_eq!(
+            sum(vec![big("5"), big(U128_END), big("7")]),
+            big("340282366920938463463374607431768211468")
+        );
+        assert_eq!(sum(Vec::<BigNum>::new()), big("0"));
+    }
+
+    // Compares the timings without asserting on them; `cargo test -- --ignored --nocapture`
+    // prints both
+    #[test]
+    #[ignore]
+    fn sum_against_adding_bignums() {
+        // Balances from 0.001 to about 1,000 DAI, as most of them are
+        let amounts = (0..200_000u64)
+            .map(|i| big(&format!("{}000000000000000", 1 + i * 5 % 1_000_000)))
+            .collect::<Vec<_>>();
+        let start = std::time::Instant::now();
+        let fast = sum(&amounts);
+        let fast_time = start.elapsed();
+        let start = std::time::Instant::now();
+        let slow = amounts.iter().fold(BigNum::from(0), |total, amount| &total + amount);
+        let slow_time = start.elapsed();
+        println!("sum: {:?}, BigNum additions: {:?}", fast_time, slow_time);
+        assert_eq!(fast, slow);
+    }
+}
@@ -0,0 +1,113 @@
+// How fast each channel pays out, from the paid totals of the last two loads, to point out the
+// campaigns that are about to run dry
+use super::{bignum, types::MarketChannel};
+
+use adex_domain::BigNum;
+use serde::Serialize;
+use std::collections::HashMap;
+
+// "Notably faster" than the median
+pub const FAST_FACTOR: f64 = 3.0;
+
+#[derive(Serialize, Default, Clone, Debug)]
+pub struct BurnRates {
+    // Channel id -> paid total, and when that was loaded (ms since the epoch)
+    previous: HashMap<String, BigNum>,
+    previous_at: Option<f64>,
+    // Channel id -> paid per ms since the previous load, for channels that paid anything
+    rates: HashMap<String, f64>,
+    median: Option<f64>,
+}
+
+impl BurnRates {
+    pub fn update(&mut self, channels: &[MarketChannel], now: f64) {
+        let paid = channels
+            .iter()
+            .map(|channel| (channel.id.clone(), channel.status.balances_sum()))
+            .collect::<HashMap<_, _>>();
+        self.rates = match self.previous_at {
+            Some(previous_at) if now > previous_at => paid
+                .iter()
+                .filter_map(|(id, paid)| {
+                    let (delta, _) = bignum::saturating_sub(paid, self.previous.get(id)?);
+                    Some((id.clone(), delta.to_f64()? / (now - previous_at)))
+                })
+                .filter(|(_, rate)| *rate > 0.0)
+                .collect(),
+            _ => HashMap::new(),
+        };
+        self.median = median(self.rates.values().cloned().collect());
+        self.previous = paid;
+        self.previous_at = Some(now);
+    }
+
+    pub fn clear(&mut self) {
+        *self = BurnRates::default();
+    }
+
+    // How many times the median the channel is paying out, if that's at least FAST_FACTOR
+    pub fn fast(&self, id: &str) -> Option<f64> {
+        let times = self.rates.get(id)? / self.median?;
+        if times >= FAST_FACTOR {
+            Some(times)
+        } else {
+            None
+        }
+    }
+}
+
+// Of the channels that paid anything; None without any
+fn median(mut rates: Vec<f64>) -> Option<f64> {
+    rates.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    let mid = rates.len() / 2;
+    match rates.len() {
+        0 => None,
+        len if len % 2 == 0 => Some((rates[mid - 1] + rates[mid]) / 2.0),
+        _ => Some(rates[mid]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{channel_with_balances, LEADER};
+
+    const MINUTE_MS: f64 = 60_000.0;
+
+    fn paid(amounts: &[u64]) -> Vec<MarketChannel> {
+        amounts
+            .iter()
+            .enumerate()
+            .map(|(i, &amount)| channel_with_balances(&i.to_string(), &[(LEADER, amount)]))
+            .collect()
+    }
+
+    #[test]
+    fn the_first_load_has_nothing_to_compare_to() {
+        let mut rates = BurnRates::default();
+        rates.update(&paid(&[10, 10]), MINUTE_MS);
+        assert_eq!(rates.fast("0"), None);
+    }
+
+    #[test]
+    fn points_out_channels_paying_much_faster_than_the_median() {
+        let mut rates = BurnRates::default();
+        rates.update(&paid(&[0, 0, 0, 0]), 0.0);
+        rates.update(&paid(&[10, 10, 12, 100]), MINUTE_MS);
+        // The median of 10, 10, 12 and 100 is 11
+        assert_eq!(rates.fast("0"), None);
+        let times = rates.fast("3").unwrap();
+        assert!((times - 100.0 / 11.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn an_unchanged_load_pays_nothing() {
+        let mut rates = BurnRates::default();
+        rates.update(&paid(&[0, 0, 0]), 0.0);
+        rates.update(&paid(&[10, 10, 100]), MINUTE_MS);
+        assert!(rates.fast("2").is_some());
+        // ChannelsUnchanged updates with the same channels
+        rates.update(&paid(&[10, 10, 100]), 2.0 * MINUTE_MS);
+        assert_eq!(rates.fast("2"), None);
+    }
+}
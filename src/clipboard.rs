@@ -0,0 +1,31 @@
+// Copies through a temporary textarea and execCommand, which works without clipboard permissions
+use wasm_bindgen::JsCast;
+
+pub fn copy_text(text: &str) -> bool {
+    let document = seed::document();
+    let textarea = match document
+        .create_element("textarea")
+        .ok()
+        .and_then(|el| el.dyn_into::<web_sys::HtmlTextAreaElement>().ok())
+    {
+        Some(textarea) => textarea,
+        None => return false,
+    };
+    let body = match document.body() {
+        Some(body) => body,
+        None => return false,
+    };
+    textarea.set_value(text);
+    // Keep it out of sight while it's in the document
+    textarea
+        .set_attribute("style", "position: fixed; top: 0; left: 0; opacity: 0;")
+        .ok();
+    body.append_child(&textarea).ok();
+    textarea.select();
+    let copied = document
+        .dyn_ref::<web_sys::HtmlDocument>()
+        .and_then(|html_document| html_document.exec_command("copy").ok())
+        .unwrap_or(false);
+    body.remove_child(&textarea).ok();
+    copied
+}
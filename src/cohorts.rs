@@ -0,0 +1,164 @@
+// Campaigns bucketed by the month they were created in, for reporting
+use super::{header, paid_ratio, plain_amount_cell, types, Model, Msg};
+
+use adex_domain::BigNum;
+use chrono::Datelike;
+use num_format::{Locale, ToFormattedString};
+use seed::prelude::*;
+use std::collections::BTreeMap;
+use types::MarketChannel;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct YearMonth {
+    pub year: i32,
+    pub month: u32,
+}
+
+#[derive(Clone, Debug)]
+pub struct Aggregates {
+    pub campaigns: usize,
+    pub deposit: BigNum,
+    pub paid: BigNum,
+    // Sum of the paid share of every campaign with a deposit, and how many there are
+    paid_ratio_sum: f64,
+    paid_ratio_count: usize,
+}
+
+impl Aggregates {
+    fn empty() -> Self {
+        Aggregates {
+            campaigns: 0,
+            deposit: BigNum::from(0),
+            paid: BigNum::from(0),
+            paid_ratio_sum: 0.0,
+            paid_ratio_count: 0,
+        }
+    }
+
+    // The average of the campaigns' paid shares, in percent; not the month's total paid share
+    pub fn average_paid_percent(&self) -> Option<f64> {
+        if self.paid_ratio_count == 0 {
+            None
+        } else {
+            Some(self.paid_ratio_sum / self.paid_ratio_count as f64 * 100.0)
+        }
+    }
+}
+
+// Newest month first; months without campaigns aren't there at all
+pub fn by_month(channels: &[&MarketChannel]) -> Vec<(YearMonth, Aggregates)> {
+    let mut months = BTreeMap::<YearMonth, Aggregates>::new();
+    for channel in channels {
+        let created = &channel.spec.created;
+        let month = YearMonth {
+            year: created.year(),
+            month: created.month(),
+        };
+        let paid = channel.status.balances_sum();
+        let aggregates = months.entry(month).or_insert_with(Aggregates::empty);
+        aggregates.campaigns += 1;
+        aggregates.deposit = &aggregates.deposit + &channel.deposit_amount;
+        if let Some(ratio) = paid_ratio(&paid, &channel.deposit_amount) {
+            aggregates.paid_ratio_sum += ratio;
+            aggregates.paid_ratio_count += 1;
+        }
+        aggregates.paid = &aggregates.paid + &paid;
+    }
+    months.into_iter().rev().collect()
+}
+
+pub fn cohorts_view(model: &Model, channels: &[&MarketChannel]) -> Node<Msg> {
+    let header_row = tr![
+        td!["Month"],
+        td!["Campaigns"],
+        td!["Deposit"],
+        td!["Paid"],
+        td!["Average paid - %"]
+    ];
+    div![
+        header(model),
+        h2!["Campaigns by creation month"],
+        table![
+            class!["cohorts"],
+            std::iter::once(header_row)
+                .chain(by_month(channels).iter().map(|(month, aggregates)| {
+                    tr![
+                        td![format!("{}-{:02}", month.year, month.month)],
+                        td![aggregates.campaigns.to_formatted_string(&Locale::en)],
+                        plain_amount_cell(&aggregates.deposit, &model.settings),
+                        plain_amount_cell(&aggregates.paid, &model.settings),
+                        td![aggregates
+                            .average_paid_percent()
+                            .map_or("N/A".to_owned(), |percent| format!("{:.1}%", percent))],
+                    ]
+                }))
+                .collect::<Vec<Node<Msg>>>()
+        ]
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+    use serde_json::json;
+
+    fn created_at(id: &str, created_ms: i64) -> MarketChannel {
+        let mut json = fixtures::channel_json(id);
+        json["spec"]["created"] = json!(created_ms);
+        fixtures::parse(json)
+    }
+
+    fn month(year: i32, month: u32) -> YearMonth {
+        YearMonth { year, month }
+    }
+
+    #[test]
+    fn by_month_splits_at_the_turn_of_the_year_in_utc() {
+        let channels = vec![
+            // 2018-12-31 23:59:59.999 and 2019-01-01 00:00:00
+            created_at("a", 1_546_300_799_999),
+            created_at("b", 1_546_300_800_000),
+            created_at("c", 1_546_300_800_000),
+        ];
+        let months = by_month(&channels.iter().collect::<Vec<_>>());
+        let counts = months
+            .iter()
+            .map(|(month, aggregates)| (*month, aggregates.campaigns))
+            .collect::<Vec<_>>();
+        assert_eq!(counts, vec![(month(2019, 1), 2), (month(2018, 12), 1)]);
+    }
+
+    #[test]
+    fn by_month_puts_newest_first_across_years() {
+        let channels = vec![
+            // 2019-12-15, 2020-01-31 and 2018-12-31
+            created_at("a", 1_576_411_200_000),
+            created_at("b", 1_580_511_600_000),
+            created_at("c", 1_546_300_799_999),
+        ];
+        let months = by_month(&channels.iter().collect::<Vec<_>>())
+            .into_iter()
+            .map(|(month, _)| month)
+            .collect::<Vec<_>>();
+        // Months without campaigns in between aren't filled in
+        assert_eq!(months, vec![month(2020, 1), month(2019, 12), month(2018, 12)]);
+    }
+
+    #[test]
+    fn by_month_adds_up_deposits_and_paid_shares() {
+        let mut a = fixtures::channel("a");
+        a.status
+            .balances
+            .insert(fixtures::LEADER.to_owned(), fixtures::big("500000000000000000000"));
+        let b = fixtures::channel("b");
+        let months = by_month(&[&a, &b]);
+        assert_eq!(months.len(), 1);
+        let (_, aggregates) = &months[0];
+        assert_eq!(aggregates.campaigns, 2);
+        assert_eq!(aggregates.deposit, fixtures::big("2000000000000000000000"));
+        assert_eq!(aggregates.paid, fixtures::big("500000000000000000000"));
+        // Half of one campaign, none of the other
+        assert_eq!(aggregates.average_paid_percent(), Some(25.0));
+    }
+}
@@ -0,0 +1,161 @@
+use serde::{Deserialize, Serialize};
+
+// Campaigns are fetched from the market in pages of this size
+pub const CAMPAIGNS_PAGE_SIZE: usize = 200;
+// How many times a single failed page is retried before giving up on the whole load
+pub const CAMPAIGNS_PAGE_RETRIES: u32 = 3;
+// Wait before the first retry of a page, doubled for each one after it
+pub const CAMPAIGNS_PAGE_RETRY_MS: f64 = 2000.0;
+// A page that takes longer than this is aborted and counts as a failure
+pub const CAMPAIGNS_PAGE_TIMEOUT_MS: u32 = 15000;
+// With balances trimming on, how many of each channel's biggest earners are kept
+pub const TRIMMED_BALANCES_KEPT: usize = 10;
+
+// Mainnet
+const MAINNET_MARKET_URL: &str = "https://market.adex.network";
+const MAINNET_ETHERSCAN_URL: &str = "https://api.etherscan.io/api";
+const MAINNET_EXPLORER_URL: &str = "https://etherscan.io";
+const MAINNET_DAI_ADDR: &str = "0x6B175474E89094C44Da98b954EedeAC495271d0F";
+const MAINNET_CORE_ADDR: &str = "0x333420fc6a897356e69b62417cd17ff012177d2b";
+
+// Testnet (Goerli)
+const TESTNET_MARKET_URL: &str = "https://goerli-market.adex.network";
+const TESTNET_ETHERSCAN_URL: &str = "https://api-goerli.etherscan.io/api";
+const TESTNET_EXPLORER_URL: &str = "https://goerli.etherscan.io";
+const TESTNET_DAI_ADDR: &str = "0x73967c6a0904aA032C103b4104747E88c566B1A2";
+// There's no known AdExCore deployment on Goerli, so nothing is locked up on-chain there
+const TESTNET_CORE_ADDR: Option<&str> = None;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum Environment {
+    Mainnet,
+    Testnet,
+    // A custom market URL; everything else is the same as mainnet
+    Custom(String),
+}
+
+impl Default for Environment {
+    fn default() -> Self {
+        Environment::Mainnet
+    }
+}
+
+// Which campaigns are asked for: what the market considers active, or every campaign ever
+// listed (`?all`), which is a much bigger download
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum DataScope {
+    Active,
+    All,
+}
+
+impl Default for DataScope {
+    fn default() -> Self {
+        DataScope::All
+    }
+}
+
+impl DataScope {
+    // Prefix of the campaigns query string, before limit and skip
+    pub fn query(self) -> &'static str {
+        match self {
+            DataScope::Active => "",
+            DataScope::All => "all&",
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            DataScope::Active => "active",
+            DataScope::All => "all",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            DataScope::Active => "Active only",
+            DataScope::All => "All history",
+        }
+    }
+}
+
+pub struct EnvConfig {
+    pub market_url: String,
+    pub etherscan_url: &'static str,
+    pub explorer_url: &'static str,
+    pub dai_addr: &'static str,
+    // The AdExCore contract holding the deposits, if the network has one
+    pub core_addr: Option<&'static str>,
+}
+
+impl Environment {
+    pub fn config(&self) -> EnvConfig {
+        match self {
+            Environment::Mainnet => EnvConfig {
+                market_url: MAINNET_MARKET_URL.to_owned(),
+                etherscan_url: MAINNET_ETHERSCAN_URL,
+                explorer_url: MAINNET_EXPLORER_URL,
+                dai_addr: MAINNET_DAI_ADDR,
+                core_addr: Some(MAINNET_CORE_ADDR),
+            },
+            Environment::Testnet => EnvConfig {
+                market_url: TESTNET_MARKET_URL.to_owned(),
+                etherscan_url: TESTNET_ETHERSCAN_URL,
+                explorer_url: TESTNET_EXPLORER_URL,
+                dai_addr: TESTNET_DAI_ADDR,
+                core_addr: TESTNET_CORE_ADDR,
+            },
+            Environment::Custom(market_url) => EnvConfig {
+                market_url: market_url.trim_end_matches('/').to_owned(),
+                ..Environment::Mainnet.config()
+            },
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Environment::Mainnet => "mainnet",
+            Environment::Testnet => "testnet",
+            Environment::Custom(_) => "custom",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_address(addr: &str) -> bool {
+        addr.len() == 42 && addr.starts_with("0x") && addr[2..].chars().all(|c| c.is_ascii_hexdigit())
+    }
+
+    #[test]
+    fn every_environment_defines_all_fields() {
+        let envs = vec![
+            Environment::Mainnet,
+            Environment::Testnet,
+            Environment::Custom("https://market.example.com/".to_owned()),
+        ];
+        for env in envs {
+            let config = env.config();
+            assert!(config.market_url.starts_with("https://"), "{}", env.name());
+            assert!(!config.market_url.ends_with('/'), "{}", env.name());
+            assert!(config.etherscan_url.starts_with("https://"), "{}", env.name());
+            assert!(config.explorer_url.starts_with("https://"), "{}", env.name());
+            assert!(is_address(config.dai_addr), "{}", env.name());
+            if let Some(core_addr) = config.core_addr {
+                assert!(is_address(core_addr), "{}", env.name());
+            }
+        }
+    }
+
+    #[test]
+    fn testnet_shares_nothing_with_mainnet() {
+        let mainnet = Environment::Mainnet.config();
+        let testnet = Environment::Testnet.config();
+        assert_ne!(mainnet.market_url, testnet.market_url);
+        assert_ne!(mainnet.etherscan_url, testnet.etherscan_url);
+        assert_ne!(mainnet.explorer_url, testnet.explorer_url);
+        assert_ne!(mainnet.dai_addr, testnet.dai_addr);
+        assert_ne!(mainnet.core_addr, testnet.core_addr);
+    }
+}
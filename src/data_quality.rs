@@ -0,0 +1,244 @@
+use super::types::MarketChannel;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+
+#[derive(Serialize, Clone, Debug)]
+pub struct OwnerMismatch {
+    pub creator: String,
+    pub owners: Vec<String>,
+}
+
+// Channels sharing the same creator+nonce, or with byte-identical specs
+// These are usually mistakes or republished campaigns, and they distort totals
+// Returns every affected channel id mapped to the ids it duplicates
+// Channels without a nonce can't reuse one, so they're only compared by spec
+pub fn find_duplicates(channels: &[MarketChannel]) -> HashMap<String, Vec<String>> {
+    let mut buckets = HashMap::<String, Vec<&str>>::new();
+    for channel in channels {
+        match serde_json::to_value(&channel.spec.nonce) {
+            Ok(Value::Null) | Err(_) => {}
+            Ok(nonce) => buckets
+                .entry(format!("nonce:{}:{}", channel.creator.to_lowercase(), nonce))
+                .or_insert_with(Vec::new)
+                .push(&channel.id),
+        }
+        if let Ok(spec) = serde_json::to_string(&channel.spec) {
+            buckets
+                .entry(format!("spec:{}", spec))
+                .or_insert_with(Vec::new)
+                .push(&channel.id);
+        }
+    }
+
+    let mut duplicates = HashMap::<String, Vec<String>>::new();
+    for ids in buckets.values().filter(|ids| ids.len() > 1) {
+        for id in ids {
+            let others = duplicates.entry(id.to_string()).or_insert_with(Vec::new);
+            for other in ids.iter().filter(|other| *other != id) {
+                if !others.iter().any(|known| known == *other) {
+                    others.push(other.to_string());
+                }
+            }
+        }
+    }
+    duplicates.retain(|_, others| !others.is_empty());
+    duplicates
+}
+
+// Owners of the channel's ad units other than its creator, without repeats
+// A creator running someone else's creatives is usually a copy-paste or a hijacked campaign
+// Units without an owner aren't counted: there's nothing to compare
+pub fn foreign_owners(channel: &MarketChannel) -> Vec<String> {
+    let mut owners = Vec::<String>::new();
+    for unit in &channel.spec.ad_units {
+        let owner = unit.owner.trim();
+        if !owner.is_empty()
+            && !owner.eq_ignore_ascii_case(&channel.creator)
+            && !owners.iter().any(|known| known.eq_ignore_ascii_case(owner))
+        {
+            owners.push(owner.to_owned());
+        }
+    }
+    owners
+}
+
+// Channel id -> its creator and the foreign owners, for the channels that have any
+pub fn find_owner_mismatches(channels: &[MarketChannel]) -> HashMap<String, OwnerMismatch> {
+    channels
+        .iter()
+        .filter_map(|channel| {
+            let owners = foreign_owners(channel);
+            if owners.is_empty() {
+                None
+            } else {
+                Some((
+                    channel.id.clone(),
+                    OwnerMismatch {
+                        creator: channel.creator.clone(),
+                        owners,
+                    },
+                ))
+            }
+        })
+        .collect()
+}
+
+// Drops entries whose id shows up again later in the list, so the last occurrence wins
+// Returns the remaining channels, in order, and how many were dropped
+pub fn dedupe_by_id(channels: Vec<MarketChannel>) -> (Vec<MarketChannel>, usize) {
+    let total = channels.len();
+    let mut seen = HashSet::new();
+    let mut deduped = channels
+        .into_iter()
+        .rev()
+        .filter(|channel| seen.insert(channel.id.clone()))
+        .collect::<Vec<_>>();
+    deduped.reverse();
+    let dropped = total - deduped.len();
+    (deduped, dropped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+    use serde_json::json;
+
+    fn sorted(mut ids: Vec<String>) -> Vec<String> {
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn distinct_channels_are_not_duplicates() {
+        let channels = vec![fixtures::channel("a"), fixtures::channel("b"), fixtures::channel("c")];
+        assert!(find_duplicates(&channels).is_empty());
+    }
+
+    #[test]
+    fn reused_nonce_of_the_same_creator() {
+        let mut a = fixtures::channel_json("a");
+        a["spec"]["nonce"] = json!("42");
+        let mut b = fixtures::channel_json("b");
+        b["spec"]["nonce"] = json!("42");
+        b["spec"]["title"] = json!("Another campaign");
+        b["creator"] = json!(fixtures::CREATOR.to_lowercase());
+        // Same nonce, but someone else's
+        let mut c = fixtures::channel_json("c");
+        c["spec"]["nonce"] = json!("42");
+        c["spec"]["title"] = json!("Unrelated");
+        c["creator"] = json!(fixtures::LEADER);
+
+        let channels = vec![fixtures::parse(a), fixtures::parse(b), fixtures::parse(c)];
+        let duplicates = find_duplicates(&channels);
+        assert_eq!(duplicates.len(), 2);
+        assert_eq!(duplicates["a"], vec!["b"]);
+        assert_eq!(duplicates["b"], vec!["a"]);
+    }
+
+    #[test]
+    fn identical_specs_of_different_creators() {
+        let a = fixtures::channel_json("a");
+        let mut b = fixtures::channel_json("b");
+        b["spec"] = a["spec"].clone();
+        b["creator"] = json!(fixtures::LEADER);
+
+        let channels = vec![fixtures::parse(a), fixtures::parse(b)];
+        let duplicates = find_duplicates(&channels);
+        assert_eq!(duplicates["a"], vec!["b"]);
+        assert_eq!(duplicates["b"], vec!["a"]);
+    }
+
+    #[test]
+    fn missing_nonces_are_not_a_reused_nonce() {
+        let mut a = fixtures::channel_json("a");
+        a["spec"]["nonce"] = json!(null);
+        let mut b = fixtures::channel_json("b");
+        b["spec"]["nonce"] = json!(null);
+        b["spec"]["title"] = json!("Another campaign");
+
+        let channels = vec![fixtures::parse(a), fixtures::parse(b)];
+        assert!(find_duplicates(&channels).is_empty());
+    }
+
+    fn ids(channels: &[MarketChannel]) -> Vec<&str> {
+        channels.iter().map(|c| c.id.as_str()).collect()
+    }
+
+    #[test]
+    fn dedupe_by_id_keeps_the_last_occurrence() {
+        let mut first = fixtures::channel_json("a");
+        first["spec"]["title"] = json!("First");
+        let mut last = fixtures::channel_json("a");
+        last["spec"]["title"] = json!("Last");
+        let channels = vec![
+            fixtures::parse(first),
+            fixtures::channel("b"),
+            fixtures::parse(last),
+            fixtures::channel("c"),
+        ];
+        let (deduped, dropped) = dedupe_by_id(channels);
+        assert_eq!(dropped, 1);
+        assert_eq!(ids(&deduped), vec!["b", "a", "c"]);
+        assert_eq!(deduped[1].spec.title.as_ref().map(String::as_str), Some("Last"));
+    }
+
+    #[test]
+    fn dedupe_by_id_without_duplicates() {
+        let channels = vec![fixtures::channel("a"), fixtures::channel("b")];
+        let (deduped, dropped) = dedupe_by_id(channels);
+        assert_eq!(dropped, 0);
+        assert_eq!(ids(&deduped), vec!["a", "b"]);
+        assert_eq!(dedupe_by_id(Vec::new()).1, 0);
+    }
+
+    #[test]
+    fn every_copy_lists_all_the_others() {
+        let a = fixtures::channel_json("a");
+        let mut b = fixtures::channel_json("b");
+        b["spec"] = a["spec"].clone();
+        let mut c = fixtures::channel_json("c");
+        c["spec"] = a["spec"].clone();
+
+        let channels = vec![fixtures::parse(a), fixtures::parse(b), fixtures::parse(c)];
+        let duplicates = find_duplicates(&channels);
+        assert_eq!(sorted(duplicates["a"].clone()), vec!["b", "c"]);
+        assert_eq!(sorted(duplicates["b"].clone()), vec!["a", "c"]);
+        assert_eq!(sorted(duplicates["c"].clone()), vec!["a", "b"]);
+    }
+
+    fn with_owners(id: &str, owners: &[&str]) -> MarketChannel {
+        let mut json = fixtures::channel_json(id);
+        json["spec"]["adUnits"] = owners
+            .iter()
+            .map(|owner| {
+                let mut unit = fixtures::ad_unit("QmcUVX7fvoLMM93uN2bD3wGTH8MXSxeL8hojYfL2Lhp7mR");
+                unit["owner"] = json!(owner);
+                unit
+            })
+            .collect();
+        fixtures::parse(json)
+    }
+
+    #[test]
+    fn owner_mismatches() {
+        let creator_lowercase = fixtures::CREATOR.to_lowercase();
+        let channels = vec![
+            // The creator's own units, in any case
+            with_owners("match", &[fixtures::CREATOR, &creator_lowercase]),
+            // Someone else's, listed once however many units they own
+            with_owners(
+                "mismatch",
+                &[fixtures::CREATOR, fixtures::LEADER, &fixtures::LEADER.to_lowercase()],
+            ),
+            // Nothing to compare
+            with_owners("missing", &["", "  "]),
+        ];
+        let mismatches = find_owner_mismatches(&channels);
+        assert_eq!(mismatches.len(), 1);
+        let mismatch = &mismatches["mismatch"];
+        assert_eq!(mismatch.creator, fixtures::CREATOR);
+        assert_eq!(mismatch.owners, vec![fixtures::LEADER.to_owned()]);
+    }
+}
@@ -0,0 +1,194 @@
+// A hidden panel for troubleshooting what a user actually loaded
+// Toggled with Shift+D, or opened right away with #debug in the URL
+// Separately, ?debug in the URL logs every message to the console
+use super::{export, types, Model, Msg};
+
+use seed::prelude::*;
+use std::collections::BTreeMap;
+use types::Loadable::*;
+use types::PageResponse;
+
+pub const HASH: &str = "#debug";
+const QUERY_PARAM: &str = "debug";
+const SNAPSHOT_FILENAME: &str = "adex-explorer-snapshot.json";
+
+pub fn debug_panel(model: &Model) -> Node<Msg> {
+    let by_status = match &model.market_channels {
+        Ready(channels) => channels.iter().fold(BTreeMap::new(), |mut counts, channel| {
+            *counts
+                .entry(format!("{:?}", channel.status.status_type))
+                .or_insert(0) += 1;
+            counts
+        }),
+        Loading => BTreeMap::new(),
+    };
+    let last_fetch = if model.last_fetch_started > 0.0 {
+        String::from(js_sys::Date::new(&model.last_fetch_started.into()).to_iso_string())
+    } else {
+        "never".to_owned()
+    };
+    let paging = match &model.campaigns_paging {
+        Some(loaded) => format!("running, {} campaigns so far", loaded.len()),
+        None => "idle".to_owned(),
+    };
+
+    div![
+        class!["debug-panel"],
+        h3!["Debug"],
+        table![
+            debug_row("Last fetch started", last_fetch),
+            debug_row(
+                "Last fetch duration",
+                model
+                    .last_fetch_duration_ms
+                    .map_or("—".to_owned(), |ms| format!("{:.0} ms", ms))
+            ),
+            debug_row("Response size", format!("{} bytes", model.last_response_bytes)),
+            debug_row(
+                "Channels by status",
+                by_status
+                    .iter()
+                    .map(|(status, count)| format!("{}: {}", status, count))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            debug_row("Parse failures", model.parse_failures.to_string()),
+            debug_row("Paging", paging),
+            debug_row("Page retries", model.page_retries.to_string()),
+            debug_row("Custom labels", model.labels.user_count().to_string()),
+            debug_row("Load error", model.load_error.clone().unwrap_or_default()),
+        ],
+        button![
+            "Download model snapshot",
+            simple_ev(Ev::Click, Msg::DownloadSnapshot)
+        ]
+    ]
+}
+
+fn debug_row(label: &str, value: String) -> Node<Msg> {
+    tr![td![label], td![value]]
+}
+
+pub fn download_snapshot(model: &Model) {
+    export::download_json(SNAPSHOT_FILENAME, model)
+}
+
+// Whether ?debug is in the query string
+pub fn logging_requested() -> bool {
+    seed::window().location().search().map_or(false, |search| {
+        search
+            .trim_start_matches('?')
+            .split('&')
+            .any(|param| param.split('=').next() == Some(QUERY_PARAM))
+    })
+}
+
+pub fn log_msg(msg: &Msg) {
+    // This fires constantly, and would drown everything else
+    if let Msg::Tick(_) = msg {
+        return;
+    }
+    web_sys::console::log_1(&format!("[update] {}", describe(msg)).into());
+}
+
+// The arm name, plus whatever is useful to know about it; never the full payload
+fn describe(msg: &Msg) -> String {
+    let result = |name: &str, ok: bool| format!("{} {}", name, if ok { "ok" } else { "failed" });
+    match msg {
+        Msg::Load(action) => format!("Load {:?}", action),
+        Msg::Tick(now) => format!("Tick {}", now),
+        Msg::ConnectivityChanged(online) => format!("ConnectivityChanged online={}", online),
+        Msg::ManualRefresh => "ManualRefresh".to_owned(),
+        Msg::Fetched(generation, msg) => format!("Fetched gen={} {}", generation, describe(msg)),
+        Msg::BalanceLoaded(resp) => result("BalanceLoaded", resp.is_ok()),
+        Msg::ChannelsLoaded(channels) => format!("ChannelsLoaded {} channels", channels.len()),
+        Msg::ChannelsUnchanged => "ChannelsUnchanged".to_owned(),
+        Msg::ChannelsFailed(reason) => format!("ChannelsFailed: {}", reason),
+        Msg::CampaignsPageLoaded(skip, page) => match page {
+            PageResponse::NotModified => format!("CampaignsPageLoaded skip={} not modified", skip),
+            PageResponse::Body { body, .. } => {
+                format!("CampaignsPageLoaded skip={} {} bytes", skip, body.len())
+            }
+            PageResponse::Failed(reason) => {
+                format!("CampaignsPageLoaded skip={} failed: {}", skip, reason)
+            }
+        },
+        Msg::VolumeLoaded(resp) => result("VolumeLoaded", resp.is_ok()),
+        Msg::ImpressionsLoaded(resp) => result("ImpressionsLoaded", resp.is_ok()),
+        Msg::DailyImpressionsLoaded(resp) => result("DailyImpressionsLoaded", resp.is_ok()),
+        Msg::YearlyImpressionsLoaded(resp) => result("YearlyImpressionsLoaded", resp.is_ok()),
+        Msg::FullChannelLoaded(Ok(channel)) => format!("FullChannelLoaded {}", channel.id),
+        Msg::FullChannelLoaded(Err(_)) => result("FullChannelLoaded", false),
+        Msg::ExportPageLoaded(skip, Ok(page)) => {
+            format!("ExportPageLoaded skip={} n={}", skip, page.len())
+        }
+        Msg::ExportPageLoaded(skip, Err(_)) => format!("ExportPageLoaded skip={} failed", skip),
+        Msg::SlotsLoaded(Ok(slots)) => format!("SlotsLoaded {} slots", slots.len()),
+        Msg::SlotsLoaded(Err(_)) => result("SlotsLoaded", false),
+        Msg::ValidatorChecked(url, healthy) => format!("ValidatorChecked {} {:?}", url, healthy),
+        Msg::ValidatorReportLoaded(id, resp) => {
+            format!("ValidatorReportLoaded {} {}", id, if resp.is_ok() { "ok" } else { "failed" })
+        }
+        Msg::HeartbeatLoaded(id, role, resp) => {
+            format!("HeartbeatLoaded {} {:?} ok={}", id, role, resp.is_ok())
+        }
+        Msg::LoadActivity(id) => format!("LoadActivity {}", id),
+        Msg::ActivityLoaded(id, resp) => format!("ActivityLoaded {} ok={}", id, resp.is_ok()),
+        Msg::LoadLabels(url) => format!("LoadLabels {}", url),
+        Msg::LabelsLoaded(Ok(labels)) => format!("LabelsLoaded {} labels", labels.len()),
+        Msg::LabelsLoaded(Err(_)) => result("LabelsLoaded", false),
+        Msg::SortSelected(sort) => format!("SortSelected {}", sort),
+        Msg::SearchChanged(search) => format!("SearchChanged {:?}", search),
+        Msg::PublisherFilterChanged(addr) => format!("PublisherFilterChanged {:?}", addr),
+        Msg::CheckedFromChanged(date) => format!("CheckedFromChanged {:?}", date),
+        Msg::CheckedToChanged(date) => format!("CheckedToChanged {:?}", date),
+        Msg::UsdMinChanged(usd) => format!("UsdMinChanged {:?}", usd),
+        Msg::UsdMaxChanged(usd) => format!("UsdMaxChanged {:?}", usd),
+        Msg::TagSelected(tag) => format!("TagSelected {:?}", tag),
+        Msg::TagMinScoreChanged(score) => format!("TagMinScoreChanged {}", score),
+        Msg::ShowExpiringSoon => "ShowExpiringSoon".to_owned(),
+        Msg::CreatorFilterChanged(addr) => format!("CreatorFilterChanged {:?}", addr),
+        Msg::ApplySearch(generation) => format!("ApplySearch gen={}", generation),
+        Msg::StatusFilterSelected(status) => format!("StatusFilterSelected {:?}", status),
+        Msg::ToggleStatusFilter(status) => format!("ToggleStatusFilter {:?}", status),
+        Msg::ClearFilters => "ClearFilters".to_owned(),
+        Msg::ResetView => "ResetView".to_owned(),
+        Msg::ResetSettings => "ResetSettings".to_owned(),
+        Msg::ToggleSettings => "ToggleSettings".to_owned(),
+        Msg::ToggleDebug => "ToggleDebug".to_owned(),
+        Msg::DownloadSnapshot => "DownloadSnapshot".to_owned(),
+        Msg::AmountUnitSelected(unit) => format!("AmountUnitSelected {}", unit),
+        Msg::DaiPrecisionSelected(precision) => format!("DaiPrecisionSelected {}", precision),
+        Msg::ToggleShowEnded => "ToggleShowEnded".to_owned(),
+        Msg::ToggleLocalTime => "ToggleLocalTime".to_owned(),
+        Msg::TogglePaidBars => "TogglePaidBars".to_owned(),
+        Msg::ToggleRateLimits => "ToggleRateLimits".to_owned(),
+        Msg::ToggleCompactDai => "ToggleCompactDai".to_owned(),
+        Msg::ToggleGroupDuplicates => "ToggleGroupDuplicates".to_owned(),
+        Msg::ToggleExpandSearchMatches => "ToggleExpandSearchMatches".to_owned(),
+        Msg::ToggleCheckValidators => "ToggleCheckValidators".to_owned(),
+        Msg::ToggleTrimBalances => "ToggleTrimBalances".to_owned(),
+        Msg::ToggleGroup(fingerprint) => format!("ToggleGroup {}", fingerprint),
+        Msg::ToggleHideUnpaid => "ToggleHideUnpaid".to_owned(),
+        Msg::TogglePresentation => "TogglePresentation".to_owned(),
+        Msg::ToggleFreezeOrder => "ToggleFreezeOrder".to_owned(),
+        Msg::CopyMarkdown => "CopyMarkdown".to_owned(),
+        Msg::CopyShareLink => "CopyShareLink".to_owned(),
+        Msg::ExportAllCsv => "ExportAllCsv".to_owned(),
+        Msg::ExportAllJson => "ExportAllJson".to_owned(),
+        Msg::OpenRawJson(id) => format!("OpenRawJson {}", id),
+        Msg::CloseRawJson => "CloseRawJson".to_owned(),
+        Msg::CopyRawJson => "CopyRawJson".to_owned(),
+        Msg::ToggleCompare(id) => format!("ToggleCompare {}", id),
+        Msg::OpenCompare => "OpenCompare".to_owned(),
+        Msg::CloseCompare => "CloseCompare".to_owned(),
+        Msg::ToggleStatusLegend => "ToggleStatusLegend".to_owned(),
+        Msg::EnvironmentSelected(env) => format!("EnvironmentSelected {}", env),
+        Msg::CustomMarketUrlChanged(url) => format!("CustomMarketUrlChanged {}", url),
+        Msg::DataScopeSelected(scope) => format!("DataScopeSelected {}", scope),
+        Msg::IpfsGatewaySelected(name) => format!("IpfsGatewaySelected {}", name),
+        Msg::CustomIpfsGatewayChanged(url) => format!("CustomIpfsGatewayChanged {}", url),
+        Msg::LoadPreviews(srcs) => format!("LoadPreviews n={}", srcs.len()),
+        Msg::PreviewFinished(src, ok) => format!("PreviewFinished {} ok={}", src, ok),
+    }
+}
@@ -0,0 +1,161 @@
+// Human readable durations, shared by everything that shows "time ago" or "time left"
+use chrono::Duration;
+
+const MINUTE: i64 = 60;
+const HOUR: i64 = 60 * MINUTE;
+const DAY: i64 = 24 * HOUR;
+const YEAR: i64 = 365 * DAY;
+
+const UNITS: [(i64, &str, &str); 5] = [
+    (YEAR, "year", "y"),
+    (DAY, "day", "d"),
+    (HOUR, "hour", "h"),
+    (MINUTE, "minute", "m"),
+    (1, "second", "s"),
+];
+
+// "3 hours ago"; a negative duration is in the future
+pub fn humanize_past(d: Duration) -> String {
+    if d < Duration::zero() {
+        return humanize_future(-d);
+    }
+    match largest_unit(d.num_seconds()) {
+        Some(amount) => format!("{} ago", amount),
+        None => "just now".to_owned(),
+    }
+}
+
+// "in 3 hours"; a negative duration is in the past
+pub fn humanize_future(d: Duration) -> String {
+    if d < Duration::zero() {
+        return humanize_past(-d);
+    }
+    match largest_unit(d.num_seconds()) {
+        Some(amount) => format!("in {}", amount),
+        None => "now".to_owned(),
+    }
+}
+
+// The two largest non-zero units, e.g. "3d 4h" or "42s"
+pub fn compact(d: Duration) -> String {
+    let seconds = d.num_seconds();
+    let sign = if seconds < 0 { "-" } else { "" };
+    let mut rest = seconds.abs();
+    let parts = UNITS
+        .iter()
+        .filter_map(|&(size, _, short)| {
+            let amount = rest / size;
+            rest %= size;
+            if amount > 0 {
+                Some(format!("{}{}", amount, short))
+            } else {
+                None
+            }
+        })
+        .take(2)
+        .collect::<Vec<_>>();
+    if parts.is_empty() {
+        "0s".to_owned()
+    } else {
+        format!("{}{}", sign, parts.join(" "))
+    }
+}
+
+// Whole amount of the largest unit that fits, e.g. "1 minute" or "5 days"
+// None for anything under a second
+fn largest_unit(seconds: i64) -> Option<String> {
+    UNITS
+        .iter()
+        .find(|&&(size, _, _)| seconds >= size)
+        .map(|&(size, name, _)| {
+            let amount = seconds / size;
+            format!("{} {}{}", amount, name, if amount == 1 { "" } else { "s" })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn past(seconds: i64) -> String {
+        humanize_past(Duration::seconds(seconds))
+    }
+
+    fn future(seconds: i64) -> String {
+        humanize_future(Duration::seconds(seconds))
+    }
+
+    fn compact_s(seconds: i64) -> String {
+        compact(Duration::seconds(seconds))
+    }
+
+    #[test]
+    fn each_unit_starts_at_its_boundary() {
+        let cases = [
+            (0, "just now"),
+            (1, "1 second ago"),
+            (59, "59 seconds ago"),
+            (MINUTE, "1 minute ago"),
+            (2 * MINUTE - 1, "1 minute ago"),
+            (2 * MINUTE, "2 minutes ago"),
+            (HOUR - 1, "59 minutes ago"),
+            (HOUR, "1 hour ago"),
+            (DAY - 1, "23 hours ago"),
+            (DAY, "1 day ago"),
+            (YEAR - 1, "364 days ago"),
+            (YEAR, "1 year ago"),
+            (2 * YEAR - 1, "1 year ago"),
+            (2 * YEAR, "2 years ago"),
+        ];
+        for &(seconds, expected) in cases.iter() {
+            assert_eq!(past(seconds), expected, "{}s", seconds);
+        }
+    }
+
+    #[test]
+    fn future_mirrors_past() {
+        assert_eq!(future(0), "now");
+        assert_eq!(future(1), "in 1 second");
+        assert_eq!(future(HOUR - 1), "in 59 minutes");
+        assert_eq!(future(HOUR), "in 1 hour");
+        assert_eq!(future(3 * DAY), "in 3 days");
+        assert_eq!(past(-HOUR), "in 1 hour");
+        assert_eq!(future(-HOUR), "1 hour ago");
+    }
+
+    #[test]
+    fn under_a_second_rounds_down() {
+        assert_eq!(humanize_past(Duration::milliseconds(999)), "just now");
+        assert_eq!(humanize_future(Duration::milliseconds(999)), "now");
+        assert_eq!(humanize_past(Duration::milliseconds(1999)), "1 second ago");
+    }
+
+    #[test]
+    fn compact_keeps_the_two_largest_units() {
+        let cases = [
+            (0, "0s"),
+            (1, "1s"),
+            (59, "59s"),
+            (MINUTE, "1m"),
+            (MINUTE + 1, "1m 1s"),
+            (HOUR - 1, "59m 59s"),
+            (HOUR, "1h"),
+            (HOUR + MINUTE + 1, "1h 1m"),
+            (DAY - 1, "23h 59m"),
+            (DAY, "1d"),
+            (DAY + 5, "1d 5s"),
+            (YEAR - 1, "364d 23h"),
+            (YEAR, "1y"),
+            (YEAR + DAY, "1y 1d"),
+        ];
+        for &(seconds, expected) in cases.iter() {
+            assert_eq!(compact_s(seconds), expected, "{}s", seconds);
+        }
+    }
+
+    #[test]
+    fn compact_negative() {
+        assert_eq!(compact_s(-1), "-1s");
+        assert_eq!(compact_s(-(HOUR + MINUTE)), "-1h 1m");
+    }
+}
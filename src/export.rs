@@ -0,0 +1,232 @@
+// Plain-text exports of the channels table, and file downloads
+use super::{
+    amounts::AmountUnit, channel_usd, duration, labels::Labels, paid_percent, types,
+    unit_amount_text,
+};
+
+use adex_domain::BigNum;
+use serde::Serialize;
+use types::MarketChannel;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::{JsCast, JsValue};
+
+pub const ALL_CSV_FILENAME: &str = "adex-channels.csv";
+pub const ALL_JSON_FILENAME: &str = "adex-channels.json";
+
+const COLUMNS: [&str; 10] = [
+    "Id",
+    "Name",
+    "Status",
+    "Deposit",
+    "Price (CPM)",
+    "Paid",
+    "Paid - %",
+    "Earners",
+    "Created",
+    "Expires in",
+];
+
+// One row of cells per channel, in the order of COLUMNS, with amounts in `unit`
+fn channel_rows(
+    channels: &[&MarketChannel],
+    labels: &Labels,
+    unit: AmountUnit,
+    precision: u8,
+    now_seconds: i64,
+) -> Vec<Vec<String>> {
+    channels
+        .iter()
+        .map(|channel| {
+            let paid = channel.status.balances_sum();
+            let cpm = channel.price_per_impression() * &1000.into();
+            let amount = |bal: &BigNum| unit_amount_text(bal, channel_usd(channel, bal), unit, precision);
+            vec![
+                channel.id.clone(),
+                channel.display_name(labels),
+                format!("{:?}", channel.status.status_type),
+                amount(&channel.deposit_amount),
+                amount(&cpm),
+                amount(&paid),
+                paid_percent(&paid, &channel.deposit_amount),
+                channel.status.earner_count().to_string(),
+                channel.spec.created.format("%Y-%m-%d %H:%M UTC").to_string(),
+                channel
+                    .time_until_expiry(now_seconds)
+                    .map_or("—".to_owned(), duration::compact),
+            ]
+        })
+        .collect()
+}
+
+// Every channel, whatever the asset; amounts of non-DAI channels are still shown with 18 decimals
+pub fn channels_csv(
+    channels: &[&MarketChannel],
+    labels: &Labels,
+    unit: AmountUnit,
+    precision: u8,
+    now_seconds: i64,
+    dai_addr: &str,
+) -> String {
+    let mut headers = COLUMNS.to_vec();
+    headers.push("Asset");
+    let rows = channel_rows(channels, labels, unit, precision, now_seconds)
+        .into_iter()
+        .zip(channels)
+        .map(|(mut row, channel)| {
+            row.push(if channel.deposit_asset.eq_ignore_ascii_case(dai_addr) {
+                "DAI".to_owned()
+            } else {
+                channel.deposit_asset.clone()
+            });
+            row
+        })
+        .collect::<Vec<_>>();
+    csv_table(&headers, &rows)
+}
+
+pub fn csv_table(headers: &[&str], rows: &[Vec<String>]) -> String {
+    let line = |cells: Vec<String>| cells.join(",");
+    std::iter::once(line(headers.iter().map(|h| csv_cell(h)).collect()))
+        .chain(rows.iter().map(|row| line(row.iter().map(|cell| csv_cell(cell)).collect())))
+        .collect::<Vec<_>>()
+        .join("\r\n")
+}
+
+// Quoted only when it has to be, with quotes doubled
+fn csv_cell(cell: &str) -> String {
+    if cell.contains(|c: char| c == ',' || c == '"' || c == '\r' || c == '\n') {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_owned()
+    }
+}
+
+pub fn channels_markdown(
+    channels: &[&MarketChannel],
+    labels: &Labels,
+    unit: AmountUnit,
+    precision: u8,
+    now_seconds: i64,
+) -> String {
+    markdown_table(&COLUMNS, &channel_rows(channels, labels, unit, precision, now_seconds))
+}
+
+// A GitHub-flavored Markdown table
+pub fn markdown_table(headers: &[&str], rows: &[Vec<String>]) -> String {
+    let line = |cells: Vec<String>| format!("| {} |", cells.join(" | "));
+    let mut lines = vec![
+        line(headers.iter().map(|h| escape_cell(h)).collect()),
+        line(headers.iter().map(|_| "---".to_owned()).collect()),
+    ];
+    lines.extend(
+        rows.iter()
+            .map(|row| line(row.iter().map(|cell| escape_cell(cell)).collect())),
+    );
+    lines.join("\n")
+}
+
+// Pipes would end the cell early, and a newline the whole row
+fn escape_cell(cell: &str) -> String {
+    cell.replace('|', "\\|").replace(|c| c == '\r' || c == '\n', " ")
+}
+
+pub fn download_json<T: Serialize>(filename: &str, value: &T) {
+    match serde_json::to_string_pretty(value) {
+        Ok(json) => download(filename, "application/json", &json),
+        Err(reason) => log!("Export error:", reason.to_string()),
+    }
+}
+
+// Goes through a Blob rather than a data URL, which browsers cap at a few megabytes; the object
+// URL is released once the click has been handled
+pub fn download(filename: &str, mime: &str, contents: &str) {
+    let parts = js_sys::Array::of1(&JsValue::from_str(contents));
+    let mut options = web_sys::BlobPropertyBag::new();
+    options.type_(&format!("{};charset=utf-8", mime));
+    let href = match web_sys::Blob::new_with_str_sequence_and_options(&parts, &options)
+        .and_then(|blob| web_sys::Url::create_object_url_with_blob(&blob))
+    {
+        Ok(href) => href,
+        Err(reason) => {
+            log!("Export error:", reason);
+            return;
+        }
+    };
+    let link = match seed::document().create_element("a") {
+        Ok(link) => link,
+        Err(_) => return,
+    };
+    link.set_attribute("href", &href).ok();
+    link.set_attribute("download", filename).ok();
+    if let Ok(link) = link.dyn_into::<web_sys::HtmlElement>() {
+        link.click();
+    }
+    let revoke = Closure::once_into_js(move || {
+        web_sys::Url::revoke_object_url(&href).ok();
+    });
+    seed::window()
+        .set_timeout_with_callback_and_timeout_and_arguments_0(revoke.unchecked_ref(), 0)
+        .ok();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn markdown_table_has_a_header_and_a_separator() {
+        let rows = vec![
+            vec!["a".to_owned(), "1".to_owned()],
+            vec!["b".to_owned(), "2".to_owned()],
+        ];
+        assert_eq!(
+            markdown_table(&["Name", "Count"], &rows),
+            "| Name | Count |\n| --- | --- |\n| a | 1 |\n| b | 2 |"
+        );
+        assert_eq!(markdown_table(&["Name"], &[]), "| Name |\n| --- |");
+    }
+
+    #[test]
+    fn markdown_cells_cannot_break_the_table() {
+        assert_eq!(escape_cell("a|b"), "a\\|b");
+        assert_eq!(escape_cell("two\nlines"), "two lines");
+        assert_eq!(escape_cell("crlf\r\nend"), "crlf  end");
+        assert_eq!(escape_cell("plain"), "plain");
+        let rows = vec![vec!["Buy | Sell\nnow".to_owned()]];
+        assert_eq!(markdown_table(&["Title"], &rows), "| Title |\n| --- |\n| Buy \\| Sell now |");
+    }
+
+    #[test]
+    fn csv_cells_are_quoted_only_when_needed() {
+        assert_eq!(csv_cell("plain"), "plain");
+        assert_eq!(csv_cell("a,b"), "\"a,b\"");
+        assert_eq!(csv_cell("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_cell("two\nlines"), "\"two\nlines\"");
+    }
+
+    #[test]
+    fn channels_markdown_has_the_csv_columns() {
+        let mut json = fixtures::channel_json("0x01");
+        json["spec"]["title"] = serde_json::json!("Summer | Winter");
+        let channel = fixtures::parse(json);
+        let markdown = channels_markdown(&[&channel], &Labels::default(), AmountUnit::Dai, 2, 0);
+        let lines = markdown.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], format!("| {} |", COLUMNS.join(" | ")));
+        assert!(lines[2].starts_with("| 0x01 | Summer \\| Winter | Active | 1000.00 DAI |"));
+    }
+
+    #[test]
+    fn channel_rows_convert_amounts_to_usd() {
+        // 10 of the 1000 DAI deposit paid out, with the deposit estimated at $1000
+        let channel = fixtures::channel_with_balances("0x01", &[("0x1", 10_000_000_000_000_000_000)]);
+        let rows = channel_rows(&[&channel], &Labels::default(), AmountUnit::Usd, 2, 0);
+        assert_eq!(rows[0][3..6], ["~$1,000.00", "~$1.00", "~$10.00"]);
+
+        let mut unestimated = channel.clone();
+        unestimated.status.usd_estimate = None;
+        let rows = channel_rows(&[&unestimated], &Labels::default(), AmountUnit::Usd, 2, 0);
+        assert_eq!(rows[0][3..6], ["N/A", "N/A", "N/A"]);
+    }
+}
@@ -0,0 +1,494 @@
+use super::tags::TagIndex;
+use super::types::{MarketChannel, MarketStatusType};
+use adex_domain::BigNum;
+use chrono::{DateTime, Duration, NaiveDate, TimeZone, Utc};
+use serde::Serialize;
+use std::collections::HashSet;
+
+// Query parameters of a share link, see `to_query`
+const SEARCH_PARAM: &str = "q";
+const PUBLISHER_PARAM: &str = "publisher";
+const CREATOR_PARAM: &str = "creator";
+const STATUS_PARAM: &str = "status";
+const CHECKED_FROM_PARAM: &str = "checked_from";
+const CHECKED_TO_PARAM: &str = "checked_to";
+const USD_MIN_PARAM: &str = "usd_min";
+const USD_MAX_PARAM: &str = "usd_max";
+const TAG_PARAM: &str = "tag";
+const TAG_SCORE_PARAM: &str = "tag_score";
+const HIDE_UNPAID_PARAM: &str = "hide_unpaid";
+
+// All the user-controlled filters on the channels table
+// Resetting them is just `Filters::default()`
+#[derive(Default, Clone, Debug, Serialize)]
+pub struct Filters {
+    pub search: String,
+    // Only show channels that have paid this address
+    pub publisher: String,
+    // Only show channels opened by this address
+    pub creator: String,
+    // Any of these; empty means every status
+    pub status: HashSet<MarketStatusType>,
+    // Bounds on when the market last checked a channel, as entered (YYYY-MM-DD, UTC); empty is unbounded
+    // Set through `set_checked_from` and `set_checked_to`, which parse them once
+    pub checked_from: String,
+    pub checked_to: String,
+    // What they parsed to, see `checked_range`
+    checked_bounds: CheckedBounds,
+    checked_error: Option<String>,
+    // Only channels with an ad unit targeting this tag with at least this score; looked up in the
+    // tag index, see tags.rs
+    pub tag: String,
+    pub tag_min_score: f64,
+    // Bounds on the USD estimate, as entered; empty or not a number is unbounded
+    // Set through `set_usd_min` and `set_usd_max`, which parse them once
+    pub usd_min: String,
+    pub usd_max: String,
+    // What they parsed to, see `usd_range`
+    usd_bounds: (Option<f32>, Option<f32>),
+    // Only channels whose validity ends within this range, as unix timestamps
+    pub expires_between: Option<(i64, i64)>,
+    // Leave channels that haven't paid anything yet out of the table
+    pub hide_unpaid: bool,
+}
+
+// Start of the "from" day and start of the day after "to"
+type CheckedBounds = (Option<DateTime<Utc>>, Option<DateTime<Utc>>);
+
+impl Filters {
+    pub fn set_checked_from(&mut self, input: String) {
+        self.checked_from = input;
+        self.parse_checked_range();
+    }
+
+    pub fn set_checked_to(&mut self, input: String) {
+        self.checked_to = input;
+        self.parse_checked_range();
+    }
+
+    pub fn set_usd_min(&mut self, input: String) {
+        self.usd_bounds.0 = usd_bound(&input);
+        self.usd_min = input;
+    }
+
+    pub fn set_usd_max(&mut self, input: String) {
+        self.usd_bounds.1 = usd_bound(&input);
+        self.usd_max = input;
+    }
+
+    fn parse_checked_range(&mut self) {
+        match checked_range(&self.checked_from, &self.checked_to) {
+            Ok(bounds) => {
+                self.checked_bounds = bounds;
+                self.checked_error = None;
+            }
+            Err(reason) => {
+                self.checked_bounds = (None, None);
+                self.checked_error = Some(reason);
+            }
+        }
+    }
+
+    pub fn active_count(&self) -> usize {
+        [
+            !self.search.trim().is_empty(),
+            self.publisher_filter().is_some(),
+            self.creator_filter().is_some(),
+            !self.status.is_empty(),
+            self.checked_bounds != (None, None),
+            self.expires_between.is_some(),
+            self.usd_range() != (None, None),
+            !self.tag.is_empty(),
+            self.hide_unpaid,
+        ]
+        .iter()
+        .filter(|active| **active)
+        .count()
+    }
+
+    // `ad_unit_text` is what `ad_unit_text` returned for the channel, and `tag_index` the index
+    // built with it
+    pub fn matches(&self, channel: &MarketChannel, ad_unit_text: &str, tag_index: &TagIndex) -> bool {
+        matches_search(channel, ad_unit_text, &self.search)
+            && self
+                .publisher_filter()
+                .map_or(true, |addr| publisher_balance(channel, &addr).is_some())
+            && self
+                .creator_filter()
+                .map_or(true, |addr| channel.creator.to_lowercase() == addr)
+            && (self.status.is_empty() || self.status.contains(&channel.status.status_type))
+            && {
+                let (from, to) = &self.checked_bounds;
+                let checked = &channel.status.last_checked;
+                from.map_or(true, |from| *checked >= from) && to.map_or(true, |to| *checked < to)
+            }
+            && self.matches_usd(channel)
+            && (self.tag.is_empty() || tag_index.matches(&self.tag, self.tag_min_score, &channel.id))
+            && self.expires_between.map_or(true, |(from, to)| {
+                channel.valid_until.map_or(false, |valid_until| {
+                    let valid_until = valid_until.timestamp();
+                    valid_until > from && valid_until <= to
+                })
+            })
+            && !(self.hide_unpaid && is_unpaid(channel))
+    }
+
+    // The publisher address, lowercased, if one is entered and valid
+    pub fn publisher_filter(&self) -> Option<String> {
+        let addr = self.publisher.trim();
+        if is_valid_address(addr) {
+            Some(addr.to_lowercase())
+        } else {
+            None
+        }
+    }
+
+    pub fn publisher_invalid(&self) -> bool {
+        !self.publisher.trim().is_empty() && self.publisher_filter().is_none()
+    }
+
+    // The creator address, lowercased, if one is entered and valid
+    pub fn creator_filter(&self) -> Option<String> {
+        let addr = self.creator.trim();
+        if is_valid_address(addr) {
+            Some(addr.to_lowercase())
+        } else {
+            None
+        }
+    }
+
+    pub fn creator_invalid(&self) -> bool {
+        !self.creator.trim().is_empty() && self.creator_filter().is_none()
+    }
+
+    // The filters that are set, as query parameters for a share link; read back by `from_query`
+    // Everything but "expiring soon", which is relative to when the data was loaded
+    pub fn to_query(&self) -> Vec<(&'static str, String)> {
+        let mut names = self
+            .status
+            .iter()
+            .filter_map(|status| match serde_json::to_value(status) {
+                Ok(serde_json::Value::String(name)) => Some(name),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        names.sort();
+        let mut pairs = vec![
+            (SEARCH_PARAM, self.search.clone()),
+            (PUBLISHER_PARAM, self.publisher.clone()),
+            (CREATOR_PARAM, self.creator.clone()),
+            (CHECKED_FROM_PARAM, self.checked_from.clone()),
+            (CHECKED_TO_PARAM, self.checked_to.clone()),
+            (USD_MIN_PARAM, self.usd_min.clone()),
+            (USD_MAX_PARAM, self.usd_max.clone()),
+            (TAG_PARAM, self.tag.clone()),
+        ];
+        if !self.tag.is_empty() && self.tag_min_score > 0.0 {
+            pairs.push((TAG_SCORE_PARAM, self.tag_min_score.to_string()));
+        }
+        pairs.retain(|(_, value)| !value.trim().is_empty());
+        if self.hide_unpaid {
+            pairs.push((HIDE_UNPAID_PARAM, "1".to_owned()));
+        }
+        pairs.extend(names.into_iter().map(|name| (STATUS_PARAM, name)));
+        pairs
+    }
+
+    // The filters in the parameters of a share link, as decoded by `query::parse`
+    // Other parameters are left to whoever reads them; values that make no sense come back as
+    // warnings rather than failing the whole link
+    pub fn from_query(pairs: &[(String, String)]) -> (Filters, Vec<String>) {
+        let mut filters = Filters::default();
+        let mut warnings = Vec::new();
+        for (name, value) in pairs {
+            let value = value.clone();
+            match name.as_str() {
+                SEARCH_PARAM => filters.search = value,
+                PUBLISHER_PARAM => filters.publisher = value,
+                CREATOR_PARAM => filters.creator = value,
+                CHECKED_FROM_PARAM => filters.set_checked_from(value),
+                CHECKED_TO_PARAM => filters.set_checked_to(value),
+                USD_MIN_PARAM => filters.set_usd_min(value),
+                USD_MAX_PARAM => filters.set_usd_max(value),
+                TAG_PARAM => filters.tag = value,
+                TAG_SCORE_PARAM => match value.parse::<f64>() {
+                    Ok(score) if score.is_finite() => filters.tag_min_score = score,
+                    _ => warnings.push(format!("Ignoring the tag score {:?}", value)),
+                },
+                HIDE_UNPAID_PARAM => match value.as_str() {
+                    "1" | "true" => filters.hide_unpaid = true,
+                    "0" | "false" => (),
+                    _ => warnings.push(format!("Ignoring the unpaid setting {:?}", value)),
+                },
+                STATUS_PARAM => match parse_status(&value) {
+                    Some(status) => {
+                        filters.status.insert(status);
+                    }
+                    None => warnings.push(format!("Ignoring the unknown status {:?}", value)),
+                },
+                _ => (),
+            }
+        }
+        (filters, warnings)
+    }
+
+    pub fn usd_range(&self) -> (Option<f32>, Option<f32>) {
+        self.usd_bounds
+    }
+
+    // Channels without an estimate are left out as soon as there's a bound
+    fn matches_usd(&self, channel: &MarketChannel) -> bool {
+        match self.usd_range() {
+            (None, None) => true,
+            (min, max) => channel.status.usd_estimate.map_or(false, |usd| {
+                min.map_or(true, |min| usd >= min) && max.map_or(true, |max| usd <= max)
+            }),
+        }
+    }
+
+    // Like an invalid publisher, an invalid range is reported and otherwise ignored
+    pub fn checked_error(&self) -> Option<&str> {
+        self.checked_error.as_ref().map(String::as_str)
+    }
+}
+
+pub fn is_unpaid(channel: &MarketChannel) -> bool {
+    channel.status.balances_sum() == BigNum::from(0)
+}
+
+fn usd_bound(input: &str) -> Option<f32> {
+    input.trim().parse::<f32>().ok().filter(|usd| usd.is_finite())
+}
+
+// Start of the "from" day and start of the day after "to", so both days are included
+fn checked_range(from: &str, to: &str) -> Result<CheckedBounds, String> {
+    let from = parse_date(from).map_err(|e| format!("From: {}", e))?;
+    let to = parse_date(to).map_err(|e| format!("To: {}", e))?;
+    if let (Some(from), Some(to)) = (from, to) {
+        if from > to {
+            return Err("From is after To".to_owned());
+        }
+    }
+    Ok((from, to.map(|to| to + Duration::days(1))))
+}
+
+// Midnight UTC of a YYYY-MM-DD date; None for an empty input
+pub fn parse_date(input: &str) -> Result<Option<DateTime<Utc>>, String> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Ok(None);
+    }
+    NaiveDate::parse_from_str(input, "%Y-%m-%d")
+        .map(|date| Some(Utc.from_utc_datetime(&date.and_hms(0, 0, 0))))
+        .map_err(|_| format!("{:?} is not a date, expected YYYY-MM-DD", input))
+}
+
+// By the same names the market uses, e.g. "Active"
+pub fn parse_status(name: &str) -> Option<MarketStatusType> {
+    serde_json::from_value(serde_json::Value::String(name.trim().to_owned())).ok()
+}
+
+pub fn is_valid_address(addr: &str) -> bool {
+    addr.len() == 42
+        && addr.starts_with("0x")
+        && addr[2..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
+// `addr` must already be lowercased
+pub fn publisher_balance<'a>(channel: &'a MarketChannel, addr: &str) -> Option<&'a BigNum> {
+    channel
+        .status
+        .balances
+        .iter()
+        .find(|(key, _)| key.to_lowercase() == addr)
+        .map(|(_, amount)| amount)
+}
+
+// The titles and descriptions of a channel's ad units, lowercased, for searching
+// Computed once per load, so typing in the search box doesn't walk every ad unit
+pub fn ad_unit_text(channel: &MarketChannel) -> String {
+    channel
+        .spec
+        .ad_units
+        .iter()
+        .flat_map(|unit| vec![unit.title.as_ref(), unit.description.as_ref()])
+        .filter_map(|text| text)
+        .map(|text| text.trim().to_lowercase())
+        .filter(|text| !text.is_empty())
+        .collect::<Vec<_>>()
+        .join(" · ")
+}
+
+// The search term in the ad unit text with some context around it, to show why a channel matched
+pub fn search_snippet(ad_unit_text: &str, search: &str) -> Option<String> {
+    const CONTEXT: usize = 30;
+    let search = search.trim().to_lowercase();
+    if search.is_empty() {
+        return None;
+    }
+    let start = ad_unit_text.find(&search)?;
+    let end = start + search.len();
+    let before = ad_unit_text[..start].chars().rev().take(CONTEXT).collect::<Vec<_>>();
+    let after = ad_unit_text[end..].chars().take(CONTEXT).collect::<String>();
+    Some(format!(
+        "{}{}{}{}{}",
+        if before.len() == CONTEXT { "…" } else { "" },
+        before.into_iter().rev().collect::<String>(),
+        &ad_unit_text[start..end],
+        after,
+        if ad_unit_text[end..].chars().count() > CONTEXT { "…" } else { "" }
+    ))
+}
+
+fn matches_search(channel: &MarketChannel, ad_unit_text: &str, search: &str) -> bool {
+    let search = search.trim().to_lowercase();
+    search.is_empty()
+        || ad_unit_text.contains(&search)
+        || channel.id.to_lowercase().contains(&search)
+        || channel.creator.to_lowercase().contains(&search)
+        || channel
+            .spec
+            .title
+            .as_ref()
+            .map_or(false, |title| title.to_lowercase().contains(&search))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::channel;
+
+    fn date(y: i32, m: u32, d: u32) -> DateTime<Utc> {
+        Utc.ymd(y, m, d).and_hms(0, 0, 0)
+    }
+
+    fn matches(filters: &Filters, channel: &MarketChannel) -> bool {
+        filters.matches(channel, "", &TagIndex::default())
+    }
+
+    fn with_range(from: &str, to: &str) -> Filters {
+        let mut filters = Filters::default();
+        filters.set_checked_from(from.to_owned());
+        filters.set_checked_to(to.to_owned());
+        filters
+    }
+
+    #[test]
+    fn parse_date_takes_a_utc_day() {
+        assert_eq!(parse_date("2019-06-08"), Ok(Some(date(2019, 6, 8))));
+        assert_eq!(parse_date(" 2019-06-08 "), Ok(Some(date(2019, 6, 8))));
+        assert_eq!(parse_date(""), Ok(None));
+        assert_eq!(parse_date("   "), Ok(None));
+        assert!(parse_date("08/06/2019").is_err());
+        assert!(parse_date("2019-02-30").is_err());
+        assert!(parse_date("yesterday").is_err());
+    }
+
+    #[test]
+    fn checked_range_includes_both_days() {
+        let filters = with_range("2019-06-08", "2019-06-08");
+        assert_eq!(filters.checked_error(), None);
+        assert_eq!(
+            filters.checked_bounds,
+            (Some(date(2019, 6, 8)), Some(date(2019, 6, 9)))
+        );
+        assert_eq!(filters.active_count(), 1);
+        // Fixtures were last checked on 2019-06-08
+        assert!(matches(&filters, &channel("a")));
+        assert!(!matches(&with_range("2019-06-09", ""), &channel("a")));
+        assert!(!matches(&with_range("", "2019-06-07"), &channel("a")));
+    }
+
+    #[test]
+    fn checked_range_from_after_to_is_reported_and_ignored() {
+        let filters = with_range("2019-06-09", "2019-06-08");
+        assert_eq!(filters.checked_error(), Some("From is after To"));
+        assert_eq!(filters.checked_bounds, (None, None));
+        assert_eq!(filters.active_count(), 0);
+        assert!(matches(&filters, &channel("a")));
+    }
+
+    #[test]
+    fn checked_range_invalid_input_is_reported_and_ignored() {
+        let mut filters = with_range("2019-06-09", "not a date");
+        assert!(filters.checked_error().unwrap().starts_with("To: "));
+        assert!(matches(&filters, &channel("a")));
+
+        // Fixing the input applies the range again
+        filters.set_checked_to("2019-06-10".to_owned());
+        assert_eq!(filters.checked_error(), None);
+        assert!(!matches(&filters, &channel("a")));
+    }
+
+    fn with_usd(usd_estimate: Option<f32>) -> MarketChannel {
+        let mut channel = channel("a");
+        channel.status.usd_estimate = usd_estimate;
+        channel
+    }
+
+    #[test]
+    fn usd_range_is_inclusive_and_ignores_what_is_not_a_number() {
+        let mut filters = Filters::default();
+        filters.set_usd_min("100".to_owned());
+        filters.set_usd_max(" 1000.5 ".to_owned());
+        assert_eq!(filters.usd_range(), (Some(100.0), Some(1000.5)));
+        assert!(matches(&filters, &with_usd(Some(100.0))));
+        assert!(matches(&filters, &with_usd(Some(1000.5))));
+        assert!(!matches(&filters, &with_usd(Some(99.9))));
+        assert!(!matches(&filters, &with_usd(Some(1001.0))));
+        // Without an estimate there's nothing to compare
+        assert!(!matches(&filters, &with_usd(None)));
+
+        filters.set_usd_min("lots".to_owned());
+        filters.set_usd_max("inf".to_owned());
+        assert_eq!(filters.usd_range(), (None, None));
+        assert_eq!(filters.active_count(), 0);
+        assert!(matches(&filters, &with_usd(None)));
+    }
+
+    #[test]
+    fn hiding_unpaid_channels_is_a_filter_like_the_others() {
+        let unpaid = channel("a");
+        let paid = crate::fixtures::channel_with_balances("b", &[("0x1", 10)]);
+        let mut filters = Filters::default();
+        assert!(matches(&filters, &unpaid));
+
+        filters.hide_unpaid = true;
+        assert_eq!(filters.active_count(), 1);
+        assert!(!matches(&filters, &unpaid));
+        assert!(matches(&filters, &paid));
+
+        let pairs = filters
+            .to_query()
+            .into_iter()
+            .map(|(name, value)| (name.to_owned(), value))
+            .collect::<Vec<_>>();
+        assert_eq!(pairs, vec![(HIDE_UNPAID_PARAM.to_owned(), "1".to_owned())]);
+        let (restored, warnings) = Filters::from_query(&pairs);
+        assert!(restored.hide_unpaid);
+        assert!(warnings.is_empty());
+
+        let (_, warnings) = Filters::from_query(&[(HIDE_UNPAID_PARAM.to_owned(), "maybe".to_owned())]);
+        assert_eq!(warnings.len(), 1);
+    }
+
+    #[test]
+    fn tag_filter_goes_through_the_tag_index() {
+        let mut tagged = crate::fixtures::channel_json("a");
+        tagged["spec"]["adUnits"][0]["targeting"] =
+            serde_json::json!([{ "tag": "crypto", "score": 60 }]);
+        let tagged = crate::fixtures::parse(tagged);
+        let untagged = channel("b");
+        let index = TagIndex::build(&[tagged.clone(), untagged.clone()]);
+
+        let mut filters = Filters::default();
+        filters.tag = "crypto".to_owned();
+        assert_eq!(filters.active_count(), 1);
+        assert!(filters.matches(&tagged, "", &index));
+        assert!(!filters.matches(&untagged, "", &index));
+        filters.tag_min_score = 60.5;
+        assert!(!filters.matches(&tagged, "", &index));
+        // No tag, no lookup
+        assert!(Filters::default().matches(&untagged, "", &index));
+    }
+}
@@ -0,0 +1,104 @@
+// Market data for the tests, in the shape the market sends it
+// Tests tweak the JSON before parsing it, so only what a test cares about has to be spelled out
+
+use super::types::MarketChannel;
+use adex_domain::BigNum;
+use serde_json::{json, Value};
+
+pub const CREATOR: &str = "0x2892f6C41E0718eeeDd49D98D648C789668cA67d";
+pub const LEADER: &str = "0xce07CbB7e054514D590a0262C93070D838bFBA2e";
+pub const FOLLOWER: &str = "0xc91763d7f14ac5c5dDfBCD012e0D2A61ab9bDED3";
+
+// 2019-06-08, in milliseconds
+pub const CREATED_MS: i64 = 1_560_000_000_000;
+// 2020-09-13, in seconds
+pub const VALID_UNTIL: i64 = 1_600_000_000;
+
+pub fn ad_unit(ipfs: &str) -> Value {
+    json!({
+        "ipfs": ipfs,
+        "type": "legacy_300x250",
+        "mediaUrl": format!("ipfs://{}", ipfs),
+        "mediaMime": "image/jpeg",
+        "targetUrl": "https://www.adex.network",
+        "targeting": [],
+        "minTargetingScore": null,
+        "tags": [{ "tag": "crypto", "score": 100 }],
+        "owner": CREATOR,
+        "created": CREATED_MS,
+        "title": "Unit",
+        "description": null,
+        "archived": false,
+        "modified": null
+    })
+}
+
+// An ad unit its owner has since archived, as campaigns can still reference them
+pub fn archived_ad_unit(ipfs: &str) -> Value {
+    let mut unit = ad_unit(ipfs);
+    unit["archived"] = json!(true);
+    unit
+}
+
+// Each id gets its own nonce, so fixtures aren't duplicates of each other by default
+fn nonce(id: &str) -> String {
+    id.bytes()
+        .fold(0u64, |acc, byte| acc.wrapping_mul(31).wrapping_add(u64::from(byte)))
+        .to_string()
+}
+
+pub fn channel_json(id: &str) -> Value {
+    json!({
+        "id": id,
+        "creator": CREATOR,
+        "depositAsset": "0x89d24A6b4CcB1B6fAA2625fE562bDD9a23260359",
+        "depositAmount": "1000000000000000000000",
+        "validUntil": VALID_UNTIL,
+        "spec": {
+            "title": "Campaign",
+            "validators": [
+                { "id": LEADER, "url": "https://tom.adex.network", "fee": "0" },
+                { "id": FOLLOWER, "url": "https://jerry.adex.network", "fee": "0" }
+            ],
+            "maxPerImpression": "1000000000000000",
+            "minPerImpression": "1000000000000000",
+            "targeting": [],
+            "minTargetingScore": null,
+            "eventSubmission": { "allow": [] },
+            "created": CREATED_MS,
+            "activeFrom": null,
+            "nonce": nonce(id),
+            "withdrawPeriodStart": CREATED_MS + 30 * 24 * 3600 * 1000,
+            "adUnits": [ad_unit("QmcUVX7fvoLMM93uN2bD3wGTH8MXSxeL8hojYfL2Lhp7mR")]
+        },
+        "status": {
+            "name": "Active",
+            "usdEstimate": 1000.0,
+            "lastApprovedBalances": {},
+            "lastChecked": CREATED_MS
+        }
+    })
+}
+
+// An amount as the market sends it, a decimal string of any length
+pub fn big(amount: &str) -> BigNum {
+    serde_json::from_value(json!(amount)).expect("a valid amount")
+}
+
+pub fn parse(json: Value) -> MarketChannel {
+    serde_json::from_value(json).expect("fixture is a valid market channel")
+}
+
+pub fn channel(id: &str) -> MarketChannel {
+    parse(channel_json(id))
+}
+
+// A channel whose earners were paid the given amounts, in wei
+pub fn channel_with_balances(id: &str, balances: &[(&str, u64)]) -> MarketChannel {
+    let mut channel = channel(id);
+    channel.status.balances = balances
+        .iter()
+        .map(|(addr, amount)| (addr.to_string(), BigNum::from(*amount)))
+        .collect();
+    channel
+}
@@ -0,0 +1,61 @@
+// A hidden element that external monitoring can assert on, without parsing the visible UI
+//
+// Contract:
+// <div id="explorer-health" hidden
+//      data-explorer-state="loaded|loading|error"
+//      data-channel-count="1432">
+//
+// `loading`: no campaigns loaded yet
+// `loaded`: campaigns are loaded and the latest fetch succeeded
+// `error`: the latest campaigns fetch failed (data may be stale or missing)
+// `data-channel-count` is the number of campaigns currently loaded (0 while loading)
+
+const MARKER_ID: &str = "explorer-health";
+
+pub enum HealthState {
+    Loading,
+    Loaded,
+    Error,
+}
+
+impl HealthState {
+    fn as_str(&self) -> &'static str {
+        match self {
+            HealthState::Loading => "loading",
+            HealthState::Loaded => "loaded",
+            HealthState::Error => "error",
+        }
+    }
+}
+
+pub fn write_marker(state: HealthState, channel_count: usize) {
+    let document = seed::document();
+    let marker = match document.get_element_by_id(MARKER_ID) {
+        Some(marker) => marker,
+        None => {
+            let marker = match document.create_element("div") {
+                Ok(marker) => marker,
+                Err(_) => return,
+            };
+            marker.set_id(MARKER_ID);
+            marker.set_attribute("hidden", "").ok();
+            match document.body() {
+                Some(body) => {
+                    body.append_child(&marker).ok();
+                }
+                None => return,
+            }
+            marker
+        }
+    };
+    // Monitors may watch the marker for mutations, so unchanged attributes are left alone
+    let attributes = [
+        ("data-explorer-state", state.as_str().to_owned()),
+        ("data-channel-count", channel_count.to_string()),
+    ];
+    for (name, value) in attributes.iter() {
+        if marker.get_attribute(name).as_ref() != Some(value) {
+            marker.set_attribute(name, value).ok();
+        }
+    }
+}
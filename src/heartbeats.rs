@@ -0,0 +1,58 @@
+// How long ago each validator of a channel last signed a Heartbeat: the first thing to look at
+// when a channel is Unhealthy or Offline
+use super::{duration, types, Msg};
+
+use chrono::{DateTime, Duration, Utc};
+use seed::prelude::*;
+use types::{Heartbeat, Heartbeats, ValidatorMessagesResp};
+
+// Validators send one about every minute; twice that is worth pointing out
+pub const STALE_SECONDS: i64 = 120;
+
+// The newest heartbeat in a validator-messages response, if there is one
+pub fn latest(resp: &ValidatorMessagesResp) -> Heartbeat {
+    resp.validator_messages
+        .iter()
+        .filter(|message| message.msg.message_type == "Heartbeat")
+        .filter_map(|message| message.msg.timestamp.or(message.received))
+        .max()
+        .map_or(Heartbeat::Missing, Heartbeat::Signed)
+}
+
+pub fn heartbeats_view(now: &DateTime<Utc>, heartbeats: &Heartbeats) -> Node<Msg> {
+    div![
+        class!["heartbeats"],
+        h3!["Heartbeats"],
+        p![
+            heartbeat("leader", now, &heartbeats.leader),
+            ", ",
+            heartbeat("follower", now, &heartbeats.follower)
+        ]
+    ]
+}
+
+// What the leader's and the follower's heartbeats read as at `now`, and whether they're stale
+// The ages are down to the second, so the update loop compares these on every Tick
+pub fn ages(now: &DateTime<Utc>, heartbeats: &Heartbeats) -> [(String, bool); 2] {
+    [age(now, &heartbeats.leader), age(now, &heartbeats.follower)]
+}
+
+fn age(now: &DateTime<Utc>, heartbeat: &Heartbeat) -> (String, bool) {
+    match heartbeat {
+        Heartbeat::Loading => ("...".to_owned(), false),
+        Heartbeat::Signed(signed) => {
+            let age = now.signed_duration_since(*signed);
+            (duration::humanize_past(age), age > Duration::seconds(STALE_SECONDS))
+        }
+        Heartbeat::Missing => ("none".to_owned(), true),
+        Heartbeat::Unavailable(reason) => (format!("unknown ({})", reason), false),
+    }
+}
+
+fn heartbeat(role: &str, now: &DateTime<Utc>, heartbeat: &Heartbeat) -> Node<Msg> {
+    let (text, stale) = age(now, heartbeat);
+    span![
+        class![if stale { "heartbeat stale" } else { "heartbeat" }],
+        format!("{}: {}", role, text)
+    ]
+}
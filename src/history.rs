@@ -0,0 +1,126 @@
+// The paid out total after every refresh, kept for the session, for "paid in the last 24h"
+use super::bignum;
+use adex_domain::BigNum;
+use serde::Serialize;
+use std::collections::VecDeque;
+
+pub const WINDOW_MS: f64 = 24.0 * 60.0 * 60.0 * 1000.0;
+// A refresh every 30 seconds is 2880 a day; manual refreshes can add a few more
+const MAX_SNAPSHOTS: usize = 4000;
+
+#[derive(Serialize, Clone, Debug)]
+pub struct Snapshot {
+    // ms since the epoch
+    pub at: f64,
+    pub paid: BigNum,
+}
+
+#[derive(Serialize, Default, Clone, Debug)]
+pub struct History {
+    snapshots: VecDeque<Snapshot>,
+}
+
+impl History {
+    pub fn push(&mut self, at: f64, paid: BigNum) {
+        self.snapshots.push_back(Snapshot { at, paid });
+        // Nothing older than the window is ever looked at
+        while self.snapshots.len() > MAX_SNAPSHOTS
+            || self
+                .snapshots
+                .front()
+                .map_or(false, |oldest| oldest.at < at - WINDOW_MS)
+        {
+            self.snapshots.pop_front();
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.snapshots.clear();
+    }
+
+    pub fn snapshots(&self) -> &VecDeque<Snapshot> {
+        &self.snapshots
+    }
+}
+
+// What was paid between the oldest snapshot within `window_ms` of the newest one and the newest,
+// along with the time that actually covers (less than the window until there's enough history)
+// None until there are two snapshots at different times
+pub fn windowed_delta(snapshots: &VecDeque<Snapshot>, window_ms: f64) -> Option<(BigNum, f64)> {
+    let newest = snapshots.back()?;
+    let oldest = snapshots
+        .iter()
+        .find(|snapshot| snapshot.at >= newest.at - window_ms)?;
+    if oldest.at >= newest.at {
+        return None;
+    }
+    // The paid total can't go down, unless the market had a hiccup; don't show that as negative
+    let (paid, _) = bignum::saturating_sub(&newest.paid, &oldest.paid);
+    Some((paid, newest.at - oldest.at))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOUR_MS: f64 = 60.0 * 60.0 * 1000.0;
+
+    fn history(points: &[(f64, u64)]) -> History {
+        let mut history = History::default();
+        for &(at, paid) in points {
+            history.push(at, BigNum::from(paid));
+        }
+        history
+    }
+
+    #[test]
+    fn windowed_delta_needs_two_points_in_time() {
+        assert_eq!(windowed_delta(&VecDeque::new(), WINDOW_MS), None);
+        let one = history(&[(HOUR_MS, 10)]);
+        assert_eq!(windowed_delta(one.snapshots(), WINDOW_MS), None);
+        let same_time = history(&[(HOUR_MS, 10), (HOUR_MS, 20)]);
+        assert_eq!(windowed_delta(same_time.snapshots(), WINDOW_MS), None);
+    }
+
+    #[test]
+    fn windowed_delta_covers_less_than_the_window_at_first() {
+        let history = history(&[(0.0, 10), (HOUR_MS, 15), (2.0 * HOUR_MS, 40)]);
+        assert_eq!(
+            windowed_delta(history.snapshots(), WINDOW_MS),
+            Some((BigNum::from(30), 2.0 * HOUR_MS))
+        );
+    }
+
+    #[test]
+    fn windowed_delta_starts_at_the_oldest_point_within_the_window() {
+        let history = history(&[(0.0, 10), (HOUR_MS, 15), (3.0 * HOUR_MS, 40)]);
+        assert_eq!(
+            windowed_delta(history.snapshots(), 2.0 * HOUR_MS),
+            Some((BigNum::from(25), 2.0 * HOUR_MS))
+        );
+        // Exactly on the edge still counts
+        assert_eq!(
+            windowed_delta(history.snapshots(), 3.0 * HOUR_MS),
+            Some((BigNum::from(30), 3.0 * HOUR_MS))
+        );
+    }
+
+    #[test]
+    fn windowed_delta_does_not_go_negative() {
+        let history = history(&[(0.0, 40), (HOUR_MS, 10)]);
+        assert_eq!(
+            windowed_delta(history.snapshots(), WINDOW_MS),
+            Some((BigNum::from(0), HOUR_MS))
+        );
+    }
+
+    #[test]
+    fn push_drops_what_falls_out_of_the_window() {
+        let history = history(&[(0.0, 10), (WINDOW_MS, 20), (WINDOW_MS + HOUR_MS, 30)]);
+        assert_eq!(history.snapshots().len(), 2);
+        assert_eq!(
+            windowed_delta(history.snapshots(), WINDOW_MS),
+            Some((BigNum::from(10), HOUR_MS))
+        );
+    }
+}
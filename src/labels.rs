@@ -0,0 +1,97 @@
+// Display names for validators and addresses we know, so the UI doesn't only show raw strings
+// Deployments can add their own through a JSON object of raw value to name, passed to `render`
+use super::validator;
+use serde::Serialize;
+use std::collections::HashMap;
+
+// Keys are validator hostnames or lowercase addresses
+const BUILTIN: &[(&str, &str)] = &[
+    ("itchy.adex.network", "AdEx (leader)"),
+    ("tom.adex.network", "AdEx (Tom)"),
+    ("jerry.adex.network", "AdEx (Jerry)"),
+];
+
+#[derive(Serialize, Default, Clone, Debug)]
+pub struct Labels {
+    // Loaded at startup; takes precedence over the built-in ones
+    user: HashMap<String, String>,
+}
+
+impl Labels {
+    pub fn set_user(&mut self, user: HashMap<String, String>) {
+        self.user = user.into_iter().map(|(raw, name)| (key(&raw), name)).collect();
+    }
+
+    pub fn user_count(&self) -> usize {
+        self.user.len()
+    }
+
+    // `raw` is a validator URL, a hostname or an address
+    pub fn name(&self, raw: &str) -> Option<&str> {
+        let key = key(raw);
+        match self.user.get(&key) {
+            Some(name) => Some(name),
+            None => BUILTIN
+                .iter()
+                .find(|&&(known, _)| known == key)
+                .map(|&(_, name)| name),
+        }
+    }
+}
+
+// URLs are looked up by hostname, so any spelling of a validator URL matches
+fn key(raw: &str) -> String {
+    let raw = raw.trim();
+    if raw.contains("://") {
+        validator::display_host(raw)
+    } else {
+        raw.to_lowercase()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(entries: &[(&str, &str)]) -> Labels {
+        let mut labels = Labels::default();
+        labels.set_user(
+            entries
+                .iter()
+                .map(|&(raw, name)| (raw.to_owned(), name.to_owned()))
+                .collect(),
+        );
+        labels
+    }
+
+    #[test]
+    fn built_in_names() {
+        let labels = Labels::default();
+        assert_eq!(labels.name("https://tom.adex.network"), Some("AdEx (Tom)"));
+        assert_eq!(labels.name("https://JERRY.adex.network/"), Some("AdEx (Jerry)"));
+        assert_eq!(labels.name("https://unknown.example.com"), None);
+    }
+
+    #[test]
+    fn user_supplied_names_override_built_in_ones() {
+        let labels = user(&[("tom.adex.network", "Tom, renamed")]);
+        assert_eq!(labels.name("https://tom.adex.network"), Some("Tom, renamed"));
+        // The others are still known
+        assert_eq!(labels.name("https://jerry.adex.network"), Some("AdEx (Jerry)"));
+    }
+
+    #[test]
+    fn user_keys_match_any_spelling() {
+        let labels = user(&[
+            ("https://Validator.Example.com/", "Example validator"),
+            ("0xABCDEF0123456789abcdef0123456789ABCDEF01", "Example advertiser"),
+        ]);
+        assert_eq!(labels.user_count(), 2);
+        assert_eq!(labels.name("validator.example.com"), Some("Example validator"));
+        assert_eq!(labels.name("http://validator.example.com:8005"), Some("Example validator"));
+        assert_eq!(
+            labels.name("0xabcdef0123456789abcdef0123456789abcdef01"),
+            Some("Example advertiser")
+        );
+    }
+}
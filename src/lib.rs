@@ -1,34 +1,102 @@
 #[macro_use]
 extern crate seed;
 
+mod activity;
+mod amounts;
+mod bignum;
+mod burn_rate;
+mod clipboard;
+mod cohorts;
+mod config;
+mod data_quality;
+mod debug;
+mod duration;
+mod export;
+mod filters;
+#[cfg(test)]
+mod fixtures;
+mod health;
+mod heartbeats;
+mod history;
+mod ipfs;
+mod labels;
+mod presentation;
+mod query;
+mod rate_limits;
+mod previews;
+mod settings;
+mod slots_table;
 mod stats_table;
+mod status_footer;
+mod tags;
+mod timestamps;
 mod types;
+mod validator;
+mod validator_report;
 
 use adex_domain::{AdUnit, BigNum, Channel};
-use chrono::{DateTime, Utc};
+use amounts::{exact_wei, format_amount, AmountUnit};
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use config::{
+    DataScope, Environment, CAMPAIGNS_PAGE_RETRIES, CAMPAIGNS_PAGE_RETRY_MS, CAMPAIGNS_PAGE_SIZE,
+    CAMPAIGNS_PAGE_TIMEOUT_MS, TRIMMED_BALANCES_KEPT,
+};
+use filters::Filters;
+use health::HealthState;
+use previews::PreviewState;
+use futures::Future;
 use lazysort::*;
 use num_format::{Locale, ToFormattedString};
 use seed::fetch;
 use seed::prelude::*;
 use seed::{Method, Request};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use settings::{Settings, MAX_DAI_PRECISION};
+use slots_table::slots_table;
 use stats_table::ad_unit_stats_table;
-use std::collections::HashSet;
-use types::{ChannelSort, EtherscanBalResp, Loadable, MarketChannel, AnalyticsResp};
+use std::cmp::Ordering;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use types::{AdSlot, ChannelSort, ChannelStats, EtherscanBalResp, MarketStatusType, Loadable, MarketChannel, AnalyticsResp, ChannelActivity, EventAggregates, FetchError, Heartbeat, Heartbeats, ValidatorRole, PageResponse, Totals, ValidatorMessagesResp, ValidatorReport};
 
 use Loadable::*;
 
-const MARKET_URL: &str = "https://market.adex.network";
 const DAILY_VOL_URL: &str = "https://tom.adex.network/analytics?metric=eventPayouts&timeframe=day";
 const DAILY_IMPRESSIONS_URL: &str = "https://tom.adex.network/analytics?metric=eventCounts&timeframe=day";
 const IMPRESSIONS_URL: &str = "https://tom.adex.network/analytics?metric=eventCounts&timeframe=month";
 const YEARLY_IMPRESSIONS_URL: &str = "https://tom.adex.network/analytics?metric=eventCounts&timeframe=year";
-const ETHERSCAN_URL: &str = "https://api.etherscan.io/api";
 const ETHERSCAN_API_KEY: &str = "CUSGAYGXI4G2EIYN1FKKACBUIQMN5BKR2B";
-const IPFS_GATEWAY: &str = "https://ipfs.adex.network/ipfs/";
-const DAI_ADDR: &str = "0x6B175474E89094C44Da98b954EedeAC495271d0F";
-const CORE_ADDR: &str = "0x333420fc6a897356e69b62417cd17ff012177d2b";
 const DEFAULT_EARNER: &str = "0xb7d3f81e857692d13e9d63b232a90f4a1793189e";
 const REFRESH_MS: i32 = 30000;
+const SEARCH_INPUT_ID: &str = "search";
+const SEARCH_DEBOUNCE_MS: i32 = 250;
+// Manual refreshes closer together than this are ignored
+const MANUAL_REFRESH_MIN_MS: f64 = 2000.0;
+const THROTTLE_HINT_MS: f64 = 1500.0;
+const COPY_NOTICE_MS: f64 = 2000.0;
+// How long the change indicators on the totals stay up after a refresh
+const DELTA_FADE_MS: f64 = 8000.0;
+// The one timer everything time-based runs off, see Msg::Tick; the notices above go away on the
+// first tick past their time, so up to this much later
+const TICK_MS: i32 = 1000;
+// Data older than this is flagged as stale, since refreshes have clearly been failing
+const STALE_MS: f64 = 120_000.0;
+// Shown in the header and the settings while every campaign ever listed is loaded
+const ALL_SCOPE_WARNING: &str =
+    "Downloads every campaign ever listed, which can take a while on a slow connection";
+// As in index.html
+const DOCUMENT_TITLE: &str = "AdEx Explorer";
+// The expiring soon panel looks this far ahead, and lists at most this many campaigns
+const EXPIRING_SOON_DAYS: i64 = 7;
+const EXPIRING_SOON_MAX: usize = 10;
+// A search matching many groups expands only the first ones, rather than the whole table
+const SEARCH_EXPANDED_MAX: usize = 10;
+// The sort in a share link; the filters have their own parameters, see filters.rs
+const SORT_PARAM: &str = "sort";
 
 // @TODO can we derive this automatically
 impl From<String> for ChannelSort {
@@ -37,15 +105,72 @@ impl From<String> for ChannelSort {
             "deposit" => ChannelSort::Deposit,
             "status" => ChannelSort::Status,
             "created" => ChannelSort::Created,
+            "publishers" => ChannelSort::PublisherCount,
+            "price" => ChannelSort::Price,
+            "expiry" => ChannelSort::Expiry,
+            "age" => ChannelSort::Age,
+            "fees" => ChannelSort::Fees,
             _ => ChannelSort::default(),
         }
     }
 }
 
-#[derive(Default)]
+// The other way around, for share links
+fn sort_name(sort: ChannelSort) -> &'static str {
+    match sort {
+        ChannelSort::Deposit => "deposit",
+        ChannelSort::Status => "status",
+        ChannelSort::Created => "created",
+        ChannelSort::PublisherCount => "publishers",
+        ChannelSort::Price => "price",
+        ChannelSort::Expiry => "expiry",
+        ChannelSort::Age => "age",
+        ChannelSort::Fees => "fees",
+    }
+}
+
+#[derive(Default, Serialize)]
 pub struct Model {
     pub load_action: ActionLoad,
-    pub sort: ChannelSort,
+    pub settings: Settings,
+    pub settings_open: bool,
+    pub debug_open: bool,
+    // Log every message to the console; set with ?debug
+    pub log_updates: bool,
+    pub filters: Filters,
+    // The query the filters were last saved as, so they're only written when they change
+    pub saved_filters: String,
+    // What's typed in the search box; only copied into the filters once typing settles
+    pub search_input: String,
+    pub search_generation: u32,
+    // Fingerprints of the duplicate groups that are expanded; kept across refreshes
+    pub expanded_groups: HashSet<String>,
+    // Those of them expanded because of the search, collapsed again when it changes
+    pub search_expanded: HashSet<String>,
+    // Ids of the channels the search matched on their own, shown with a detail row under them
+    pub expanded_channels: HashSet<String>,
+    // While set, rows keep this order (channel id -> position) across refreshes
+    pub frozen_order: Option<HashMap<String, usize>>,
+    // Ids of the channels selected for comparison, at most two
+    pub compare: Vec<String>,
+    pub compare_open: bool,
+    pub status_legend_open: bool,
+    // Reachability of each validator, by canonical base URL; missing means unknown
+    pub validator_health: HashMap<String, bool>,
+    // Display names for known validators and addresses
+    pub labels: labels::Labels,
+    // Channel id and what its leader says, for unhealthy channels on the detail page
+    pub validator_report: Option<(String, ValidatorReport)>,
+    // Channel id and the latest heartbeat of each of its validators, on the detail page
+    pub heartbeats: Option<(String, Heartbeats)>,
+    // Channel id and its hourly events, once asked for on the detail page
+    pub activity: Option<(String, ChannelActivity)>,
+    // Derived per-channel figures, keyed by channel id
+    pub channel_stats: HashMap<String, ChannelStats>,
+    // Channel id -> ids of the channels it duplicates
+    pub duplicates: HashMap<String, Vec<String>>,
+    // Channel id -> ad unit owners other than the creator
+    pub owner_mismatches: HashMap<String, data_quality::OwnerMismatch>,
     // Market channels & balance: for the summaries page
     pub market_channels: Loadable<Vec<MarketChannel>>,
     pub balance: Loadable<EtherscanBalResp>,
@@ -53,13 +178,86 @@ pub struct Model {
     pub impressions: Loadable<AnalyticsResp>,
     pub daily_impressions: Loadable<AnalyticsResp>,
     pub yearly_impressions: Loadable<AnalyticsResp>,
+    // Publisher ad slots: for the slots page
+    pub slots: Loadable<Vec<AdSlot>>,
     // Current selected channel: for ChannelDetail
     pub channel: Loadable<Channel>,
     pub last_loaded: i64,
+    // When the last load was kicked off, in ms; used to throttle manual refreshes
+    pub last_fetch_started: f64,
+    // Feedback after copying something to the clipboard
+    pub copy_notice: Option<String>,
+    // When the throttle hint, the copy notice and the change indicators go away, if they're up
+    // All in ms, and checked on every Tick
+    pub throttle_hint_until: Option<f64>,
+    pub copy_notice_until: Option<f64>,
+    pub deltas_until: Option<f64>,
+    // Pretty-printed JSON of the channel shown in the raw JSON modal
+    pub raw_modal: Option<String>,
+    // The trimmed channel whose raw JSON is being fetched in full, to open the modal with
+    pub raw_modal_pending: Option<String>,
+    // With balances trimming on, the channel of the detail page or the raw JSON modal, fetched on
+    // its own with all of its balances
+    pub full_channel: Option<MarketChannel>,
+    // Pages of the "Export all (JSON)" download so far, while it's fetching them untrimmed
+    #[serde(skip)]
+    pub export_pages: Option<Vec<serde_json::Value>>,
+    // Figures for the debug panel, about the last complete campaigns load
+    pub last_fetch_duration_ms: Option<f64>,
+    pub last_response_bytes: usize,
+    pub loading_bytes: usize,
+    pub parse_failures: u32,
+    // For the status footer; all times in ms
+    pub now: f64,
+    pub last_success: Option<f64>,
+    pub consecutive_failures: u32,
+    pub next_refresh_at: f64,
+    // Polling is suspended while the browser says we're offline
+    pub offline: bool,
+    // Lowercased ad unit titles and descriptions, by channel id, for the search
+    pub ad_unit_text: HashMap<String, String>,
+    // Summarized event submission rules, by channel id; missing if the spec has none
+    pub rate_limits: HashMap<String, String>,
+    // Which channels target which tags, rebuilt on every load
+    pub tag_index: tags::TagIndex,
+    // Which ad unit previews on the detail page were asked for and how they did
+    pub previews: previews::Previews,
+    // Presentation mode: the page scrolls by itself, see presentation.rs
+    pub presenting: bool,
+    // What scrolls it, only there while presenting
+    #[serde(skip)]
+    pub presentation: Option<presentation::Session>,
+    // Totals as of the last complete load and the one before, for the change indicators
+    pub totals: Option<Totals>,
+    pub previous_totals: Option<Totals>,
+    // The paid out total after each refresh this session
+    pub paid_history: history::History,
+    // Paid per channel as of the last load, to tell which ones pay out unusually fast
+    pub burn_rates: burn_rate::BurnRates,
+    // Bumped whenever the environment changes, so responses from the old one get dropped
+    pub load_generation: u32,
+    // Set when the latest campaigns fetch failed
+    pub load_error: Option<String>,
+    // Pages of campaigns received so far, while a paged load is running
+    pub campaigns_paging: Option<Vec<MarketChannel>>,
+    pub page_retries: u32,
+    // The failed page waiting to be fetched again, and when; fetched by Tick
+    pub page_retry_at: Option<(usize, f64)>,
+    // Whether any page of the running load differs from the last one
+    pub paging_changed: bool,
+    // ETag and body hash of each page, keyed by skip, to tell when nothing changed
+    pub page_etags: HashMap<usize, String>,
+    pub page_hashes: HashMap<usize, u64>,
+    // The first load shows pages as they arrive, before all of them are in
+    pub channels_partial: bool,
+    // Later loads keep showing the data we have, with an indicator, until they're done
+    pub refreshing: bool,
+    // Number of campaigns in the last complete load, to estimate progress
+    pub last_channel_count: Option<usize>,
 }
 
 // Update
-#[derive(Clone, PartialEq, Debug)]
+#[derive(Clone, PartialEq, Debug, Serialize)]
 pub enum ActionLoad {
     // The summary includes latest campaigns on the market,
     // and some on-chain data (e.g. DAI balance on core SC)
@@ -67,6 +265,10 @@ pub enum ActionLoad {
     // Channels will show the summary plus the channels
     ChannelsActive,
     ChannelsAll,
+    // Campaigns bucketed by creation month
+    Cohorts,
+    // Publisher ad slots from the market
+    Slots,
     // The channel detail contains a summary of what validator knows about a channel
     ChannelDetail(String),
 }
@@ -77,57 +279,68 @@ impl Default for ActionLoad {
 }
 
 impl ActionLoad {
-    fn perform_effects(&self, orders: &mut impl Orders<Msg>) {
+    fn perform_effects(&self, model: &mut Model, orders: &mut impl Orders<Msg>) {
+        model.last_fetch_started = js_sys::Date::now();
+        let env = model.settings.env.config();
+        let generation = model.load_generation;
         match self {
-            ActionLoad::Summary | ActionLoad::ChannelsActive | ActionLoad::ChannelsAll => {
+            ActionLoad::Summary
+            | ActionLoad::ChannelsActive
+            | ActionLoad::ChannelsAll
+            | ActionLoad::Cohorts => {
                 // Load on-chain balances
-                let etherscan_uri = format!(
-                    "{}?module=account&action=tokenbalance&contractAddress={}&address={}&tag=latest&apikey={}",
-                    ETHERSCAN_URL,
-                    DAI_ADDR,
-                    CORE_ADDR,
-                    ETHERSCAN_API_KEY
-                );
-                orders.perform_cmd(
-                    Request::new(etherscan_uri)
-                        .method(Method::Get)
-                        .fetch_json_data(Msg::BalanceLoaded),
-                );
+                if let Some(core_addr) = env.core_addr {
+                    let etherscan_uri = format!(
+                        "{}?module=account&action=tokenbalance&contractAddress={}&address={}&tag=latest&apikey={}",
+                        env.etherscan_url,
+                        env.dai_addr,
+                        core_addr,
+                        ETHERSCAN_API_KEY
+                    );
+                    orders.perform_cmd(fetch_tagged(etherscan_uri, generation, Msg::BalanceLoaded));
+                }
 
                 // Load campaigns from the market
-                orders.perform_cmd(
-                    Request::new(format!("{}/campaigns?all", MARKET_URL))
-                        .method(Method::Get)
-                        .fetch_json_data(Msg::ChannelsLoaded),
-                );
+                load_campaigns(&env.market_url, model, orders);
 
                 // Load volume
-                orders.perform_cmd(
-                    Request::new(String::from(DAILY_VOL_URL))
-                        .method(Method::Get)
-                        .fetch_json_data(Msg::VolumeLoaded),
-                );
-                orders.perform_cmd(
-                    Request::new(String::from(IMPRESSIONS_URL))
-                        .method(Method::Get)
-                        .fetch_json_data(Msg::ImpressionsLoaded),
-                );
-                orders.perform_cmd(
-                    Request::new(String::from(DAILY_IMPRESSIONS_URL))
-                        .method(Method::Get)
-                        .fetch_json_data(Msg::DailyImpressionsLoaded),
-                );
-                orders.perform_cmd(
-                    Request::new(String::from(YEARLY_IMPRESSIONS_URL))
-                        .method(Method::Get)
-                        .fetch_json_data(Msg::YearlyImpressionsLoaded),
-                );
+                orders.perform_cmd(fetch_tagged(
+                    String::from(DAILY_VOL_URL),
+                    generation,
+                    Msg::VolumeLoaded,
+                ));
+                orders.perform_cmd(fetch_tagged(
+                    String::from(IMPRESSIONS_URL),
+                    generation,
+                    Msg::ImpressionsLoaded,
+                ));
+                orders.perform_cmd(fetch_tagged(
+                    String::from(DAILY_IMPRESSIONS_URL),
+                    generation,
+                    Msg::DailyImpressionsLoaded,
+                ));
+                orders.perform_cmd(fetch_tagged(
+                    String::from(YEARLY_IMPRESSIONS_URL),
+                    generation,
+                    Msg::YearlyImpressionsLoaded,
+                ));
+            }
+            ActionLoad::Slots => {
+                orders.perform_cmd(fetch_tagged(
+                    format!("{}/slots", env.market_url),
+                    generation,
+                    Msg::SlotsLoaded,
+                ));
             }
-            // NOTE: not used yet
             ActionLoad::ChannelDetail(id) => {
+                // The detail is rendered from the market's view of the channel, which it has on
+                // its own; there's no need to page through every other campaign for it
+                orders.perform_cmd(fetch_full_channel(&env.market_url, id, generation));
+
+                // NOTE: not used yet
                 let market_uri = format!(
                     "{}/channel/{}/events-aggregates/{}?timeframe=hour&limit=168",
-                    MARKET_URL,
+                    env.market_url,
                     &id,
                     // @TODO get rid of this default earner thing, it's very very temporary
                     // we should get an aggr of all earners
@@ -139,40 +352,526 @@ impl ActionLoad {
     }
 }
 
+fn load_campaigns(market_url: &str, model: &mut Model, orders: &mut impl Orders<Msg>) {
+    if start_paging(model) {
+        orders.perform_cmd(fetch_campaigns_page(market_url, 0, model));
+    }
+}
+
+fn fetch_full_channel(
+    market_url: &str,
+    id: &str,
+    generation: u32,
+) -> impl Future<Item = Msg, Error = Msg> {
+    fetch_tagged(format!("{}/campaigns/{}", market_url, id), generation, Msg::FullChannelLoaded)
+}
+
+// The channel with all of its balances: the one fetched on its own, or the listed one if it
+// wasn't trimmed
+fn full_channel<'a>(model: &'a Model, id: &str) -> Option<&'a MarketChannel> {
+    match (&model.full_channel, &model.market_channels) {
+        (Some(channel), _) if channel.id == id => Some(channel),
+        (_, Ready(channels)) => channels
+            .iter()
+            .find(|c| c.id == id && c.status.trimmed.is_none()),
+        _ => None,
+    }
+}
+
+// The channel of the detail page: the one fetched on its own, or the listed one until that's in
+fn detail_channel(model: &Model) -> Option<&MarketChannel> {
+    let id = match &model.load_action {
+        ActionLoad::ChannelDetail(id) => id,
+        _ => return None,
+    };
+    match (&model.full_channel, &model.market_channels) {
+        (Some(channel), _) if &channel.id == id => Some(channel),
+        (_, Ready(channels)) => channels.iter().find(|c| &c.id == id),
+        _ => None,
+    }
+}
+
+// Not tagged with the load generation: the download is what the market had when it was asked for
+fn fetch_export_page(
+    market_url: &str,
+    skip: usize,
+    model: &Model,
+) -> impl Future<Item = Msg, Error = Msg> {
+    Request::new(format!(
+        "{}/campaigns?{}limit={}&skip={}",
+        market_url,
+        model.settings.data_scope.query(),
+        CAMPAIGNS_PAGE_SIZE,
+        skip
+    ))
+    .method(Method::Get)
+    .fetch_json_data(move |resp| Msg::ExportPageLoaded(skip, resp))
+}
+
+// Keeps a page of the export; the skip of the next page to fetch, or None once `export_pages` has
+// all of them
+fn add_export_page(model: &mut Model, skip: usize, page: Vec<serde_json::Value>) -> Option<usize> {
+    let is_last = page.len() < CAMPAIGNS_PAGE_SIZE;
+    model.export_pages.get_or_insert_with(Vec::new).extend(page);
+    if is_last {
+        None
+    } else {
+        Some(skip + CAMPAIGNS_PAGE_SIZE)
+    }
+}
+
+// False if a paged load is already running
+fn start_paging(model: &mut Model) -> bool {
+    // A paged load is already running, and it will end up with the latest data anyway
+    if model.campaigns_paging.is_some() {
+        return false;
+    }
+    model.campaigns_paging = Some(Vec::new());
+    model.refreshing = !model.market_channels.is_loading();
+    model.page_retries = 0;
+    model.page_retry_at = None;
+    model.paging_changed = false;
+    model.loading_bytes = 0;
+    true
+}
+
+// Fetched as a string rather than JSON, so we can look at the headers and
+// skip parsing pages that haven't changed
+fn fetch_campaigns_page(
+    market_url: &str,
+    skip: usize,
+    model: &Model,
+) -> impl Future<Item = Msg, Error = Msg> {
+    let generation = model.load_generation;
+    let mut request = Request::new(format!(
+        "{}/campaigns?{}limit={}&skip={}",
+        market_url,
+        model.settings.data_scope.query(),
+        CAMPAIGNS_PAGE_SIZE,
+        skip
+    ))
+    .method(Method::Get)
+    .timeout(CAMPAIGNS_PAGE_TIMEOUT_MS);
+    if let Some(etag) = model.page_etags.get(&skip) {
+        request = request.header("If-None-Match", etag);
+    }
+    request.fetch_string(move |fetch_object| {
+        let page = match fetch_object.result {
+            Ok(resp) => PageResponse::from_response(
+                resp.status.code,
+                &resp.status.text,
+                resp.raw.headers().get("ETag").ok().and_then(|etag| etag),
+                resp.data.map_err(|reason| format!("{:?}", reason)),
+            ),
+            Err(fetch::RequestError::DomException(ref exception))
+                if exception.name() == "AbortError" =>
+            {
+                PageResponse::Failed(FetchError::Timeout)
+            }
+            Err(reason) => PageResponse::Failed(FetchError::Network(format!("{:?}", reason))),
+        };
+        Msg::Fetched(generation, Box::new(Msg::CampaignsPageLoaded(skip, page)))
+    })
+}
+
+// What's next for a paged load once one of its pages is in
+enum PageStep {
+    // Fetch the page at `skip`, re-rendering first only if `render` is set
+    Fetch { skip: usize, render: bool },
+    Retry { skip: usize, reason: FetchError },
+    Loaded(Vec<MarketChannel>),
+    // Every page is what it was in the previous load
+    Unchanged,
+    Failed(FetchError),
+}
+
+fn campaigns_page_loaded(
+    skip: usize,
+    resp: PageResponse,
+    model: &mut Model,
+    orders: &mut impl Orders<Msg>,
+) {
+    let market_url = model.settings.env.config().market_url;
+    match next_page_step(skip, resp, model) {
+        PageStep::Fetch { skip, render } => {
+            if !render {
+                orders.skip();
+            }
+            orders.perform_cmd(fetch_campaigns_page(&market_url, skip, model));
+        }
+        PageStep::Retry { skip, reason } => {
+            // The next Tick past `page_retry_at` fetches it again
+            log!("CampaignsPageLoaded error, retrying:", skip, reason.to_string());
+        }
+        PageStep::Loaded(channels) => update(Msg::ChannelsLoaded(channels), model, orders),
+        PageStep::Unchanged => update(Msg::ChannelsUnchanged, model, orders),
+        PageStep::Failed(reason) => update(Msg::ChannelsFailed(reason), model, orders),
+    }
+}
+
+// Keeps the page at `skip` and works out what comes next, without any fetching of its own
+fn next_page_step(skip: usize, resp: PageResponse, model: &mut Model) -> PageStep {
+    let page = match resp {
+        PageResponse::Failed(reason) => {
+            return retry_page(skip, reason, model);
+        }
+        PageResponse::NotModified => previous_page(model, skip),
+        PageResponse::Body { etag, body } => {
+            model.loading_bytes += body.len();
+            let hash = hash_body(&body);
+            let unchanged = model.page_hashes.get(&skip) == Some(&hash);
+            let page = if unchanged {
+                previous_page(model, skip)
+            } else {
+                match serde_json::from_str::<Vec<MarketChannel>>(&body) {
+                    Ok(page) => {
+                        model.paging_changed = true;
+                        Some(page)
+                    }
+                    Err(reason) => {
+                        model.parse_failures += 1;
+                        let reason = FetchError::Deserialize(reason.to_string());
+                        return retry_page(skip, reason, model);
+                    }
+                }
+            };
+            match etag {
+                Some(etag) => model.page_etags.insert(skip, etag),
+                None => model.page_etags.remove(&skip),
+            };
+            model.page_hashes.insert(skip, hash);
+            page
+        }
+    };
+    let page = match page {
+        Some(page) => page,
+        None => {
+            // We were told nothing changed but don't have the old page; fetch it in full
+            model.page_etags.remove(&skip);
+            model.page_hashes.remove(&skip);
+            return PageStep::Fetch { skip, render: true };
+        }
+    };
+    let is_last = page.len() < CAMPAIGNS_PAGE_SIZE;
+    let mut loaded = model.campaigns_paging.take().unwrap_or_default();
+    loaded.extend(page);
+    model.page_retries = 0;
+    if is_last {
+        model.last_response_bytes = model.loading_bytes;
+        let same_count = channel_count(model) == loaded.len();
+        if !model.paging_changed && same_count && !model.channels_partial {
+            PageStep::Unchanged
+        } else {
+            PageStep::Loaded(loaded)
+        }
+    } else {
+        // Make the first load usable as soon as the first page is in
+        let render = model.channels_partial || model.market_channels.is_loading();
+        if render {
+            set_channels(model, loaded.clone());
+            model.channels_partial = true;
+        }
+        model.campaigns_paging = Some(loaded);
+        PageStep::Fetch {
+            skip: skip + CAMPAIGNS_PAGE_SIZE,
+            render,
+        }
+    }
+}
+
+fn retry_page(skip: usize, reason: FetchError, model: &mut Model) -> PageStep {
+    if reason.is_retryable() && model.page_retries < CAMPAIGNS_PAGE_RETRIES {
+        // Retry just this page, backing off a little more each time
+        let delay = CAMPAIGNS_PAGE_RETRY_MS * f64::from(1 << model.page_retries);
+        model.page_retries += 1;
+        model.page_retry_at = Some((skip, model.now + delay));
+        PageStep::Retry { skip, reason }
+    } else {
+        // Some pages may have been recorded without making it into the model
+        model.campaigns_paging = None;
+        model.page_retry_at = None;
+        model.page_etags.clear();
+        model.page_hashes.clear();
+        PageStep::Failed(reason)
+    }
+}
+
+// Used when the server ignores If-None-Match
+fn hash_body(body: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    body.hash(&mut hasher);
+    hasher.finish()
+}
+
+// The part of the current channels that came from the page at `skip`
+fn previous_page(model: &Model, skip: usize) -> Option<Vec<MarketChannel>> {
+    match &model.market_channels {
+        Ready(channels) if skip <= channels.len() => {
+            let end = channels.len().min(skip + CAMPAIGNS_PAGE_SIZE);
+            Some(channels[skip..end].to_vec())
+        }
+        _ => None,
+    }
+}
+
+// Every response is tagged with the load generation it was requested in
+fn fetch_tagged<T>(
+    url: String,
+    generation: u32,
+    to_msg: fn(fetch::ResponseDataResult<T>) -> Msg,
+) -> impl Future<Item = Msg, Error = Msg>
+where
+    T: DeserializeOwned + std::fmt::Debug + 'static,
+{
+    Request::new(url)
+        .method(Method::Get)
+        .fetch_json_data(move |resp| Msg::Fetched(generation, Box::new(to_msg(resp))))
+}
+
+// What a failed `fetch_tagged` request comes down to, in the same terms as a failed page
+fn fetch_error<T>(reason: &fetch::FailReason<T>) -> FetchError {
+    match reason {
+        fetch::FailReason::Status(status, _) => FetchError::Http(status.code, status.text.clone()),
+        fetch::FailReason::DataError(error, _) => FetchError::Deserialize(format!("{:?}", error)),
+        fetch::FailReason::RequestError(error, _) => FetchError::Network(format!("{:?}", error)),
+    }
+}
+
 #[derive(Clone)]
 pub enum Msg {
     Load(ActionLoad),
-    Refresh,
+    // The time, in ms; what's due at that time happens in `update`
+    Tick(f64),
+    ConnectivityChanged(bool),
+    ManualRefresh,
+    Fetched(u32, Box<Msg>),
     BalanceLoaded(fetch::ResponseDataResult<EtherscanBalResp>),
-    ChannelsLoaded(fetch::ResponseDataResult<Vec<MarketChannel>>),
+    ChannelsLoaded(Vec<MarketChannel>),
+    ChannelsUnchanged,
+    ChannelsFailed(FetchError),
+    CampaignsPageLoaded(usize, PageResponse),
+    FullChannelLoaded(fetch::ResponseDataResult<MarketChannel>),
+    ExportPageLoaded(usize, fetch::ResponseDataResult<Vec<serde_json::Value>>),
     VolumeLoaded(fetch::ResponseDataResult<AnalyticsResp>),
     ImpressionsLoaded(fetch::ResponseDataResult<AnalyticsResp>),
     DailyImpressionsLoaded(fetch::ResponseDataResult<AnalyticsResp>),
     YearlyImpressionsLoaded(fetch::ResponseDataResult<AnalyticsResp>),
+    SlotsLoaded(fetch::ResponseDataResult<Vec<AdSlot>>),
+    ValidatorChecked(String, Option<bool>),
+    ValidatorReportLoaded(String, fetch::ResponseDataResult<ValidatorMessagesResp>),
+    HeartbeatLoaded(String, ValidatorRole, fetch::ResponseDataResult<ValidatorMessagesResp>),
+    LoadActivity(String),
+    ActivityLoaded(String, fetch::ResponseDataResult<EventAggregates>),
+    LoadLabels(String),
+    LabelsLoaded(fetch::ResponseDataResult<HashMap<String, String>>),
     SortSelected(String),
+    SearchChanged(String),
+    PublisherFilterChanged(String),
+    CreatorFilterChanged(String),
+    CheckedFromChanged(String),
+    CheckedToChanged(String),
+    UsdMinChanged(String),
+    TagSelected(String),
+    TagMinScoreChanged(String),
+    UsdMaxChanged(String),
+    ShowExpiringSoon,
+    ApplySearch(u32),
+    StatusFilterSelected(MarketStatusType),
+    ToggleStatusFilter(MarketStatusType),
+    ClearFilters,
+    ResetView,
+    ResetSettings,
+    ToggleSettings,
+    ToggleDebug,
+    DownloadSnapshot,
+    DaiPrecisionSelected(String),
+    AmountUnitSelected(String),
+    ToggleShowEnded,
+    ToggleLocalTime,
+    TogglePaidBars,
+    ToggleRateLimits,
+    ToggleCompactDai,
+    ToggleGroupDuplicates,
+    ToggleExpandSearchMatches,
+    ToggleCheckValidators,
+    ToggleTrimBalances,
+    ToggleGroup(String),
+    ToggleFreezeOrder,
+    TogglePresentation,
+    ToggleHideUnpaid,
+    CopyMarkdown,
+    CopyShareLink,
+    ExportAllCsv,
+    ExportAllJson,
+    OpenRawJson(String),
+    CloseRawJson,
+    CopyRawJson,
+    ToggleCompare(String),
+    OpenCompare,
+    CloseCompare,
+    ToggleStatusLegend,
+    EnvironmentSelected(String),
+    CustomMarketUrlChanged(String),
+    DataScopeSelected(String),
+    IpfsGatewaySelected(String),
+    CustomIpfsGatewayChanged(String),
+    LoadPreviews(Vec<String>),
+    PreviewFinished(String, bool),
 }
 
 fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
+    if model.log_updates {
+        debug::log_msg(&msg);
+    }
+    let rematch = changes_what_is_shown(&msg);
     match msg {
         Msg::Load(load_action) => {
             // Do not render
             orders.skip();
+            model.previews.cancel_pending();
+            model.full_channel = None;
             // Perform the effects
-            load_action.perform_effects(orders);
+            load_action.perform_effects(model, orders);
             // This can be used on refresh
             model.load_action = load_action;
         }
-        Msg::Refresh => {
+        Msg::Tick(now) => {
+            let effects = tick(model, now);
+            if effects.refresh {
+                model.load_action.clone().perform_effects(model, orders);
+            }
+            if let Some(skip) = effects.retry_page {
+                let market_url = model.settings.env.config().market_url;
+                orders.perform_cmd(fetch_campaigns_page(&market_url, skip, model));
+            }
+            if !effects.render {
+                orders.skip();
+            }
+        }
+        Msg::ConnectivityChanged(online) => {
+            model.offline = !online;
+            if online {
+                // Catch up right away instead of waiting for the next poll
+                model.load_action.clone().perform_effects(model, orders);
+            }
+        }
+        Msg::ManualRefresh => {
+            if manual_refresh(model, js_sys::Date::now()) {
+                model.load_action.clone().perform_effects(model, orders);
+            }
+        }
+        Msg::ToggleDebug => model.debug_open = !model.debug_open,
+        Msg::DownloadSnapshot => {
             orders.skip();
-            model.load_action.perform_effects(orders);
+            debug::download_snapshot(model);
+        }
+        Msg::Fetched(generation, msg) => {
+            if generation == model.load_generation {
+                update(*msg, model, orders);
+            } else {
+                // Response from a previous environment, drop it
+                orders.skip();
+            }
         }
         Msg::BalanceLoaded(Ok(resp)) => model.balance = Ready(resp),
         Msg::BalanceLoaded(Err(reason)) => log!("BalanceLoaded error:", reason),
-        Msg::ChannelsLoaded(Ok(channels)) => {
-            model.market_channels = Ready(channels);
+        Msg::ChannelsLoaded(channels) => {
+            let (channels, dropped) = data_quality::dedupe_by_id(channels);
+            if dropped > 0 {
+                log!("Dropped channels with a duplicate id:", dropped);
+                // The pages no longer line up with what we keep, so don't reuse any of them next time
+                model.page_etags.clear();
+                model.page_hashes.clear();
+            }
+            if model.settings.check_validators {
+                check_validators(&channels, model.load_generation, orders);
+            }
+            model.previous_totals = model.totals.replace(Totals::of(&channels));
+            if model.previous_totals.is_some() {
+                model.deltas_until = Some(js_sys::Date::now() + DELTA_FADE_MS);
+            }
+            if let Some(totals) = &model.totals {
+                model.paid_history.push(js_sys::Date::now(), totals.paid.clone());
+            }
+            model.tag_index = tags::TagIndex::build(&channels);
+            model.burn_rates.update(&channels, js_sys::Date::now());
+            set_channels(model, channels);
+            model.channels_partial = false;
+            model.refreshing = false;
+            model.last_channel_count = Some(channel_count(model));
+            model.last_loaded = (js_sys::Date::now() as i64) / 1000;
+            model.last_fetch_duration_ms = Some(js_sys::Date::now() - model.last_fetch_started);
+            model.last_success = Some(js_sys::Date::now());
+            model.consecutive_failures = 0;
+            model.load_error = None;
+            load_validator_report(model, orders);
+            load_heartbeats(model, orders);
+        }
+        Msg::ChannelsUnchanged => {
+            // Still a data point: nothing was paid since the last one, so no channel is burning
+            // through its deposit any more; that's rendered, everything else stays as it is
+            if let Some(totals) = &model.totals {
+                model.paid_history.push(js_sys::Date::now(), totals.paid.clone());
+            }
+            if let Ready(channels) = &model.market_channels {
+                model.burn_rates.update(channels, js_sys::Date::now());
+            }
+            model.refreshing = false;
             model.last_loaded = (js_sys::Date::now() as i64) / 1000;
+            model.last_fetch_duration_ms = Some(js_sys::Date::now() - model.last_fetch_started);
+            model.last_success = Some(js_sys::Date::now());
+            model.consecutive_failures = 0;
+            model.load_error = None;
+            load_validator_report(model, orders);
+            load_heartbeats(model, orders);
+        }
+        Msg::ChannelsFailed(reason) => {
+            log!("ChannelsLoaded error:", reason.to_string());
+            model.last_fetch_duration_ms = Some(js_sys::Date::now() - model.last_fetch_started);
+            model.consecutive_failures += 1;
+            model.refreshing = false;
+            model.load_error = Some(reason.to_string());
+        }
+        Msg::CampaignsPageLoaded(skip, resp) => campaigns_page_loaded(skip, resp, model, orders),
+        Msg::FullChannelLoaded(Ok(channel)) => {
+            if model.raw_modal_pending.as_ref() == Some(&channel.id) {
+                model.raw_modal_pending = None;
+                model.raw_modal = serde_json::to_string_pretty(&channel).ok();
+            }
+            model.full_channel = Some(channel);
+            if detail_channel(model).is_some() {
+                model.last_loaded = (js_sys::Date::now() as i64) / 1000;
+                model.load_error = None;
+                load_validator_report(model, orders);
+                load_heartbeats(model, orders);
+            }
+        }
+        Msg::FullChannelLoaded(Err(reason)) => {
+            log!("FullChannelLoaded error:", reason);
+            if let ActionLoad::ChannelDetail(_) = model.load_action {
+                model.load_error = Some(fetch_error(&reason).to_string());
+            }
+            model.raw_modal_pending = None;
+        }
+        Msg::ExportPageLoaded(skip, Ok(page)) => {
+            orders.skip();
+            match add_export_page(model, skip, page) {
+                Some(next) => {
+                    let market_url = model.settings.env.config().market_url;
+                    orders.perform_cmd(fetch_export_page(&market_url, next, model));
+                }
+                None => {
+                    if let Some(channels) = model.export_pages.take() {
+                        export::download_json(export::ALL_JSON_FILENAME, &channels);
+                    }
+                }
+            }
+        }
+        Msg::ExportPageLoaded(_, Err(reason)) => {
+            log!("ExportPageLoaded error:", reason);
+            model.export_pages = None;
+            show_copy_notice(model, "Could not export the campaigns".to_owned());
         }
-        Msg::ChannelsLoaded(Err(reason)) => log!("ChannelsLoaded error:", reason),
         Msg::VolumeLoaded(Ok(vol)) => model.volume = Ready(vol),
         Msg::VolumeLoaded(Err(reason)) => log!("VolumeLoaded error:", reason),
         Msg::ImpressionsLoaded(Ok(impressions)) => model.impressions = Ready(impressions),
@@ -181,79 +880,1133 @@ fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
         Msg::DailyImpressionsLoaded(Err(reason)) => log!("DailyImpressionsLoaded error:", reason),
         Msg::YearlyImpressionsLoaded(Ok(impressions)) => model.yearly_impressions = Ready(impressions),
         Msg::YearlyImpressionsLoaded(Err(reason)) => log!("YearlyImpressionsLoaded error:", reason),
-        Msg::SortSelected(sort_name) => model.sort = sort_name.into(),
+        Msg::SlotsLoaded(Ok(slots)) => {
+            model.slots = Ready(slots);
+            model.load_error = None;
+            model.last_loaded = (js_sys::Date::now() as i64) / 1000;
+        }
+        Msg::SlotsLoaded(Err(reason)) => {
+            log!("SlotsLoaded error:", reason);
+            model.load_error = Some(fetch_error(&reason).to_string());
+        }
+        Msg::ValidatorChecked(url, Some(reachable)) => {
+            model.validator_health.insert(url, reachable);
+        }
+        Msg::ValidatorChecked(url, None) => {
+            model.validator_health.remove(&url);
+        }
+        Msg::ValidatorReportLoaded(id, resp) => {
+            let report = match resp {
+                Ok(resp) => ValidatorReport::Loaded(resp),
+                Err(reason) => {
+                    let reason = match reason {
+                        fetch::FailReason::Status(status, _) => {
+                            format!("HTTP {} {}", status.code, status.text)
+                        }
+                        fetch::FailReason::DataSerialization(..) => "unexpected response".to_owned(),
+                        _ => "no response".to_owned(),
+                    };
+                    log!("ValidatorReportLoaded error:", reason);
+                    ValidatorReport::Unreachable(reason)
+                }
+            };
+            model.validator_report = Some((id, report));
+        }
+        Msg::HeartbeatLoaded(id, role, resp) => {
+            if let Some((shown, heartbeats)) = &mut model.heartbeats {
+                if *shown == id {
+                    *heartbeats.get_mut(role) = match resp {
+                        Ok(resp) => heartbeats::latest(&resp),
+                        Err(fetch::FailReason::Status(status, _)) if status.code == 404 => {
+                            Heartbeat::Unavailable("not published".to_owned())
+                        }
+                        Err(fetch::FailReason::Status(status, _)) => {
+                            Heartbeat::Unavailable(format!("HTTP {}", status.code))
+                        }
+                        Err(_) => Heartbeat::Unavailable("no response".to_owned()),
+                    };
+                }
+            }
+        }
+        Msg::LoadActivity(id) => load_activity(model, id, orders),
+        Msg::ActivityLoaded(id, resp) => {
+            let activity = match resp {
+                Ok(aggregates) => ChannelActivity::Loaded(aggregates),
+                Err(reason) => {
+                    log!("ActivityLoaded error:", reason);
+                    ChannelActivity::Unavailable(match reason {
+                        fetch::FailReason::Status(status, _) if status.code == 401 || status.code == 403 => {
+                            "The leader only shares event aggregates with authenticated users".to_owned()
+                        }
+                        fetch::FailReason::Status(status, _) if status.code == 404 => {
+                            "The leader doesn't publish event aggregates for this channel".to_owned()
+                        }
+                        fetch::FailReason::Status(status, _) => {
+                            format!("The leader answered HTTP {} {}", status.code, status.text)
+                        }
+                        fetch::FailReason::DataSerialization(..) => {
+                            "The leader's event aggregates are in a format we don't understand".to_owned()
+                        }
+                        _ => "The leader could not be reached".to_owned(),
+                    })
+                }
+            };
+            model.activity = Some((id, activity));
+        }
+        Msg::LoadLabels(url) => {
+            orders.perform_cmd(
+                Request::new(url)
+                    .method(Method::Get)
+                    .fetch_json_data(Msg::LabelsLoaded),
+            );
+        }
+        Msg::LabelsLoaded(Ok(user_labels)) => model.labels.set_user(user_labels),
+        Msg::LabelsLoaded(Err(reason)) => log!("LabelsLoaded error:", reason),
+        Msg::SortSelected(sort_name) => {
+            model.settings.sort = sort_name.into();
+            model.frozen_order = None;
+            settings::save_settings(&model.settings);
+        }
+        Msg::CopyMarkdown => {
+            if let Ready(channels) = &model.market_channels {
+                let all = channels.iter().collect::<Vec<_>>();
+                let visible = visible_channels(model, &in_scope_channels(model, &all));
+                let markdown = export::channels_markdown(
+                    &visible,
+                    &model.labels,
+                    model.settings.amount_unit,
+                    model.settings.dai_precision,
+                    model.last_loaded,
+                );
+                let notice = if clipboard::copy_text(&markdown) {
+                    format!("Copied {} rows", visible.len())
+                } else {
+                    "Could not copy to the clipboard".to_owned()
+                };
+                show_copy_notice(model, notice);
+            }
+        }
+        Msg::ExportAllCsv => {
+            orders.skip();
+            if let Ready(channels) = &model.market_channels {
+                let csv = export::channels_csv(
+                    &channels.iter().collect::<Vec<_>>(),
+                    &model.labels,
+                    model.settings.amount_unit,
+                    model.settings.dai_precision,
+                    model.last_loaded,
+                    model.settings.env.config().dai_addr,
+                );
+                export::download(export::ALL_CSV_FILENAME, "text/csv", &csv);
+            }
+        }
+        Msg::ExportAllJson => {
+            orders.skip();
+            if let Ready(channels) = &model.market_channels {
+                if !channels.iter().any(|c| c.status.trimmed.is_some()) {
+                    export::download_json(export::ALL_JSON_FILENAME, channels);
+                } else if model.export_pages.is_none() {
+                    // The trimmed balances would be exported cut down, so fetch them all again
+                    model.export_pages = Some(Vec::new());
+                    let market_url = model.settings.env.config().market_url;
+                    orders.perform_cmd(fetch_export_page(&market_url, 0, model));
+                }
+            }
+        }
+        Msg::CopyShareLink => {
+            let notice = if clipboard::copy_text(&share_link(model)) {
+                "Link copied"
+            } else {
+                "Could not copy to the clipboard"
+            };
+            show_copy_notice(model, notice.to_owned());
+        }
+        Msg::OpenRawJson(id) => match full_channel(model, &id) {
+            Some(channel) => model.raw_modal = serde_json::to_string_pretty(channel).ok(),
+            // Trimmed; the modal opens once the whole channel is in
+            None => {
+                let market_url = model.settings.env.config().market_url;
+                orders.perform_cmd(fetch_full_channel(&market_url, &id, model.load_generation));
+                model.raw_modal_pending = Some(id);
+            }
+        },
+        Msg::CloseRawJson => {
+            if model.raw_modal.is_none() {
+                orders.skip();
+            }
+            model.raw_modal = None;
+            model.raw_modal_pending = None;
+        }
+        Msg::CopyRawJson => {
+            if let Some(json) = &model.raw_modal {
+                let notice = if clipboard::copy_text(json) {
+                    "Copied".to_owned()
+                } else {
+                    "Could not copy to the clipboard".to_owned()
+                };
+                show_copy_notice(model, notice);
+            }
+        }
+        Msg::ToggleHideUnpaid => model.filters.hide_unpaid = !model.filters.hide_unpaid,
+        Msg::TogglePresentation => {
+            model.presenting = !model.presenting;
+            // Dropping the session stops the scrolling and removes its listeners
+            model.presentation = if model.presenting {
+                Some(presentation::Session::start())
+            } else {
+                None
+            };
+        }
+        Msg::ToggleFreezeOrder => {
+            model.frozen_order = match (&model.frozen_order, &model.market_channels) {
+                (None, Ready(channels)) => Some(
+                    channels
+                        .iter()
+                        .sorted_by(|x, y| compare_channels(model, x, y))
+                        .enumerate()
+                        .map(|(i, channel)| (channel.id.clone(), i))
+                        .collect(),
+                ),
+                _ => None,
+            };
+        }
+        Msg::SearchChanged(search) => {
+            orders.skip();
+            let generation = schedule_search(model, search);
+            orders.perform_cmd(after_timeout(SEARCH_DEBOUNCE_MS, Msg::ApplySearch(generation)));
+        }
+        Msg::PublisherFilterChanged(publisher) => model.filters.publisher = publisher,
+        Msg::CreatorFilterChanged(creator) => model.filters.creator = creator,
+        Msg::CheckedFromChanged(date) => model.filters.set_checked_from(date),
+        Msg::CheckedToChanged(date) => model.filters.set_checked_to(date),
+        Msg::UsdMinChanged(usd) => model.filters.set_usd_min(usd),
+        Msg::TagSelected(tag) => {
+            model.filters.tag = tag;
+            model.filters.tag_min_score = 0.0;
+        }
+        Msg::TagMinScoreChanged(score) => {
+            model.filters.tag_min_score = score.parse().unwrap_or(0.0);
+        }
+        Msg::UsdMaxChanged(usd) => model.filters.set_usd_max(usd),
+        Msg::ShowExpiringSoon => {
+            let horizon = Duration::days(EXPIRING_SOON_DAYS).num_seconds();
+            model.filters.expires_between = Some((model.last_loaded, model.last_loaded + horizon));
+        }
+        Msg::ApplySearch(generation) => {
+            if !apply_search(model, generation) {
+                orders.skip();
+            }
+        }
+        Msg::StatusFilterSelected(status) => {
+            model.filters.status = std::iter::once(status).collect();
+        }
+        Msg::ToggleStatusFilter(status) => {
+            if !model.filters.status.remove(&status) {
+                model.filters.status.insert(status);
+            }
+        }
+        Msg::ClearFilters => {
+            model.filters = Filters::default();
+            model.search_input.clear();
+        }
+        Msg::ResetView => {
+            model.filters = Filters::default();
+            model.search_input.clear();
+            model.expanded_groups.clear();
+            model.search_expanded.clear();
+            model.expanded_channels.clear();
+            model.compare.clear();
+            model.compare_open = false;
+            model.frozen_order = None;
+            model.settings.sort = ChannelSort::default();
+            settings::save_settings(&model.settings);
+        }
+        Msg::ResetSettings => {
+            settings::clear_settings();
+            let previous_scope = model.settings.data_scope;
+            let was_trimmed = model.settings.trim_balances;
+            // Keep the environment for now, so switching back to the default one reloads the data
+            model.settings = Settings {
+                env: model.settings.env.clone(),
+                ..Settings::default()
+            };
+            model.validator_health.clear();
+            if model.settings.env != Environment::default() {
+                switch_environment(Environment::default(), model, orders);
+            } else if model.settings.data_scope != previous_scope
+                // Like turning trimming off by hand: the dropped earners only come back with a
+                // full reload, which also forgets the ETags of the trimmed pages
+                || (was_trimmed && !model.settings.trim_balances)
+            {
+                reload_from_scratch(model, orders);
+            }
+        }
+        Msg::ToggleSettings => model.settings_open = !model.settings_open,
+        Msg::DaiPrecisionSelected(precision) => {
+            if let Ok(precision) = precision.parse::<u8>() {
+                model.settings.dai_precision = precision.min(MAX_DAI_PRECISION);
+                settings::save_settings(&model.settings);
+            }
+        }
+        Msg::AmountUnitSelected(unit) => {
+            model.settings.amount_unit = unit.into();
+            settings::save_settings(&model.settings);
+        }
+        Msg::ToggleShowEnded => {
+            model.settings.show_ended = !model.settings.show_ended;
+            settings::save_settings(&model.settings);
+        }
+        Msg::ToggleLocalTime => {
+            model.settings.local_time = !model.settings.local_time;
+            settings::save_settings(&model.settings);
+        }
+        Msg::TogglePaidBars => {
+            model.settings.paid_bars = !model.settings.paid_bars;
+            settings::save_settings(&model.settings);
+        }
+        Msg::ToggleRateLimits => {
+            model.settings.show_rate_limits = !model.settings.show_rate_limits;
+            settings::save_settings(&model.settings);
+        }
+        Msg::ToggleCompactDai => {
+            model.settings.compact_dai = !model.settings.compact_dai;
+            settings::save_settings(&model.settings);
+        }
+        Msg::ToggleCheckValidators => {
+            model.settings.check_validators = !model.settings.check_validators;
+            settings::save_settings(&model.settings);
+            if !model.settings.check_validators {
+                model.validator_health.clear();
+            } else if let Ready(channels) = &model.market_channels {
+                check_validators(channels, model.load_generation, orders);
+            }
+        }
+        // Trimming works on what's loaded; the dropped earners only come back with a full reload
+        Msg::ToggleTrimBalances => {
+            model.settings.trim_balances = !model.settings.trim_balances;
+            settings::save_settings(&model.settings);
+            if model.settings.trim_balances {
+                model.filters.publisher.clear();
+                if let Ready(channels) = std::mem::replace(&mut model.market_channels, Loading) {
+                    set_channels(model, channels);
+                }
+            } else {
+                reload_from_scratch(model, orders);
+            }
+        }
+        Msg::ToggleGroupDuplicates => {
+            model.settings.group_duplicates = !model.settings.group_duplicates;
+            settings::save_settings(&model.settings);
+        }
+        Msg::ToggleExpandSearchMatches => {
+            model.settings.expand_search_matches = !model.settings.expand_search_matches;
+            settings::save_settings(&model.settings);
+        }
+        Msg::ToggleCompare(id) => {
+            if model.compare.contains(&id) {
+                model.compare.retain(|x| x != &id);
+            } else {
+                if model.compare.len() == 2 {
+                    model.compare.remove(0);
+                }
+                model.compare.push(id);
+            }
+            if model.compare.len() != 2 {
+                model.compare_open = false;
+            }
+        }
+        Msg::OpenCompare => model.compare_open = model.compare.len() == 2,
+        Msg::CloseCompare => model.compare_open = false,
+        Msg::ToggleStatusLegend => model.status_legend_open = !model.status_legend_open,
+        Msg::ToggleGroup(fingerprint) => {
+            // Once toggled by hand, a group is no longer the search's to collapse
+            model.search_expanded.remove(&fingerprint);
+            if !model.expanded_groups.remove(&fingerprint) {
+                model.expanded_groups.insert(fingerprint);
+            }
+        }
+        Msg::EnvironmentSelected(env_name) => {
+            let env = match &env_name as &str {
+                "testnet" => Environment::Testnet,
+                "custom" => Environment::Custom(model.settings.env.config().market_url),
+                _ => Environment::Mainnet,
+            };
+            switch_environment(env, model, orders);
+        }
+        Msg::CustomMarketUrlChanged(url) => {
+            switch_environment(Environment::Custom(url.trim().to_owned()), model, orders);
+        }
+        Msg::DataScopeSelected(scope) => {
+            let scope = match &scope as &str {
+                "active" => DataScope::Active,
+                _ => DataScope::All,
+            };
+            if scope != model.settings.data_scope {
+                model.settings.data_scope = scope;
+                settings::save_settings(&model.settings);
+                // Blanks whatever was loaded under the old scope, so none of it shows under the
+                // new label while the reload runs
+                reload_from_scratch(model, orders);
+            }
+        }
+        Msg::IpfsGatewaySelected(name) => {
+            model.settings.ipfs_gateway = match &name as &str {
+                "cloudflare" => ipfs::Gateway::Cloudflare,
+                "adex" => ipfs::Gateway::Adex,
+                "custom" => ipfs::Gateway::Custom(model.settings.ipfs_gateway.base_url()),
+                _ => ipfs::Gateway::IpfsIo,
+            };
+            settings::save_settings(&model.settings);
+        }
+        Msg::CustomIpfsGatewayChanged(url) => {
+            model.settings.ipfs_gateway = ipfs::Gateway::Custom(url.trim().to_owned());
+            settings::save_settings(&model.settings);
+        }
+        Msg::LoadPreviews(srcs) => {
+            for src in srcs {
+                model.previews.request(src);
+            }
+        }
+        Msg::PreviewFinished(src, ok) => model.previews.finished(&src, ok),
+    }
+
+    if rematch {
+        expand_search_matches(model);
     }
+    save_filters_if_changed(model);
+    write_health_marker(model);
+    write_status_footer(model);
+    write_document_title(model);
 }
 
-// View
-fn view(model: &Model) -> Node<Msg> {
-    let channels = match &model.market_channels {
-        Loading => return h2!["Loading..."],
-        Ready(c) => c,
+// Lets a wall monitor show at a glance whether the data can be trusted
+fn write_status_footer(model: &Model) {
+    let compact = |ms: f64| duration::compact(Duration::milliseconds(ms.max(0.0) as i64));
+    let mut parts = vec![(
+        match model.last_success {
+            Some(at) => format!("Last updated {} ago", compact(model.now - at)),
+            None => "Not loaded yet".to_owned(),
+        },
+        "",
+    )];
+    if model.consecutive_failures > 0 {
+        parts.push((
+            format!(
+                "{} failed refresh{}",
+                model.consecutive_failures,
+                if model.consecutive_failures == 1 { "" } else { "es" }
+            ),
+            "failures",
+        ));
+    }
+    parts.push((
+        if model.offline {
+            "Refresh paused while offline".to_owned()
+        } else {
+            format!("Next refresh in {}", compact(model.next_refresh_at - model.now))
+        },
+        "",
+    ));
+    status_footer::write(&parts);
+}
+
+// The name of the channel on its detail page, so tabs and bookmarks can be told apart
+fn write_document_title(model: &Model) {
+    let title = match detail_channel(model) {
+        Some(channel) => format!("{} · {}", channel.display_name(&model.labels), DOCUMENT_TITLE),
+        None => DOCUMENT_TITLE.to_owned(),
     };
+    if seed::document().title() != title {
+        seed::document().set_title(&title);
+    }
+}
 
-    let channels_dai = channels
-        .iter();
-        // disabled cause of the SAI to DAI migration
-        // .filter(|MarketChannel { deposit_asset, .. }| deposit_asset == DAI_ADDR);
+fn write_health_marker(model: &Model) {
+    let (state, count) = match (&model.market_channels, &model.load_error) {
+        (_, Some(_)) => (
+            HealthState::Error,
+            match &model.market_channels {
+                Ready(channels) => channels.len(),
+                Loading => 0,
+            },
+        ),
+        (Ready(channels), None) => (HealthState::Loaded, channels.len()),
+        (Loading, None) => (HealthState::Loading, 0),
+    };
+    health::write_marker(state, count);
+}
 
-    let total_paid = channels_dai.clone().map(|x| x.status.balances_sum()).sum();
-    let total_deposit = channels_dai
-        .clone()
-        .map(|MarketChannel { deposit_amount, .. }| deposit_amount)
-        .sum();
+fn set_channels(model: &mut Model, mut channels: Vec<MarketChannel>) {
+    if model.settings.trim_balances {
+        for channel in channels.iter_mut() {
+            channel.status.trim(TRIMMED_BALANCES_KEPT);
+        }
+    }
+    model.channel_stats = channels
+        .iter()
+        .map(|c| (c.id.clone(), ChannelStats::from_status(&c.status)))
+        .collect();
+    model.duplicates = data_quality::find_duplicates(&channels);
+    model.owner_mismatches = data_quality::find_owner_mismatches(&channels);
+    model.ad_unit_text = channels
+        .iter()
+        .map(|c| (c.id.clone(), filters::ad_unit_text(c)))
+        .collect();
+    model.rate_limits = channels
+        .iter()
+        .filter_map(|c| Some((c.id.clone(), rate_limits::channel_summary(c)?)))
+        .collect();
+    model.market_channels = Ready(channels);
+    expand_search_matches(model);
+}
 
-    let unique_units = &channels
+// Keeps what was typed and returns the generation its debounced ApplySearch has to carry
+// Any previously scheduled ApplySearch becomes stale
+fn schedule_search(model: &mut Model, search: String) -> u32 {
+    model.search_input = search;
+    model.search_generation += 1;
+    model.search_generation
+}
+
+// Applies the typed search, unless more was typed after the keystroke that scheduled it
+fn apply_search(model: &mut Model, generation: u32) -> bool {
+    if generation != model.search_generation {
+        return false;
+    }
+    model.filters.search = model.search_input.clone();
+    expand_search_matches(model);
+    true
+}
+
+// Whatever changes which channels are in the table; the search matches are expanded again after it
+fn changes_what_is_shown(msg: &Msg) -> bool {
+    match msg {
+        Msg::Load(_)
+        | Msg::PublisherFilterChanged(_)
+        | Msg::CreatorFilterChanged(_)
+        | Msg::CheckedFromChanged(_)
+        | Msg::CheckedToChanged(_)
+        | Msg::UsdMinChanged(_)
+        | Msg::UsdMaxChanged(_)
+        | Msg::TagSelected(_)
+        | Msg::TagMinScoreChanged(_)
+        | Msg::StatusFilterSelected(_)
+        | Msg::ToggleStatusFilter(_)
+        | Msg::ClearFilters
+        | Msg::ToggleHideUnpaid
+        | Msg::ToggleShowEnded
+        | Msg::ToggleGroupDuplicates
+        | Msg::ToggleExpandSearchMatches => true,
+        _ => false,
+    }
+}
+
+// Collapses what was expanded for the previous search, then, if the option is on, expands what
+// the table shows of the new one: the duplicate groups it matches more than one campaign of, and
+// the detail row of every other match; groups the user expanded are left alone
+fn expand_search_matches(model: &mut Model) {
+    for fingerprint in model.search_expanded.drain() {
+        model.expanded_groups.remove(&fingerprint);
+    }
+    model.expanded_channels.clear();
+    if !model.settings.expand_search_matches || model.filters.search.trim().is_empty() {
+        return;
+    }
+    let channels = match &model.market_channels {
+        Ready(channels) => channels.iter().collect::<Vec<_>>(),
+        Loading => return,
+    };
+    // In table order, so the cap leaves the ones further down collapsed
+    let matched = visible_channels(model, &in_scope_channels(model, &channels))
         .iter()
-        .flat_map(|x| &x.spec.ad_units)
-        .map(|x| &x.ipfs)
-        .collect::<HashSet<_>>();
+        .map(|c| (c.fingerprint(), c.id.clone()))
+        .collect::<Vec<_>>();
+    let mut counts = HashMap::<&str, usize>::new();
+    for (fingerprint, _) in &matched {
+        *counts.entry(fingerprint.as_str()).or_insert(0) += 1;
+    }
+    let grouped = model.settings.group_duplicates;
+    let mut seen = HashSet::<&str>::new();
+    let mut expanded = 0;
+    for (fingerprint, id) in &matched {
+        if expanded == SEARCH_EXPANDED_MAX {
+            break;
+        }
+        if !grouped || counts[fingerprint.as_str()] == 1 {
+            model.expanded_channels.insert(id.clone());
+            expanded += 1;
+        } else if seen.insert(fingerprint.as_str()) && !model.expanded_groups.contains(fingerprint) {
+            model.expanded_groups.insert(fingerprint.clone());
+            model.search_expanded.insert(fingerprint.clone());
+            expanded += 1;
+        }
+    }
+}
 
-    let unique_publishers = channels_dai
-        .clone()
-        .flat_map(|x| {
-            x.status
-                .balances
-                .keys()
-                .map(|k| k.to_lowercase())
-                .filter(|k| *k != x.creator.to_lowercase())
-                .collect::<Vec<_>>()
-        })
-        .collect::<HashSet<_>>();
+fn channel_count(model: &Model) -> usize {
+    match &model.market_channels {
+        Ready(channels) => channels.len(),
+        Loading => 0,
+    }
+}
 
-    let unique_advertisers = channels_dai
-        .clone()
+// On the detail page of an unhealthy channel, asks its leader for the latest validator messages
+fn load_validator_report(model: &mut Model, orders: &mut impl Orders<Msg>) {
+    let channel = match detail_channel(model) {
+        Some(channel) if channel.status.status_type.is_unhealthy() => channel,
+        _ => {
+            model.validator_report = None;
+            return;
+        }
+    };
+    let id = channel.id.clone();
+    let base = match validator::normalize_url(&channel.spec.validators.leader().url).base {
+        Some(base) => base,
+        None => {
+            let reason = "invalid leader URL".to_owned();
+            model.validator_report = Some((id, ValidatorReport::Unreachable(reason)));
+            return;
+        }
+    };
+    // Keep showing the previous report for this channel while refreshing it
+    if model.validator_report.as_ref().map_or(true, |(shown, _)| *shown != id) {
+        model.validator_report = Some((id.clone(), ValidatorReport::Loading));
+    }
+    let generation = model.load_generation;
+    orders.perform_cmd(
+        Request::new(format!("{}/channel/{}/validator-messages", base, id))
+            .method(Method::Get)
+            .fetch_json_data(move |resp| {
+                Msg::Fetched(generation, Box::new(Msg::ValidatorReportLoaded(id, resp)))
+            }),
+    );
+}
+
+// On the detail page, asks each validator's sentry for the latest heartbeat it signed
+fn load_heartbeats(model: &mut Model, orders: &mut impl Orders<Msg>) {
+    let channel = match detail_channel(model) {
+        Some(channel) => channel,
+        None => {
+            model.heartbeats = None;
+            return;
+        }
+    };
+    let id = channel.id.clone();
+    let validators = [
+        (ValidatorRole::Leader, channel.spec.validators.leader()),
+        (ValidatorRole::Follower, channel.spec.validators.follower()),
+    ];
+    let requests = validators
+        .iter()
+        .map(|(role, validator)| {
+            (*role, validator::normalize_url(&validator.url).base, validator.id.clone())
+        })
+        .collect::<Vec<_>>();
+    // Keep showing the previous ages for this channel while refreshing them
+    if model.heartbeats.as_ref().map_or(true, |(shown, _)| *shown != id) {
+        let heartbeats = Heartbeats {
+            leader: Heartbeat::Loading,
+            follower: Heartbeat::Loading,
+        };
+        model.heartbeats = Some((id.clone(), heartbeats));
+    }
+    let generation = model.load_generation;
+    for (role, base, validator_id) in requests {
+        let base = match base {
+            Some(base) => base,
+            None => {
+                if let Some((_, heartbeats)) = &mut model.heartbeats {
+                    *heartbeats.get_mut(role) = Heartbeat::Unavailable("invalid URL".to_owned());
+                }
+                continue;
+            }
+        };
+        let id = id.clone();
+        orders.perform_cmd(
+            Request::new(format!(
+                "{}/channel/{}/validator-messages/{}/Heartbeat?limit=1",
+                base, id, validator_id
+            ))
+            .method(Method::Get)
+            .fetch_json_data(move |resp| {
+                Msg::Fetched(generation, Box::new(Msg::HeartbeatLoaded(id, role, resp)))
+            }),
+        );
+    }
+}
+
+// The leader's event aggregates for the last activity::HOURS hours
+fn load_activity(model: &mut Model, id: String, orders: &mut impl Orders<Msg>) {
+    let base = match &model.market_channels {
+        Ready(channels) => channels
+            .iter()
+            .find(|channel| channel.id == id)
+            .and_then(|channel| validator::normalize_url(&channel.spec.validators.leader().url).base),
+        Loading => None,
+    };
+    let base = match base {
+        Some(base) => base,
+        None => {
+            let reason = "The leader's URL is invalid".to_owned();
+            model.activity = Some((id, ChannelActivity::Unavailable(reason)));
+            return;
+        }
+    };
+    let after = js_sys::Date::now() as i64 - activity::HOURS * 60 * 60 * 1000;
+    model.activity = Some((id.clone(), ChannelActivity::Loading));
+    let generation = model.load_generation;
+    orders.perform_cmd(
+        Request::new(format!("{}/channel/{}/events-aggregates?after={}", base, id, after))
+            .method(Method::Get)
+            .fetch_json_data(move |resp| {
+                Msg::Fetched(generation, Box::new(Msg::ActivityLoaded(id, resp)))
+            }),
+    );
+}
+
+// Pings every distinct validator once; the result is kept until the next refresh
+fn check_validators(channels: &[MarketChannel], generation: u32, orders: &mut impl Orders<Msg>) {
+    // Keyed by the canonical base, so spelling variants of one validator are pinged once
+    let bases = channels
+        .iter()
+        .filter(|c| !is_misconfigured(c))
+        .flat_map(|c| vec![&c.spec.validators.leader().url, &c.spec.validators.follower().url])
+        .filter_map(|url| validator::normalize_url(url).base)
+        .collect::<HashSet<_>>();
+    for base in bases {
+        orders.perform_cmd(
+            Request::new(format!("{}/cfg", base))
+                .method(Method::Get)
+                .fetch_string_data(move |resp| {
+                    let reachable = match resp {
+                        Ok(_) => Some(true),
+                        Err(fetch::FailReason::Status(..)) => Some(false),
+                        // Network and CORS failures look the same: we just don't know
+                        Err(_) => None,
+                    };
+                    Msg::Fetched(generation, Box::new(Msg::ValidatorChecked(base, reachable)))
+                }),
+        );
+    }
+}
+
+// The current page, with the sort and the filters in the query, as `restore_view` reads them
+fn share_link(model: &Model) -> String {
+    let href = seed::window().location().href().unwrap_or_default();
+    share_link_from(&href, model)
+}
+
+// Other query parameters (like ?debug) and the hash are left out, they're not meant to be passed on
+fn share_link_from(href: &str, model: &Model) -> String {
+    let page = href.split(|c| c == '?' || c == '#').next().unwrap_or_default();
+    let mut pairs = vec![(SORT_PARAM, sort_name(model.settings.sort).to_owned())];
+    pairs.extend(model.filters.to_query());
+    format!("{}?{}", page, query::encode(&pairs))
+}
+
+// Brings back what a share link had on the screen; `search` is the query string it was opened with
+// Without one, the filters saved on the last visit stay
+// Returns what couldn't be understood, to be logged
+// The sort goes into the settings without saving them, so it's only kept if they're saved anyway
+fn restore_view(model: &mut Model, search: &str) -> Vec<String> {
+    let pairs = query::parse(search);
+    let (filters, warnings) = Filters::from_query(&pairs);
+    let sort = pairs.iter().find(|(name, _)| name == SORT_PARAM);
+    if sort.is_some() || !filters.to_query().is_empty() || !warnings.is_empty() {
+        model.filters = filters;
+    }
+    if let Some((_, name)) = sort {
+        model.settings.sort = name.clone().into();
+    }
+    model.search_input = model.filters.search.clone();
+    warnings
+}
+
+fn save_filters_if_changed(model: &mut Model) {
+    let query = query::encode(&model.filters.to_query());
+    if query != model.saved_filters {
+        settings::save_filters(&model.filters);
+        model.saved_filters = query;
+    }
+}
+
+fn show_copy_notice(model: &mut Model, notice: String) {
+    model.copy_notice = Some(notice);
+    model.copy_notice_until = Some(model.now + COPY_NOTICE_MS);
+}
+
+// What a Tick has to do besides moving the model to `now`
+#[derive(Debug, PartialEq)]
+struct TickEffects {
+    // Time for the periodic refresh
+    refresh: bool,
+    // The failed page at this skip is due to be fetched again
+    retry_page: Option<usize>,
+    // Usually only the footer changes, and that's written outside of the view
+    render: bool,
+}
+
+// Everything a Tick does to the model, apart from the orders, so it can be tested with any `now`
+fn tick(model: &mut Model, now: f64) -> TickEffects {
+    let timely_before = timely_text(model);
+    let before = model.now;
+    model.now = now;
+    let expired = expire_notices(model);
+    let mut refresh = false;
+    if now >= model.next_refresh_at {
+        model.next_refresh_at = now + f64::from(REFRESH_MS);
+        refresh = !model.offline;
+    }
+    let retry_page = match model.page_retry_at {
+        Some((skip, at)) if now >= at => {
+            model.page_retry_at = None;
+            Some(skip)
+        }
+        _ => None,
+    };
+    TickEffects {
+        refresh,
+        retry_page,
+        render: expired || timely_text(model) != timely_before || ages_changed(before, now),
+    }
+}
+
+// False when it's too soon after the last fetch, and only the throttle hint shows
+// A load that's retrying a page is given up on, so the refresh starts over right away rather than
+// doing nothing until the retries are through
+fn manual_refresh(model: &mut Model, now: f64) -> bool {
+    if now - model.last_fetch_started < MANUAL_REFRESH_MIN_MS {
+        model.throttle_hint_until = Some(model.now + THROTTLE_HINT_MS);
+        return false;
+    }
+    model.throttle_hint_until = None;
+    if model.campaigns_paging.is_some() && model.page_retries > 0 {
+        // Whatever the retried page was going to bring back is dropped
+        model.load_generation += 1;
+        model.campaigns_paging = None;
+        model.page_retries = 0;
+        model.page_retry_at = None;
+    }
+    // The next automatic refresh counts from this one, rather than following right after it
+    model.next_refresh_at = model.now + f64::from(REFRESH_MS);
+    true
+}
+
+// Takes down whatever notice is past its time; true if anything was
+fn expire_notices(model: &mut Model) -> bool {
+    let now = model.now;
+    let mut expired = false;
+    for until in &mut [
+        &mut model.throttle_hint_until,
+        &mut model.copy_notice_until,
+        &mut model.deltas_until,
+    ] {
+        if until.map_or(false, |until| now >= until) {
+            **until = None;
+            expired = true;
+        }
+    }
+    if model.copy_notice_until.is_none() {
+        model.copy_notice = None;
+    }
+    expired
+}
+
+// Resolves to `msg` after `ms` milliseconds
+// Only for delays well under a Tick, like the search debounce; anything longer goes through Tick
+fn after_timeout(ms: i32, msg: Msg) -> impl Future<Item = Msg, Error = Msg> {
+    let promise = js_sys::Promise::new(&mut |resolve, _| {
+        seed::window()
+            .set_timeout_with_callback_and_timeout_and_arguments_0(&resolve, ms)
+            .expect("set timeout");
+    });
+    JsFuture::from(promise).then(move |_| -> Result<Msg, Msg> { Ok(msg) })
+}
+
+fn switch_environment(env: Environment, model: &mut Model, orders: &mut impl Orders<Msg>) {
+    if env == model.settings.env {
+        return;
+    }
+    model.settings.env = env;
+    settings::save_settings(&model.settings);
+    reload_from_scratch(model, orders);
+}
+
+// Clear everything loaded from the previous environment or data scope and start over
+// The page is blanked until the new campaigns come in, so the old ones are never shown under the
+// new network's name
+fn reload_from_scratch(model: &mut Model, orders: &mut impl Orders<Msg>) {
+    model.load_generation += 1;
+    model.load_error = None;
+    model.campaigns_paging = None;
+    model.page_retry_at = None;
+    model.channels_partial = false;
+    model.last_channel_count = None;
+    model.market_channels = Loading;
+    model.channel_stats.clear();
+    model.duplicates.clear();
+    model.owner_mismatches.clear();
+    model.ad_unit_text.clear();
+    model.rate_limits.clear();
+    model.tag_index = tags::TagIndex::default();
+    model.validator_report = None;
+    model.heartbeats = None;
+    model.activity = None;
+    model.compare.clear();
+    model.compare_open = false;
+    model.frozen_order = None;
+    model.raw_modal = None;
+    model.raw_modal_pending = None;
+    model.full_channel = None;
+    model.last_success = None;
+    model.consecutive_failures = 0;
+    model.totals = None;
+    model.previous_totals = None;
+    model.paid_history.clear();
+    model.burn_rates.clear();
+    model.deltas_until = None;
+    model.page_etags.clear();
+    model.page_hashes.clear();
+    model.balance = Loading;
+    model.slots = Loading;
+    model.load_action.clone().perform_effects(model, orders);
+}
+
+// View
+fn view(model: &Model) -> Node<Msg> {
+    div![
+        if model.debug_open {
+            debug::debug_panel(model)
+        } else {
+            seed::empty()
+        },
+        offline_banner(model),
+        stale_banner(model),
+        div![
+            class!(if is_stale(model) { "page stale" } else { "page" }),
+            page_view(model)
+        ],
+        match &model.raw_modal {
+            Some(json) => raw_json_modal(json, model.copy_notice.as_ref()),
+            None => seed::empty(),
+        },
+    ]
+}
+
+fn raw_json_modal(json: &str, copy_notice: Option<&String>) -> Node<Msg> {
+    div![
+        class!["modal-backdrop"],
+        div![
+            class!["modal"],
+            div![
+                class!["modal-actions"],
+                button!["Copy", simple_ev(Ev::Click, Msg::CopyRawJson)],
+                match copy_notice {
+                    Some(notice) => span![class!["copy-notice"], notice.as_str()],
+                    None => seed::empty(),
+                },
+                button![
+                    class!["modal-close"],
+                    attrs! { At::Title => "Close (Esc)" },
+                    "×",
+                    simple_ev(Ev::Click, Msg::CloseRawJson)
+                ],
+            ],
+            pre![json],
+        ]
+    ]
+}
+
+// What reads differently as time passes, down to the second: the stale banner and the heartbeats
+// Compared on every Tick, so they're rendered again right when they change, and only then
+type TimelyText = (Option<String>, Option<[(String, bool); 2]>);
+
+fn timely_text(model: &Model) -> TimelyText {
+    let now = Utc.timestamp_millis(model.now as i64);
+    (
+        stale_message(model),
+        model
+            .heartbeats
+            .as_ref()
+            .map(|(_, heartbeats)| heartbeats::ages(&now, heartbeats)),
+    )
+}
+
+// Whether the other ages on the page, like the age column, may read differently at `now` than they did
+// at `before`; they're rendered again once a minute rather than on every Tick
+fn ages_changed(before: f64, now: f64) -> bool {
+    let minute = |at: f64| (at / 60_000.0).floor() as i64;
+    minute(before) != minute(now)
+}
+
+// Whether the last successful load is older than STALE_MS
+// Nothing is stale before the first load, that's just loading
+fn is_stale(model: &Model) -> bool {
+    model
+        .last_success
+        .map_or(false, |at| model.now - at > STALE_MS)
+}
+
+fn stale_banner(model: &Model) -> Node<Msg> {
+    match stale_message(model) {
+        Some(message) => div![class!["stale-banner"], message],
+        None => seed::empty(),
+    }
+}
+
+// Its age moves on with every minute, but not on the minute, see `timely_text`
+fn stale_message(model: &Model) -> Option<String> {
+    match model.last_success {
+        Some(at) if is_stale(model) => Some(format!(
+            "This data is stale: it was last updated {} ({})",
+            duration::humanize_past(Duration::milliseconds((model.now - at) as i64)),
+            timestamps::format(
+                &Utc.timestamp_millis(at as i64),
+                model.settings.local_time,
+                "%H:%M"
+            )
+        )),
+        _ => None,
+    }
+}
+
+fn offline_banner(model: &Model) -> Node<Msg> {
+    if !model.offline {
+        return seed::empty();
+    }
+    div![
+        class!["offline-banner"],
+        match model.last_success {
+            Some(at) => format!(
+                "You are offline — data last updated {}",
+                timestamps::format(
+                    &Utc.timestamp_millis(at as i64),
+                    model.settings.local_time,
+                    "%H:%M"
+                )
+            ),
+            None => "You are offline — no data loaded yet".to_owned(),
+        }
+    ]
+}
+
+fn page_view(model: &Model) -> Node<Msg> {
+    if model.load_action == ActionLoad::Slots {
+        return slots_view(model);
+    }
+
+    if let ActionLoad::ChannelDetail(_) = &model.load_action {
+        return match detail_channel(model) {
+            Some(channel) => channel_detail_view(model, Some(channel)),
+            None if model.load_error.is_none() => h2!["Loading..."],
+            None => div![load_error_banner(model), channel_detail_view(model, None)],
+        };
+    }
+
+    let channels = match &model.market_channels {
+        Loading => return div![load_error_banner(model), h2!["Loading..."]],
+        Ready(c) => c,
+    };
+
+    if model.load_action == ActionLoad::Cohorts {
+        return cohorts::cohorts_view(model, &channels.iter().collect::<Vec<_>>());
+    }
+
+    let channels_dai = channels
+        .iter();
+        // disabled cause of the SAI to DAI migration
+        // .filter(|MarketChannel { deposit_asset, .. }| deposit_asset == DAI_ADDR);
+
+    let total_paid = bignum::sum(channels_dai.clone().map(|x| x.status.balances_sum()));
+    let total_deposit = bignum::sum(
+        channels_dai
+            .clone()
+            .map(|MarketChannel { deposit_amount, .. }| deposit_amount),
+    );
+    let total_unspent = bignum::sum(channels_dai.clone().map(|x| x.unspent().0));
+    // In dollars, each channel's share converted on its own; None if any has no estimate
+    let total_usd = |amount: fn(&MarketChannel) -> BigNum| {
+        channels_dai
+            .clone()
+            .map(|c| channel_usd(c, &amount(c)))
+            .sum::<Option<f64>>()
+    };
+
+    let unique_units = &channels
+        .iter()
+        .flat_map(|x| &x.spec.ad_units)
+        .map(|x| &x.ipfs)
+        .collect::<HashSet<_>>();
+
+    let unique_advertisers = channels_dai
+        .clone()
         .map(|x| x.creator.to_lowercase())
         .collect::<HashSet<_>>();
  
     div![
+        header(model),
+        scope_summary(
+            channels_dai.clone().count(),
+            channels_dai
+                .clone()
+                .filter(|c| !c.deposit_asset.eq_ignore_ascii_case(model.settings.env.config().dai_addr))
+                .count()
+        ),
+        paging_progress(model),
+        refreshing_indicator(model.refreshing),
+        load_error_banner(model),
         // Cards
         card("Campaigns", Ready(channels.len().to_string())),
         card("Ad units", Ready(unique_units.len().to_string())),
-        card("Publishers", Ready(unique_publishers.len().to_string())),
+        card(
+            "Publishers",
+            Ready(at_least(distinct_earners(channels_dai.clone()), channels.iter().any(|c| c.status.trimmed.is_some())))
+        ),
         card("Advertisers", Ready(unique_advertisers.len().to_string())),
         impressions_card("Monthly impressions", &model.impressions),
         impressions_card("Daily impressions", &model.daily_impressions),
         br![],
-        card(
+        card_with_delta(
             "Total campaign deposits",
-            Ready(dai_readable(&total_deposit))
+            amount_value(
+                amount_text(&total_deposit, total_usd(|c| c.deposit_amount.clone()), &model.settings),
+                &total_deposit
+            ),
+            totals_delta(model, |t| &t.deposit),
         ),
-        card("Paid out", Ready(dai_readable(&total_paid))),
-        a![
-            attrs! { At::Href => format!("https://etherscan.io/address/{}#tokentxns", CORE_ADDR) },
-            card(
-                "Locked up on-chain",
-                match &model.balance {
-                    Ready(resp) => Ready(dai_readable(&resp.result)),
-                    Loading => Loading,
-                }
+        card_with_delta(
+            "Paid out",
+            amount_value(
+                amount_text(&total_paid, total_usd(|c| c.status.balances_sum()), &model.settings),
+                &total_paid
             ),
-        ],
+            totals_delta(model, |t| &t.paid),
+        ),
+        card_with_delta(
+            "Impressions paid for (estimated)",
+            span![model
+                .totals
+                .as_ref()
+                .map_or("N/A".to_owned(), |totals| impression_count(&totals.impressions))],
+            impressions_delta(model),
+        ),
+        trailing_paid_card(model),
+        card_with_delta(
+            "Total unspent budget",
+            amount_value(
+                amount_text(&total_unspent, total_usd(|c| c.unspent().0), &model.settings),
+                &total_unspent
+            ),
+            totals_delta(model, |t| &t.unspent),
+        ),
+        locked_up_card(model),
         volume_card(
             "24h volume",
             match &model.volume {
-                Ready(vol) => Ready(dai_readable(&vol.aggr.iter().map(|x| &x.value).sum())),
+                Ready(vol) => {
+                    let volume = bignum::sum(vol.aggr.iter().map(|x| &x.value));
+                    Ready(amount_value(plain_amount_text(&volume, &model.settings), &volume))
+                }
                 Loading => Loading,
             },
             &model.volume
@@ -262,34 +2015,11 @@ fn view(model: &Model) -> Node<Msg> {
         impressions_card("Yearly impressions (transactions)", &model.yearly_impressions),
         // Tables
         if model.load_action == ActionLoad::ChannelsActive || model.load_action == ActionLoad::ChannelsAll {
-            div![
-                select![
-                    attrs! {At::Value => "deposit"},
-                    option![attrs! {At::Value => "deposit"}, "Sort by deposit"],
-                    option![attrs! {At::Value => "status"}, "Sort by status"],
-                    option![attrs! {At::Value => "created"}, "Sort by created"],
-                    input_ev(Ev::Input, Msg::SortSelected)
-                ],
-                channel_table(
-                    model.last_loaded,
-                    &channels_dai
-                        .clone()
-                        .filter(|channel| model.load_action == ActionLoad::ChannelsAll || match channel.status.status_type {
-                            types::MarketStatusType::Expired | types::MarketStatusType::Exhausted => false,
-                            _ => true
-                        })
-                        .sorted_by(|x, y| match model.sort {
-                            ChannelSort::Deposit => y.deposit_amount.cmp(&x.deposit_amount),
-                            ChannelSort::Status => x.status.status_type.cmp(&y.status.status_type),
-                            ChannelSort::Created => y.spec.created.cmp(&x.spec.created),
-                        })
-                        .collect::<Vec<_>>()
-                ),
-            ]
+            channels_section(model, &channels_dai.clone().collect::<Vec<_>>())
         } else {
             seed::empty()
         },
-        ad_unit_stats_table(&channels_dai.clone().collect::<Vec<_>>()),
+        ad_unit_stats_table(&channels_dai.clone().collect::<Vec<_>>(), &model.settings),
         a![
             attrs! { At::Href => "https://platform.adex.network/#/"},
             div![
@@ -307,123 +2037,1863 @@ fn view(model: &Model) -> Node<Msg> {
     ]
 }
 
-fn impressions_card(title: &str, loadable: &types::Loadable<AnalyticsResp>) -> Node<Msg> {
-    volume_card(
-        title,
-        match loadable {
-            Ready(vol) => Ready(
-                vol.aggr
-                    .iter()
-                    .map(|x| &x.value)
-                    .sum::<BigNum>()
-                    .to_u64()
-                    .unwrap_or(0)
-                    .to_formatted_string(&Locale::en)
-            ),
-            Loading => Loading,
-        },
-        loadable
-    )
+fn slots_view(model: &Model) -> Node<Msg> {
+    div![
+        header(model),
+        match &model.slots {
+            Loading => div![load_error_banner(model), h2!["Loading..."]],
+            Ready(slots) => div![
+                card("Ad slots", Ready(slots.len().to_string())),
+                slots_table(model.last_loaded, slots, model.settings.sort, model.settings.local_time),
+            ],
+        }
+    ]
 }
 
-fn card(label: &str, value: Loadable<String>) -> Node<Msg> {
+fn channel_detail_view(model: &Model, channel: Option<&MarketChannel>) -> Node<Msg> {
+    let channel = match channel {
+        Some(channel) => channel,
+        None => return div![header(model), h2!["Channel not found"]],
+    };
+    let paid_total = channel.status.balances_sum();
     div![
-        class!["card"],
-        match value {
-            Loading => div![class!["card-value loading"]],
-            Ready(value) => div![class!["card-value"], value],
+        header(model),
+        h2![
+            match channel.name(&model.labels) {
+                Some(name) => format!("{} ", name),
+                None => format!("Channel {} ", channel.id),
+            },
+            match channel_status_url(channel) {
+                Some(url) => status_json_link(url),
+                None => seed::empty(),
+            }
+        ],
+        if channel.name(&model.labels).is_some() {
+            p![class!["channel-id"], format!("Channel {}", channel.id)]
+        } else {
+            seed::empty()
         },
-        div![class!["card-label"], label],
+        amount_card(
+            "Deposit",
+            amount_value(
+                channel_amount_text(channel, &channel.deposit_amount, &model.settings),
+                &channel.deposit_amount
+            )
+        ),
+        amount_card(
+            "Paid out",
+            amount_value(channel_amount_text(channel, &paid_total, &model.settings), &paid_total)
+        ),
+        card(
+            "Paid - %",
+            Ready(paid_percent(&paid_total, &channel.deposit_amount))
+        ),
+        card(
+            "Paid (USD)",
+            Ready(channel_paid_usd(channel).map_or("N/A".to_owned(), |usd| usd_readable(usd as f32)))
+        ),
+        card("Status", Ready(format!("{:?}", &channel.status.status_type))),
+        match channel.status.status_type {
+            MarketStatusType::Withdraw | MarketStatusType::Expired | MarketStatusType::Exhausted => {
+                let (unspent, clamped) = channel.unspent();
+                let text = channel_amount_text(channel, &unspent, &model.settings);
+                amount_card(
+                    "Withdrawable",
+                    amount_value(if clamped { format!("⚠ {}", text) } else { text }, &unspent),
+                )
+            }
+            _ => seed::empty(),
+        },
+        card(
+            "Top publisher",
+            Ready(
+                match model.channel_stats.get(&channel.id).and_then(|s| {
+                    Some((s.top_earner.as_ref()?, s.top_earner_share()?))
+                }) {
+                    Some(((addr, _), share)) => {
+                        format!("{} with {:.0}%", address_name(&model.labels, addr), share)
+                    }
+                    None => "—".to_owned(),
+                }
+            )
+        ),
+        match &model.heartbeats {
+            Some((id, heartbeats)) if *id == channel.id => {
+                heartbeats::heartbeats_view(&Utc.timestamp_millis(model.now as i64), heartbeats)
+            }
+            _ => seed::empty(),
+        },
+        match &model.validator_report {
+            Some((id, report)) if *id == channel.id => {
+                validator_report::validator_report(model.last_loaded, report, model.settings.local_time)
+            }
+            _ => seed::empty(),
+        },
+        card(
+            "Earner concentration",
+            Ready(match model.channel_stats.get(&channel.id) {
+                Some(stats) if stats.earners > 0 => concentration_label(stats.concentration),
+                _ => "—".to_owned(),
+            })
+        ),
+        p![
+            class!["channel-parties"],
+            "Creator: ",
+            a![
+                attrs! {
+                    At::Href => format!("{}/address/{}", model.settings.env.config().explorer_url, channel.creator);
+                    At::Target => "_blank"
+                },
+                labeled(&model.labels, &channel.creator)
+            ],
+            " ",
+            a![
+                class!["creator-filter"],
+                attrs! { At::Href => "/channels-all"; At::Title => "Show every campaign by this creator" },
+                "all campaigns",
+                simple_ev(Ev::Click, Msg::CreatorFilterChanged(channel.creator.clone()))
+            ],
+            format!(" · Nonce: {}", channel_nonce(channel)),
+            " · Leader: ",
+            labeled(&model.labels, &channel.spec.validators.leader().url),
+            " · Follower: ",
+            labeled(&model.labels, &channel.spec.validators.follower().url),
+        ],
+        view_lifecycle(model.last_loaded, channel, model.settings.local_time),
+        if is_misconfigured(channel) {
+            p![
+                class!["warning"],
+                "A validator URL of this campaign is invalid, so what its validators report can't be checked"
+            ]
+        } else {
+            seed::empty()
+        },
+        if all_units_archived(channel) {
+            p![
+                class!["warning"],
+                "Every ad unit of this campaign has been archived, so it can't serve anything"
+            ]
+        } else {
+            seed::empty()
+        },
+        activity::activity_view(
+            &channel.id,
+            match &model.activity {
+                Some((id, activity)) if *id == channel.id => Some(activity),
+                _ => None,
+            },
+            &Utc.timestamp_millis(model.now as i64)
+        ),
+        ad_unit_previews(model, channel),
     ]
 }
 
-fn volume_chart(vol: &AnalyticsResp) -> Option<Node<Msg>> {
-    let values = vol.aggr.iter().map(|x| &x.value);
-    let min = values.clone().min()?;
-    let max = values.clone().max()?;
-    let range = max - min;
-    let width = 250_u64;
-    let height = 60_u64;
-    let points = values
-        .clone()
-        .map(|v| {
-            (&(v - min) * &height.into())
-                .div_floor(&range)
-                .to_u64()
-                .unwrap_or(0)
-        })
-        .take(vol.aggr.len() - 1)
+fn ad_unit_previews(model: &Model, channel: &MarketChannel) -> Node<Msg> {
+    if channel.spec.ad_units.is_empty() {
+        return seed::empty();
+    }
+    let units = channel
+        .spec
+        .ad_units
+        .iter()
+        .map(|unit| (unit, ipfs::media_url(&unit.media_url, &model.settings.ipfs_gateway)))
         .collect::<Vec<_>>();
-    let len = points.len() as u64;
-    let ratio = width as f64 / (len - 1) as f64;
-    Some(svg![
-        attrs! {
-            At::Style => "position: absolute; right: 0px; left: 0px; bottom: 10px;";
-            At::Width => format!("{}px", width);
-            At::Height => format!("{}px", height);
-            At::ViewBox => format!("0 0 {} {}", width, height);
+    let not_requested = units
+        .iter()
+        .filter_map(|(_, src)| src.clone())
+        .filter(|src| model.previews.state(src) == PreviewState::NotRequested)
+        .collect::<Vec<_>>();
+    div![
+        class!["ad-units"],
+        h3![
+            format!("Ad units ({})", units.len()),
+            match model.previews.in_flight() {
+                0 => seed::empty(),
+                loading => span![class!["previews-loading"], format!(" · loading {}", loading)],
+            }
+        ],
+        if not_requested.is_empty() {
+            seed::empty()
+        } else {
+            button![
+                simple_ev(Ev::Click, Msg::LoadPreviews(not_requested)),
+                "Load all previews"
+            ]
         },
-        polyline![attrs! {
-            At::Fill => "none";
-            At::Custom("stroke".into()) => "#c8dbec";
-            At::Custom("stroke-width".into()) => "4";
-            At::Custom("points".into()) => points
-                .iter()
-                .enumerate()
-                .map(|(i, p)| format!("{},{}", (i as f64 * ratio).ceil(), height-p))
-                .collect::<Vec<_>>()
-                .join(" ")
-        }],
-    ])
+        div![units
+            .iter()
+            .map(|(unit, src)| ad_unit_card(&model.previews, unit, src.as_ref()))
+            .collect::<Vec<_>>()]
+    ]
 }
 
-fn volume_card(card_label: &str, val: Loadable<String>, vol: &Loadable<AnalyticsResp>) -> Node<Msg> {
-    let (card_value, vol) = match (&val, vol) {
-        (Ready(val), Ready(vol)) => (val, vol),
-        _ => return card(card_label, Loading),
+fn ad_unit_card(previews: &previews::Previews, unit: &AdUnit, src: Option<&String>) -> Node<Msg> {
+    let preview = match src {
+        None => preview_placeholder("No preview"),
+        Some(src) => match previews.state(src) {
+            PreviewState::NotRequested => div![
+                class!["preview-placeholder", "clickable"],
+                "Load preview",
+                simple_ev(Ev::Click, Msg::LoadPreviews(vec![src.clone()]))
+            ],
+            PreviewState::Waiting => preview_placeholder("Waiting..."),
+            PreviewState::Failed => preview_placeholder("Preview unavailable"),
+            PreviewState::Loading | PreviewState::Loaded => tracked_preview(unit, src),
+        },
     };
-    match volume_chart(vol) {
-        Some(chart) => div![
-            class!["card chart"],
-            chart,
+    div![
+        class![if unit.archived { "ad-unit archived" } else { "ad-unit" }],
+        preview,
+        p![
+            unit.title.as_ref().map_or("Untitled", |title| title.as_str()),
+            if unit.archived {
+                span![
+                    class!["badge archived"],
+                    attrs! { At::Title => "The owner has archived this ad unit" },
+                    "archived"
+                ]
+            } else {
+                seed::empty()
+            }
+        ],
+        a![
+            attrs! { At::Href => &unit.target_url; At::Target => "_blank" },
+            unit.target_url.as_str()
+        ]
+    ]
+}
+
+fn preview_placeholder(text: &str) -> Node<Msg> {
+    div![class!["preview-placeholder"], text]
+}
+
+// Reports back when it's done, so the next waiting preview can start and a failed one shows the
+// placeholder instead of a broken image
+fn tracked_preview(unit: &AdUnit, src: &str) -> Node<Msg> {
+    let failed = simple_ev(Ev::Error, Msg::PreviewFinished(src.to_owned(), false));
+    if unit.media_mime.starts_with("video/") {
+        video![
+            attrs! { At::Src => src; At::AutoPlay => true; At::Loop => true; At::Muted => true },
+            simple_ev(Ev::LoadedData, Msg::PreviewFinished(src.to_owned(), true)),
+            failed
+        ]
+    } else {
+        img![
+            attrs! { At::Src => src },
+            simple_ev(Ev::Load, Msg::PreviewFinished(src.to_owned(), true)),
+            failed
+        ]
+    }
+}
+
+// Timeline of the channel lifecycle: created -> withdraw period -> expired
+fn view_lifecycle(now_seconds: i64, channel: &MarketChannel, local: bool) -> Node<Msg> {
+    let stages = [
+        ("Created", Some(&channel.spec.created)),
+        ("Withdraw period starts", Some(&channel.spec.withdraw_period_start)),
+        ("Valid until", channel.valid_until.as_ref()),
+    ];
+    ul![
+        class!["lifecycle"],
+        stages
+            .iter()
+            .filter_map(|&(label, time)| Some((label, time?)))
+            .map(|(label, time)| {
+                li![
+                    class!(if time.timestamp() <= now_seconds {
+                        "stage past"
+                    } else {
+                        "stage upcoming"
+                    }),
+                    div![class!["stage-label"], label],
+                    div![
+                        class!["stage-time"],
+                        attrs! { At::Title => timestamps::exact(time, local) },
+                        timestamps::format(time, local, "%Y-%m-%d %H:%M")
+                    ],
+                ]
+            })
+            .collect::<Vec<_>>()
+    ]
+}
+
+// With a retry, which is what the refresh button does
+fn load_error_banner(model: &Model) -> Node<Msg> {
+    match &model.load_error {
+        Some(reason) => div![
+            class!["load-error"],
+            format!("{}: {} ", load_error_subject(&model.load_action), reason),
+            a![
+                class!["retry"],
+                "Retry",
+                simple_ev(Ev::Click, Msg::ManualRefresh)
+            ]
+        ],
+        None => seed::empty(),
+    }
+}
+
+fn load_error_subject(load_action: &ActionLoad) -> &'static str {
+    match load_action {
+        ActionLoad::Slots => "Could not load the ad slots",
+        ActionLoad::ChannelDetail(_) => "Could not load the campaign",
+        _ => "Could not refresh campaigns",
+    }
+}
+
+fn refreshing_indicator(refreshing: bool) -> Node<Msg> {
+    if refreshing {
+        div![class!["refreshing"], "Refreshing…"]
+    } else {
+        seed::empty()
+    }
+}
+
+fn paging_progress(model: &Model) -> Node<Msg> {
+    if !model.channels_partial {
+        return seed::empty();
+    }
+    let loaded = channel_count(model).to_formatted_string(&Locale::en);
+    div![
+        class!["paging-progress"],
+        match model.last_channel_count {
+            Some(expected) => format!(
+                "Loading {} of ~{}…",
+                loaded,
+                expected.to_formatted_string(&Locale::en)
+            ),
+            None => format!("Loading {}…", loaded),
+        }
+    ]
+}
+
+// Campaigns in other assets (mostly SAI, from before the migration) are shown and counted along
+// with the DAI ones, so the header says how many of them there are
+fn scope_summary(shown: usize, non_dai: usize) -> Node<Msg> {
+    div![
+        class!["scope-summary"],
+        format!("Showing {} campaigns", shown.to_formatted_string(&Locale::en)),
+        if non_dai > 0 {
+            format!(" ({} not in DAI)", non_dai.to_formatted_string(&Locale::en))
+        } else {
+            String::new()
+        }
+    ]
+}
+
+fn header(model: &Model) -> Node<Msg> {
+    div![
+        class!["header"],
+        nav_tab("Summary", "/", model.load_action == ActionLoad::Summary),
+        nav_tab("Campaigns", "/channels", model.load_action == ActionLoad::ChannelsActive),
+        nav_tab("By month", "/months", model.load_action == ActionLoad::Cohorts),
+        nav_tab("Slots", "/slots", model.load_action == ActionLoad::Slots),
+        span![
+            class!["data-scope"],
+            attrs! { At::Title => match model.settings.data_scope {
+                DataScope::Active => "Only campaigns the market considers active are loaded",
+                DataScope::All => "Every campaign ever listed on the market is loaded",
+            } },
+            model.settings.data_scope.label()
+        ],
+        if model.settings.data_scope == DataScope::All {
+            span![
+                class!["data-scope-warning"],
+                attrs! { At::Title => ALL_SCOPE_WARNING },
+                "large download"
+            ]
+        } else {
+            seed::empty()
+        },
+        campaign_counts(&model.market_channels),
+        if model.throttle_hint_until.is_some() {
+            span![class!["refresh-hint"], "Please wait a moment"]
+        } else {
+            seed::empty()
+        },
+        div![
+            class!["button refresh"],
+            attrs! { At::Title => "Refresh" },
+            "⟳",
+            simple_ev(Ev::Click, Msg::ManualRefresh)
+        ],
+        div![
+            class!["button settings-toggle"],
+            attrs! { At::Title => "Settings" },
+            "⚙",
+            simple_ev(Ev::Click, Msg::ToggleSettings)
+        ],
+        if model.settings_open {
+            settings_panel(&model.settings, model.now)
+        } else {
+            seed::empty()
+        }
+    ]
+}
+
+// "1,432 campaigns · 389 active"; the active count links to the campaigns filtered by it
+fn campaign_counts(channels: &Loadable<Vec<MarketChannel>>) -> Node<Msg> {
+    let channels = match channels {
+        Ready(channels) => channels,
+        Loading => return seed::empty(),
+    };
+    let summary = types::summarize(channels);
+    span![
+        class!["campaign-counts"],
+        format!("{} campaigns · ", summary.total.to_formatted_string(&Locale::en)),
+        a![
+            attrs! { At::Href => "/channels"; At::Title => "Show only active campaigns" },
+            format!("{} active", summary.active.to_formatted_string(&Locale::en)),
+            simple_ev(Ev::Click, Msg::StatusFilterSelected(MarketStatusType::Active))
+        ],
+        span![
+            attrs! { At::Title => "Distinct publishers with a balance in any campaign" },
+            format!(
+                " · {} earners",
+                at_least(distinct_earners(channels), channels.iter().any(|c| c.status.trimmed.is_some()))
+            )
+        ],
+        span![
+            attrs! { At::Title => "Estimated from each campaign's USD value and paid share" },
+            format!(
+                " · ~{} paid out",
+                usd_readable(channels.iter().filter_map(channel_paid_usd).sum::<f64>() as f32)
+            )
+        ]
+    ]
+}
+
+// A count of distinct addresses, which is only a lower bound once balances are trimmed
+fn at_least(count: usize, trimmed: bool) -> String {
+    let count = count.to_formatted_string(&Locale::en);
+    if trimmed {
+        format!("≥{}", count)
+    } else {
+        count
+    }
+}
+
+// A union, not a sum: an address earning from several campaigns counts once
+// Creators with a balance in their own campaign aren't earners, so both the header and the
+// Publishers card leave them out
+fn distinct_earners<'a, I>(channels: I) -> usize
+where
+    I: IntoIterator<Item = &'a MarketChannel>,
+{
+    channels
+        .into_iter()
+        .flat_map(|channel| {
+            let creator = channel.creator.to_lowercase();
+            channel
+                .status
+                .balances
+                .keys()
+                .map(|addr| addr.to_lowercase())
+                .filter(move |addr| *addr != creator)
+        })
+        .collect::<HashSet<_>>()
+        .len()
+}
+
+fn nav_tab(label: &str, href: &str, active: bool) -> Node<Msg> {
+    a![
+        class!(if active { "tab active" } else { "tab" }),
+        attrs! { At::Href => href },
+        label
+    ]
+}
+
+// The selects listen to change rather than input: some browsers don't fire input when the
+// selection is changed with the keyboard
+// `now` is in milliseconds; the local timezone is named as of then
+fn settings_panel(settings: &Settings, now: f64) -> Node<Msg> {
+    div![
+        class!["settings-panel"],
+        label!["Sort channels by"],
+        select![
+            select_option("deposit", "Deposit", settings.sort == ChannelSort::Deposit),
+            select_option("status", "Status", settings.sort == ChannelSort::Status),
+            select_option("created", "Created", settings.sort == ChannelSort::Created),
+            select_option(
+                "publishers",
+                "Earners",
+                settings.sort == ChannelSort::PublisherCount
+            ),
+            select_option("price", "Price", settings.sort == ChannelSort::Price),
+            select_option("expiry", "Expiring soonest", settings.sort == ChannelSort::Expiry),
+            select_option("age", "Oldest", settings.sort == ChannelSort::Age),
+            select_option("fees", "Validator fees", settings.sort == ChannelSort::Fees),
+            input_ev(Ev::Change, Msg::SortSelected)
+        ],
+        label!["Network"],
+        select![
+            select_option("mainnet", "Mainnet", settings.env == Environment::Mainnet),
+            select_option("testnet", "Testnet (Goerli)", settings.env == Environment::Testnet),
+            select_option("custom", "Custom market URL", settings.env.name() == "custom"),
+            input_ev(Ev::Change, Msg::EnvironmentSelected)
+        ],
+        match &settings.env {
+            Environment::Custom(market_url) => vec![
+                label!["Market URL"],
+                input![
+                    attrs! { At::Value => market_url; At::Placeholder => "https://market.example.com" },
+                    input_ev(Ev::Change, Msg::CustomMarketUrlChanged)
+                ],
+            ],
+            _ => vec![],
+        },
+        label!["Campaigns"],
+        select![
+            [DataScope::Active, DataScope::All]
+                .iter()
+                .map(|&scope| select_option(scope.name(), scope.label(), settings.data_scope == scope))
+                .collect::<Vec<_>>(),
+            input_ev(Ev::Change, Msg::DataScopeSelected)
+        ],
+        if settings.data_scope == DataScope::All {
+            span![class!["settings-note"], ALL_SCOPE_WARNING]
+        } else {
+            seed::empty()
+        },
+        label!["IPFS gateway"],
+        select![
+            select_option("ipfs.io", "ipfs.io", settings.ipfs_gateway == ipfs::Gateway::IpfsIo),
+            select_option("cloudflare", "Cloudflare", settings.ipfs_gateway == ipfs::Gateway::Cloudflare),
+            select_option("adex", "AdEx", settings.ipfs_gateway == ipfs::Gateway::Adex),
+            select_option("custom", "Custom gateway URL", settings.ipfs_gateway.name() == "custom"),
+            input_ev(Ev::Change, Msg::IpfsGatewaySelected)
+        ],
+        match &settings.ipfs_gateway {
+            ipfs::Gateway::Custom(gateway_url) => vec![
+                label!["Gateway URL"],
+                input![
+                    attrs! { At::Value => gateway_url; At::Placeholder => "https://gateway.example.com/ipfs/" },
+                    input_ev(Ev::Change, Msg::CustomIpfsGatewayChanged)
+                ],
+                match settings.ipfs_gateway.error() {
+                    Some(reason) => div![class!["validation-error"], reason],
+                    None => seed::empty(),
+                },
+            ],
+            _ => vec![],
+        },
+        label!["DAI decimals"],
+        select![
+            (0..=MAX_DAI_PRECISION)
+                .map(|precision| select_option(
+                    &precision.to_string(),
+                    &precision.to_string(),
+                    settings.dai_precision == precision
+                ))
+                .collect::<Vec<_>>(),
+            input_ev(Ev::Change, Msg::DaiPrecisionSelected)
+        ],
+        label!["Amounts in"],
+        select![
+            [AmountUnit::Dai, AmountUnit::DaiExact, AmountUnit::Usd, AmountUnit::Gwei, AmountUnit::Wei]
+                .iter()
+                .map(|&unit| select_option(
+                    unit.name(),
+                    match unit {
+                        AmountUnit::Dai => "DAI",
+                        AmountUnit::DaiExact => "DAI, all decimals",
+                        AmountUnit::Gwei => "Gwei",
+                        AmountUnit::Wei => "wei",
+                        AmountUnit::Usd => "USD (estimated)",
+                    },
+                    settings.amount_unit == unit
+                ))
+                .collect::<Vec<_>>(),
+            input_ev(Ev::Change, Msg::AmountUnitSelected)
+        ],
+        label![format!(
+            "Show times in local timezone ({})",
+            timestamps::browser_zone_label(&Utc.timestamp_millis(now as i64))
+        )],
+        checkbox(settings.local_time, Msg::ToggleLocalTime),
+        label!["Show paid as a bar"],
+        checkbox(settings.paid_bars, Msg::TogglePaidBars),
+        label!["Abbreviate large DAI amounts"],
+        checkbox(settings.compact_dai, Msg::ToggleCompactDai),
+        label!["Show event rate limits"],
+        checkbox(settings.show_rate_limits, Msg::ToggleRateLimits),
+        label!["Group duplicate campaigns"],
+        checkbox(settings.group_duplicates, Msg::ToggleGroupDuplicates),
+        label!["Expand groups matching the search"],
+        checkbox(settings.expand_search_matches, Msg::ToggleExpandSearchMatches),
+        label!["Check validator reachability"],
+        checkbox(settings.check_validators, Msg::ToggleCheckValidators),
+        label!["Keep only the top earners in memory"],
+        checkbox(settings.trim_balances, Msg::ToggleTrimBalances),
+        button![
+            class!["reset-settings"],
+            "Reset all settings",
+            simple_ev(Ev::Click, Msg::ResetSettings)
+        ],
+    ]
+}
+
+fn checkbox(checked: bool, msg: Msg) -> Node<Msg> {
+    let mut attrs = attrs! { At::Type => "checkbox" };
+    if checked {
+        attrs.add(At::Checked, "checked");
+    }
+    input![attrs, simple_ev(Ev::Click, msg)]
+}
+
+fn select_option(value: &str, label: &str, selected: bool) -> Node<Msg> {
+    let mut attrs = attrs! { At::Value => value };
+    if selected {
+        attrs.add(At::Selected, "selected");
+    }
+    option![attrs, label]
+}
+
+fn impressions_card(title: &str, loadable: &types::Loadable<AnalyticsResp>) -> Node<Msg> {
+    volume_card(
+        title,
+        match loadable {
+            Ready(vol) => Ready(span![vol
+                .aggr
+                .iter()
+                .map(|x| &x.value)
+                .sum::<BigNum>()
+                .to_u64()
+                .unwrap_or(0)
+                .to_formatted_string(&Locale::en)]),
+            Loading => Loading,
+        },
+        loadable
+    )
+}
+
+// The change in one of the totals since the previous refresh, and whether it went up
+// None on the first load, when it didn't change, or once it has faded
+fn totals_change(model: &Model, figure: fn(&Totals) -> &BigNum) -> Option<(BigNum, bool)> {
+    match (&model.totals, &model.previous_totals) {
+        (Some(totals), Some(previous)) if model.deltas_until.is_some() => {
+            bignum::diff(figure(totals), figure(previous))
+        }
+        _ => None,
+    }
+}
+
+fn totals_delta(model: &Model, figure: fn(&Totals) -> &BigNum) -> Node<Msg> {
+    match totals_change(model, figure) {
+        Some((amount, up)) => div![
+            class!(if up { "card-delta up" } else { "card-delta down" }),
+            attrs! { At::Title => exact_wei(&amount) },
+            format!(
+                "{} {}{}",
+                if up { "▲" } else { "▼" },
+                if up { "+" } else { "−" },
+                plain_amount_text(&amount, &model.settings)
+            )
+        ],
+        None => seed::empty(),
+    }
+}
+
+fn impressions_delta(model: &Model) -> Node<Msg> {
+    match totals_change(model, |t| &t.impressions) {
+        Some((count, up)) => div![
+            class!(if up { "card-delta up" } else { "card-delta down" }),
+            format!(
+                "{} {}{} impressions",
+                if up { "▲" } else { "▼" },
+                if up { "+" } else { "−" },
+                impression_count(&count)
+            )
+        ],
+        None => seed::empty(),
+    }
+}
+
+fn impression_count(count: &BigNum) -> String {
+    count.to_u64().unwrap_or(u64::MAX).to_formatted_string(&Locale::en)
+}
+
+fn card_with_delta(label: &str, value: Node<Msg>, delta: Node<Msg>) -> Node<Msg> {
+    div![
+        class!["card"],
+        div![class!["card-value"], value],
+        delta,
+        div![class!["card-label"], label],
+    ]
+}
+
+// An amount, which comes with its exact wei; see amount_value
+fn amount_card(label: &str, value: Node<Msg>) -> Node<Msg> {
+    card_with_delta(label, value, seed::empty())
+}
+
+fn card(label: &str, value: Loadable<String>) -> Node<Msg> {
+    div![
+        class!["card"],
+        match value {
+            Loading => div![class!["card-value loading"]],
+            Ready(value) => div![class!["card-value"], value],
+        },
+        div![class!["card-label"], label],
+    ]
+}
+
+// The DAI held by the core contract; networks without one just say so
+fn locked_up_card(model: &Model) -> Node<Msg> {
+    let env = model.settings.env.config();
+    let core_addr = match env.core_addr {
+        Some(core_addr) => core_addr,
+        None => return card("Locked up on-chain", Ready("N/A".to_owned())),
+    };
+    a![
+        attrs! { At::Href => format!("{}/address/{}#tokentxns", env.explorer_url, core_addr) },
+        match &model.balance {
+            Ready(resp) => amount_card(
+                "Locked up on-chain",
+                amount_value(plain_amount_text(&resp.result, &model.settings), &resp.result)
+            ),
+            Loading => card("Locked up on-chain", Loading),
+        },
+    ]
+}
+
+// Paid out within the last 24h, from the totals seen this session
+// Until the page has been open that long, it says how long a window it actually covers
+fn trailing_paid_card(model: &Model) -> Node<Msg> {
+    let snapshots = model.paid_history.snapshots();
+    let (paid, covered_ms) = match history::windowed_delta(snapshots, history::WINDOW_MS) {
+        Some(delta) => delta,
+        None => return card("Paid in the last 24h", Loading),
+    };
+    let window = if covered_ms >= history::WINDOW_MS - f64::from(REFRESH_MS) {
+        "24h".to_owned()
+    } else {
+        duration::compact(Duration::milliseconds(covered_ms as i64))
+    };
+    let card_label = format!("Paid in the last {}", window);
+    let card_value = amount_value(plain_amount_text(&paid, &model.settings), &paid);
+    match sparkline(&snapshots.iter().map(|s| &s.paid).collect::<Vec<_>>()) {
+        Some(chart) => div![
+            class!["card chart"],
+            chart,
+            div![class!["card-value"], card_value],
+            div![class!["card-label"], card_label],
+        ],
+        None => amount_card(&card_label, card_value),
+    }
+}
+
+fn volume_chart(vol: &AnalyticsResp) -> Option<Node<Msg>> {
+    let values = vol.aggr.iter().map(|x| &x.value).collect::<Vec<_>>();
+    // The last point is left out, as it may still be incomplete
+    sparkline(&values[..values.len().checked_sub(1)?])
+}
+
+// None with less than two points, or when they're all the same
+fn sparkline(values: &[&BigNum]) -> Option<Node<Msg>> {
+    let min = *values.iter().min()?;
+    let max = *values.iter().max()?;
+    let range = max - min;
+    if values.len() < 2 || range == BigNum::from(0) {
+        return None;
+    }
+    let width = 250_u64;
+    let height = 60_u64;
+    let points = values
+        .iter()
+        .map(|&v| {
+            (&(v - min) * &height.into())
+                .div_floor(&range)
+                .to_u64()
+                .unwrap_or(0)
+        })
+        .collect::<Vec<_>>();
+    let len = points.len() as u64;
+    let ratio = width as f64 / (len - 1) as f64;
+    Some(svg![
+        attrs! {
+            At::Style => "position: absolute; right: 0px; left: 0px; bottom: 10px;";
+            At::Width => format!("{}px", width);
+            At::Height => format!("{}px", height);
+            At::ViewBox => format!("0 0 {} {}", width, height);
+        },
+        polyline![attrs! {
+            At::Fill => "none";
+            At::Custom("stroke".into()) => "#c8dbec";
+            At::Custom("stroke-width".into()) => "4";
+            At::Custom("points".into()) => points
+                .iter()
+                .enumerate()
+                .map(|(i, p)| format!("{},{}", (i as f64 * ratio).ceil(), height-p))
+                .collect::<Vec<_>>()
+                .join(" ")
+        }],
+    ])
+}
+
+fn volume_card(card_label: &str, val: Loadable<Node<Msg>>, vol: &Loadable<AnalyticsResp>) -> Node<Msg> {
+    let (card_value, vol) = match (val, vol) {
+        (Ready(val), Ready(vol)) => (val, vol),
+        _ => return card(card_label, Loading),
+    };
+    match volume_chart(vol) {
+        Some(chart) => div![
+            class!["card chart"],
+            chart,
             div![class!["card-value"], card_value],
             div![class!["card-label"], card_label],
         ],
-        None => card(card_label, val),
+        None => card_with_delta(card_label, card_value, seed::empty()),
+    }
+}
+
+// Expired and exhausted channels are out of scope on /channels, unless the user asked for them
+fn in_scope_channels<'a>(model: &Model, channels: &[&'a MarketChannel]) -> Vec<&'a MarketChannel> {
+    let show_ended = model.load_action == ActionLoad::ChannelsAll || model.settings.show_ended;
+    channels
+        .iter()
+        .cloned()
+        .filter(|channel| show_ended || match channel.status.status_type {
+            types::MarketStatusType::Expired | types::MarketStatusType::Exhausted => false,
+            _ => true
+        })
+        .collect()
+}
+
+// The rows of the channels table, filtered and in display order
+fn visible_channels<'a>(model: &Model, in_scope: &[&'a MarketChannel]) -> Vec<&'a MarketChannel> {
+    in_scope
+        .iter()
+        .cloned()
+        .filter(|channel| {
+            model
+                .filters
+                .matches(channel, ad_unit_text(model, channel), &model.tag_index)
+        })
+        .sorted_by(|x, y| match &model.frozen_order {
+            // Channels that weren't there when the order was frozen go last
+            Some(order) => {
+                let position = |c: &MarketChannel| order.get(&c.id).cloned().unwrap_or(usize::MAX);
+                position(x)
+                    .cmp(&position(y))
+                    .then_with(|| compare_channels(model, x, y))
+            }
+            None => compare_channels(model, x, y),
+        })
+        .collect()
+}
+
+fn channels_section(model: &Model, channels: &[&MarketChannel]) -> Node<Msg> {
+    let in_scope = in_scope_channels(model, channels);
+    let ended_hidden = channels.len() - in_scope.len();
+    let visible = visible_channels(model, &in_scope);
+    let unpaid_hidden = if model.filters.hide_unpaid {
+        let unpaid_shown = Filters {
+            hide_unpaid: false,
+            ..model.filters.clone()
+        };
+        in_scope
+            .iter()
+            .filter(|channel| {
+                unpaid_shown.matches(channel, ad_unit_text(model, channel), &model.tag_index)
+                    && filters::is_unpaid(channel)
+            })
+            .count()
+    } else {
+        0
+    };
+
+    div![
+        input![
+            class!["search"],
+            attrs! {
+                At::Id => SEARCH_INPUT_ID;
+                At::Value => model.search_input;
+                At::Placeholder => "Search by id, creator, title or ad text (press / to focus)"
+            },
+            input_ev(Ev::Input, Msg::SearchChanged)
+        ],
+        if model.settings.trim_balances {
+            input![
+                class!["search publisher"],
+                attrs! {
+                    At::Disabled => "disabled";
+                    At::Placeholder => "Publisher address (0x...)";
+                    At::Title => "Needs every earner's balance; turn off trimming in the settings"
+                }
+            ]
+        } else {
+            input![
+                class!["search publisher"],
+                attrs! {
+                    At::Value => model.filters.publisher;
+                    At::Placeholder => "Publisher address (0x...)"
+                },
+                input_ev(Ev::Input, Msg::PublisherFilterChanged)
+            ]
+        },
+        if model.filters.publisher_invalid() {
+            div![class!["validation-error"], "Not a valid address: expected 0x followed by 40 hex characters"]
+        } else {
+            seed::empty()
+        },
+        input![
+            class!["search creator"],
+            attrs! {
+                At::Value => model.filters.creator;
+                At::Placeholder => "Creator address (0x...)"
+            },
+            input_ev(Ev::Input, Msg::CreatorFilterChanged)
+        ],
+        if model.filters.creator_invalid() {
+            div![class!["validation-error"], "Not a valid address: expected 0x followed by 40 hex characters"]
+        } else {
+            seed::empty()
+        },
+        checked_range_inputs(&model.filters),
+        usd_range_inputs(&model.filters),
+        tag_filter(&model.tag_index, &model.filters),
+        if model.load_action == ActionLoad::ChannelsActive {
+            ended_toggle(model.settings.show_ended, ended_hidden)
+        } else {
+            seed::empty()
+        },
+        expiring_soon_panel(model, &in_scope),
+        status_chips(&model.filters.status, &in_scope),
+        status_legend(model.status_legend_open),
+        unpaid_toggle(model.filters.hide_unpaid, unpaid_hidden),
+        filter_indicator(visible.len(), in_scope.len(), model.filters.active_count()),
+        button![
+            class!["reset-view"],
+            attrs! { At::Title => "Clear filters, search, sort and selection" },
+            "Reset view",
+            simple_ev(Ev::Click, Msg::ResetView)
+        ],
+        compare_button(&model.compare),
+        button![
+            class!["copy-markdown"],
+            attrs! { At::Title => "Copy the rows below as a Markdown table" },
+            "Copy as Markdown",
+            simple_ev(Ev::Click, Msg::CopyMarkdown)
+        ],
+        button![
+            class!["export-all"],
+            attrs! { At::Title => "Download every channel, whatever the filters and the asset" },
+            "Export all (CSV)",
+            simple_ev(Ev::Click, Msg::ExportAllCsv)
+        ],
+        button![
+            class!["export-all"],
+            attrs! { At::Title => "Download every channel as the market returned it" },
+            "Export all (JSON)",
+            simple_ev(Ev::Click, Msg::ExportAllJson)
+        ],
+        button![
+            class!["copy-link"],
+            attrs! { At::Title => "Copy a link to this page with the current status filter" },
+            "Copy link",
+            simple_ev(Ev::Click, Msg::CopyShareLink)
+        ],
+        match &model.copy_notice {
+            Some(notice) => span![class!["copy-notice"], notice.as_str()],
+            None => seed::empty(),
+        },
+        button![
+            class!(if model.frozen_order.is_some() { "freeze active" } else { "freeze" }),
+            attrs! { At::Title => "Keep rows in place while the data refreshes" },
+            if model.frozen_order.is_some() { "Unfreeze order" } else { "Freeze order" },
+            simple_ev(Ev::Click, Msg::ToggleFreezeOrder)
+        ],
+        button![
+            class!(if model.presenting { "present active" } else { "present" }),
+            attrs! { At::Title => "Scroll through the table by itself, for wall displays" },
+            if model.presenting { "Stop presenting" } else { "Present" },
+            simple_ev(Ev::Click, Msg::TogglePresentation)
+        ],
+        if model.compare_open {
+            compare_panel(model, channels)
+        } else {
+            seed::empty()
+        },
+        channel_table(model, &visible),
+        data_quality_panel(&model.duplicates, &model.owner_mismatches),
+    ]
+}
+
+// Campaigns running out within EXPIRING_SOON_DAYS, soonest first, so advertisers can be
+// reminded to renew them
+fn expiring_soon_panel(model: &Model, channels: &[&MarketChannel]) -> Node<Msg> {
+    let horizon = Duration::days(EXPIRING_SOON_DAYS);
+    let expiring = channels
+        .iter()
+        .filter_map(|&channel| {
+            let left = channel.time_until_expiry(model.last_loaded)?;
+            if left <= horizon {
+                Some((left, channel))
+            } else {
+                None
+            }
+        })
+        .sorted_by(|(x, _), (y, _)| x.cmp(y))
+        .collect::<Vec<_>>();
+    if expiring.is_empty() {
+        return seed::empty();
+    }
+    div![
+        class!["expiring-soon"],
+        h3![format!(
+            "Expiring in the next {} days ({})",
+            EXPIRING_SOON_DAYS,
+            expiring.len()
+        )],
+        table![expiring
+            .iter()
+            .take(EXPIRING_SOON_MAX)
+            .map(|(left, channel)| {
+                tr![
+                    td![a![
+                        attrs! { At::Href => format!("/channel/{}", channel.id) },
+                        channel.display_name(&model.labels)
+                    ]],
+                    {
+                        let unspent = channel.unspent().0;
+                        dai_cell(&unspent, channel_usd(channel, &unspent), &model.settings)
+                    },
+                    td![duration::compact(*left)],
+                ]
+            })
+            .collect::<Vec<_>>()],
+        a![
+            class!["view-all"],
+            if expiring.len() > EXPIRING_SOON_MAX {
+                format!("View all {}", expiring.len())
+            } else {
+                "Show in the table".to_owned()
+            },
+            simple_ev(Ev::Click, Msg::ShowExpiringSoon)
+        ]
+    ]
+}
+
+fn tag_filter(tag_index: &tags::TagIndex, filters: &Filters) -> Node<Msg> {
+    let tags = tag_index.tags();
+    if tags.is_empty() {
+        return seed::empty();
+    }
+    div![
+        class!["tag-filter"],
+        label!["Targeting tag "],
+        select![
+            select_option("", "Any", filters.tag.is_empty()),
+            tags.iter()
+                .map(|(tag, count)| {
+                    select_option(tag, &format!("{} ({})", tag, count), filters.tag == *tag)
+                })
+                .collect::<Vec<_>>(),
+            input_ev(Ev::Change, Msg::TagSelected)
+        ],
+        match tag_index.max_score(&filters.tag) {
+            Some(max_score) if max_score > 0.0 => span![
+                label![format!(" with a score of at least {:.1} ", filters.tag_min_score)],
+                input![
+                    attrs! {
+                        At::Type => "range";
+                        At::Min => "0";
+                        At::Max => max_score;
+                        At::Step => "any";
+                        At::Value => filters.tag_min_score
+                    },
+                    input_ev(Ev::Input, Msg::TagMinScoreChanged)
+                ]
+            ],
+            _ => seed::empty(),
+        }
+    ]
+}
+
+fn usd_range_inputs(filters: &Filters) -> Node<Msg> {
+    div![
+        class!["usd-range"],
+        label!["USD estimate from "],
+        input![
+            attrs! { At::Type => "number"; At::Min => "0"; At::Value => filters.usd_min },
+            input_ev(Ev::Input, Msg::UsdMinChanged)
+        ],
+        label![" to "],
+        input![
+            attrs! { At::Type => "number"; At::Min => "0"; At::Value => filters.usd_max },
+            input_ev(Ev::Input, Msg::UsdMaxChanged)
+        ],
+    ]
+}
+
+fn checked_range_inputs(filters: &Filters) -> Node<Msg> {
+    div![
+        class!["checked-range"],
+        label!["Last checked from "],
+        input![
+            attrs! { At::Type => "date"; At::Value => filters.checked_from },
+            input_ev(Ev::Input, Msg::CheckedFromChanged)
+        ],
+        label![" to "],
+        input![
+            attrs! { At::Type => "date"; At::Value => filters.checked_to },
+            input_ev(Ev::Input, Msg::CheckedToChanged)
+        ],
+        " (UTC)",
+        match filters.checked_error() {
+            Some(reason) => div![class!["validation-error"], reason],
+            None => seed::empty(),
+        }
+    ]
+}
+
+// One toggle per status present, with how many channels have it; any number can be on
+// Counts ignore the other filters, so a chip doesn't vanish as soon as another one is picked
+fn status_chips(selected: &HashSet<MarketStatusType>, channels: &[&MarketChannel]) -> Node<Msg> {
+    let mut counts = types::summarize(channels.iter().cloned()).by_status;
+    // A selected status stays visible at zero, otherwise it couldn't be deselected
+    for status in selected {
+        counts.entry(status.clone()).or_insert(0);
+    }
+    div![
+        class!["status-chips"],
+        counts
+            .into_iter()
+            .map(|(status, count)| {
+                let active = selected.contains(&status);
+                button![
+                    class!(if active { "chip active" } else { "chip" }),
+                    format!("{:?} ({})", status, count.to_formatted_string(&Locale::en)),
+                    simple_ev(Ev::Click, Msg::ToggleStatusFilter(status))
+                ]
+            })
+            .collect::<Vec<_>>()
+    ]
+}
+
+// The color of each status, as used in the table
+fn status_class(status: &MarketStatusType) -> &'static str {
+    match status {
+        MarketStatusType::Active => "status-active",
+        MarketStatusType::Initializing | MarketStatusType::Waiting | MarketStatusType::Ready => {
+            "status-pending"
+        }
+        MarketStatusType::Invalid
+        | MarketStatusType::Offline
+        | MarketStatusType::Disconnected
+        | MarketStatusType::Unhealthy => "status-unhealthy",
+        MarketStatusType::Withdraw | MarketStatusType::Expired | MarketStatusType::Exhausted => {
+            "status-ended"
+        }
+    }
+}
+
+// Every status with its swatch, from status_class so the two can't disagree
+fn status_legend(open: bool) -> Node<Msg> {
+    div![
+        class!["status-legend"],
+        button![
+            class!["legend-toggle"],
+            if open { "Status colors ▾" } else { "Status colors ▸" },
+            simple_ev(Ev::Click, Msg::ToggleStatusLegend)
+        ],
+        if open {
+            MarketStatusType::ALL
+                .iter()
+                .map(|status| {
+                    span![
+                        class!["legend-entry"],
+                        span![attrs! { At::Class => format!("swatch {}", status_class(status)) }],
+                        format!("{:?}", status)
+                    ]
+                })
+                .collect::<Vec<_>>()
+        } else {
+            vec![]
+        }
+    ]
+}
+
+fn ended_toggle(show_ended: bool, hidden: usize) -> Node<Msg> {
+    div![
+        class!["ended-toggle"],
+        label![
+            checkbox(show_ended, Msg::ToggleShowEnded),
+            " Show ended campaigns"
+        ],
+        if hidden > 0 {
+            span![
+                class!["hidden-count"],
+                format!(" ({} hidden)", hidden.to_formatted_string(&Locale::en))
+            ]
+        } else {
+            seed::empty()
+        }
+    ]
+}
+
+fn unpaid_toggle(hide_unpaid: bool, hidden: usize) -> Node<Msg> {
+    div![
+        class!["ended-toggle"],
+        label![
+            checkbox(hide_unpaid, Msg::ToggleHideUnpaid),
+            " Hide campaigns that haven't paid anything"
+        ],
+        if hidden > 0 {
+            span![
+                class!["hidden-count"],
+                format!(" ({} hidden)", hidden.to_formatted_string(&Locale::en))
+            ]
+        } else {
+            seed::empty()
+        }
+    ]
+}
+
+fn ad_unit_text<'a>(model: &'a Model, channel: &MarketChannel) -> &'a str {
+    model.ad_unit_text.get(&channel.id).map_or("", String::as_str)
+}
+
+// The campaign still references its units, but their owner archived all of them
+fn all_units_archived(channel: &MarketChannel) -> bool {
+    !channel.spec.ad_units.is_empty() && channel.spec.ad_units.iter().all(|unit| unit.archived)
+}
+
+// A validator URL nothing can be built on, or no validator at all (see types.rs): the channel
+// still counts towards the deposit and paid totals, but is left out of everything that's per
+// validator
+fn is_misconfigured(channel: &MarketChannel) -> bool {
+    let validators = &channel.spec.validators;
+    channel.spec.validator_count < 2
+        || [&validators.leader().url, &validators.follower().url]
+            .iter()
+            .any(|url| validator::normalize_url(url).base.is_none())
+}
+
+fn data_quality_panel(
+    duplicates: &HashMap<String, Vec<String>>,
+    owner_mismatches: &HashMap<String, data_quality::OwnerMismatch>,
+) -> Node<Msg> {
+    if duplicates.is_empty() && owner_mismatches.is_empty() {
+        return seed::empty();
+    }
+    div![
+        class!["data-quality"],
+        h3!["Data quality"],
+        ul![duplicates
+            .iter()
+            .sorted_by(|x, y| x.0.cmp(y.0))
+            .map(|(id, others)| li![
+                a![attrs! { At::Href => format!("/channel/{}", id) }, id.as_str()],
+                format!(" duplicates {}", others.join(", "))
+            ])
+            .chain(
+                owner_mismatches
+                    .iter()
+                    .sorted_by(|x, y| x.0.cmp(y.0))
+                    .map(|(id, mismatch)| li![
+                        a![attrs! { At::Href => format!("/channel/{}", id) }, id.as_str()],
+                        format!(
+                            " was created by {} but has ad units owned by {}",
+                            mismatch.creator,
+                            mismatch.owners.join(", ")
+                        )
+                    ])
+            )
+            .collect::<Vec<_>>()]
+    ]
+}
+
+fn duplicate_badge(duplicates: &HashMap<String, Vec<String>>, id: &str) -> Node<Msg> {
+    match duplicates.get(id) {
+        Some(others) => span![
+            class!["badge duplicate"],
+            attrs! { At::Title => format!("Duplicate of: {}", others.join(", ")) },
+            "duplicate"
+        ],
+        None => seed::empty(),
+    }
+}
+
+fn owner_mismatch_badge(owner_mismatches: &HashMap<String, data_quality::OwnerMismatch>, id: &str) -> Node<Msg> {
+    match owner_mismatches.get(id) {
+        Some(mismatch) => span![
+            class!["badge owner-mismatch"],
+            attrs! { At::Title => format!("Ad units owned by someone other than the creator: {}", mismatch.owners.join(", ")) },
+            "foreign units"
+        ],
+        None => seed::empty(),
+    }
+}
+
+fn compare_button(compare: &[String]) -> Node<Msg> {
+    let mut attrs = attrs! { At::Title => "Select exactly two channels to compare" };
+    if compare.len() != 2 {
+        attrs.add(At::Disabled, "disabled");
+    }
+    button![
+        class!["compare-button"],
+        attrs,
+        format!("Compare ({}/2)", compare.len()),
+        simple_ev(Ev::Click, Msg::OpenCompare)
+    ]
+}
+
+fn compare_panel(model: &Model, channels: &[&MarketChannel]) -> Node<Msg> {
+    let find = |id: &String| channels.iter().find(|c| &c.id == id);
+    let (a, b) = match (find(&model.compare[0]), find(&model.compare[1])) {
+        (Some(a), Some(b)) => (a, b),
+        _ => return seed::empty(),
+    };
+    let (a_paid, b_paid) = (a.status.balances_sum(), b.status.balances_sum());
+    // Amounts differ by what they show, not by their last wei, which is on hover
+    let amount_rows = vec![
+        ("Deposit", &a.deposit_amount, &b.deposit_amount),
+        ("Paid", &a_paid, &b_paid),
+    ]
+    .into_iter()
+    .map(|(label, x, y)| {
+        let x_text = channel_amount_text(a, x, &model.settings);
+        let y_text = channel_amount_text(b, y, &model.settings);
+        let differs = x_text != y_text;
+        (label, amount_value(x_text, x), amount_value(y_text, y), differs)
+    });
+    let rows = vec![
+        (
+            "Paid - %",
+            paid_percent(&a.status.balances_sum(), &a.deposit_amount),
+            paid_percent(&b.status.balances_sum(), &b.deposit_amount),
+        ),
+        ("Est. impressions", estimated_impressions(a), estimated_impressions(b)),
+        (
+            "Status",
+            format!("{:?}", &a.status.status_type),
+            format!("{:?}", &b.status.status_type),
+        ),
+        (
+            "Leader",
+            validator_name(&model.labels, &a.spec.validators.leader().url),
+            validator_name(&model.labels, &b.spec.validators.leader().url),
+        ),
+        (
+            "Follower",
+            validator_name(&model.labels, &a.spec.validators.follower().url),
+            validator_name(&model.labels, &b.spec.validators.follower().url),
+        ),
+    ];
+    div![
+        class!["compare-panel"],
+        table![
+            tr![
+                td![],
+                td![a.id.chars().take(6).collect::<String>()],
+                td![b.id.chars().take(6).collect::<String>()]
+            ],
+            amount_rows
+                .chain(rows.into_iter().map(|(label, x, y)| {
+                    let differs = x != y;
+                    (label, span![x], span![y], differs)
+                }))
+                .map(|(label, x, y, differs)| {
+                    tr![
+                        class!(if differs { "differs" } else { "same" }),
+                        td![label],
+                        td![x],
+                        td![y]
+                    ]
+                })
+                .collect::<Vec<_>>()
+        ],
+        button!["Close", simple_ev(Ev::Click, Msg::CloseCompare)]
+    ]
+}
+
+// Impressions paid for so far, assuming every impression was paid the minimum price
+fn estimated_impressions(channel: &MarketChannel) -> String {
+    if channel.price_per_impression() == &BigNum::from(0) {
+        return "N/A".to_owned();
+    }
+    channel
+        .status
+        .balances_sum()
+        .div_floor(channel.price_per_impression())
+        .to_u64()
+        .unwrap_or(0)
+        .to_formatted_string(&Locale::en)
+}
+
+// Ties are broken by id, so rows keep their places from one refresh to the next whatever order
+// the market returns them in
+fn compare_channels(model: &Model, x: &MarketChannel, y: &MarketChannel) -> Ordering {
+    compare_by_sort(model, x, y).then_with(|| x.id.cmp(&y.id))
+}
+
+fn compare_by_sort(model: &Model, x: &MarketChannel, y: &MarketChannel) -> Ordering {
+    match model.settings.sort {
+        ChannelSort::Deposit => y.deposit_amount.cmp(&x.deposit_amount),
+        ChannelSort::Status => x.status.status_type.cmp(&y.status.status_type),
+        ChannelSort::Created => y.spec.created.cmp(&x.spec.created),
+        // Most earners first, ties broken by paid total
+        ChannelSort::PublisherCount => {
+            let key = |c: &MarketChannel| {
+                model
+                    .channel_stats
+                    .get(&c.id)
+                    .map(|stats| (stats.earners, &stats.paid))
+            };
+            key(y).cmp(&key(x))
+        }
+        ChannelSort::Price => y.price_per_impression().cmp(x.price_per_impression()),
+        // Soonest first, expired ones and those without an expiry last
+        ChannelSort::Expiry => {
+            let key = |c: &MarketChannel| match c.time_until_expiry(model.last_loaded) {
+                Some(left) => (false, left),
+                None => (true, Duration::zero()),
+            };
+            key(x).cmp(&key(y))
+        }
+        // Oldest first
+        ChannelSort::Age => x.spec.created.cmp(&y.spec.created),
+        ChannelSort::Fees => y.validator_fees().cmp(&x.validator_fees()),
+    }
+}
+
+fn filter_indicator(shown: usize, total: usize, active_filters: usize) -> Node<Msg> {
+    if active_filters == 0 {
+        return seed::empty();
     }
+    div![
+        class!["filter-indicator"],
+        format!(
+            "Showing {} of {} campaigns · {} filter{} active · ",
+            shown.to_formatted_string(&Locale::en),
+            total.to_formatted_string(&Locale::en),
+            active_filters,
+            if active_filters == 1 { "" } else { "s" }
+        ),
+        a![
+            class!["clear-filters"],
+            "clear all",
+            simple_ev(Ev::Click, Msg::ClearFilters)
+        ]
+    ]
 }
 
-fn channel_table(last_loaded: i64, channels: &[&MarketChannel]) -> Node<Msg> {
+fn channel_table(model: &Model, channels: &[&MarketChannel]) -> Node<Msg> {
     let header = tr![
-        td!["URL"],
+        td![],
+        td!["Campaign"],
         td!["USD estimate"],
         td!["Deposit"],
-        // td!["CPM"],
+        td!["Price (CPM)"],
+        td!["Fees"],
+        if model.settings.show_rate_limits {
+            td!["Rate limit"]
+        } else {
+            seed::empty()
+        },
         td!["Paid"],
         td!["Paid - %"],
+        td!["Earners"],
+        td!["Top publisher"],
+        if model.filters.publisher_filter().is_some() {
+            td!["Publisher earnings"]
+        } else {
+            seed::empty()
+        },
         //td!["Max impressions"],
         td!["Status"],
+        td!["Expires in"],
         td!["Created"],
+        td!["Age"],
         //td!["Last updated"],
         td!["Preview"]
     ];
 
+    let columns = match &header {
+        Node::Element(el) => el
+            .children
+            .iter()
+            .filter(|td| match td {
+                Node::Empty => false,
+                _ => true,
+            })
+            .count(),
+        _ => 0,
+    };
+    let rows = table_rows(model, channels).into_iter().map(|row| {
+        let key = row.key();
+        let mut node = match row {
+            TableRow::Channel(c) => channel(model, c),
+            TableRow::Detail(c) => channel_detail_row(model, c, columns),
+            TableRow::Group {
+                fingerprint,
+                group,
+                expanded,
+            } => channel_group(model, &fingerprint, &group, expanded),
+        };
+        if let Node::Element(el) = &mut node {
+            el.attrs.add(At::Id, &key);
+        }
+        node
+    });
+
     let channels = std::iter::once(header)
-        .chain(channels.iter().map(|c| channel(last_loaded, c)))
+        .chain(rows)
         .collect::<Vec<Node<Msg>>>();
 
-    table![channels]
+    // Scrolls sideways on screens too narrow for every column, see .table-scroll in styles.css
+    div![class!["table-scroll"], table![class!["channel-table"], channels]]
+}
+
+enum TableRow<'a> {
+    Channel(&'a MarketChannel),
+    // More about the channel above it, for a search match
+    Detail(&'a MarketChannel),
+    // The summary row of duplicates; its channels follow it when it's expanded
+    Group {
+        fingerprint: String,
+        group: Vec<&'a MarketChannel>,
+        expanded: bool,
+    },
+}
+
+impl<'a> TableRow<'a> {
+    // Stays the same across refreshes, so the same row keeps the same element
+    fn key(&self) -> String {
+        match self {
+            TableRow::Channel(channel) => format!("row-{}", channel.id),
+            TableRow::Detail(channel) => format!("detail-{}", channel.id),
+            TableRow::Group { fingerprint, .. } => {
+                let mut hasher = DefaultHasher::new();
+                fingerprint.hash(&mut hasher);
+                format!("group-{:x}", hasher.finish())
+            }
+        }
+    }
+}
+
+// The rows of the channel table, in order
+fn table_rows<'a>(model: &Model, channels: &[&'a MarketChannel]) -> Vec<TableRow<'a>> {
+    let with_detail = |channel: &'a MarketChannel| {
+        let mut rows = vec![TableRow::Channel(channel)];
+        if model.expanded_channels.contains(&channel.id) {
+            rows.push(TableRow::Detail(channel));
+        }
+        rows
+    };
+    if !model.settings.group_duplicates {
+        return channels.iter().flat_map(|&c| with_detail(c)).collect();
+    }
+    group_by_fingerprint(channels)
+        .into_iter()
+        .flat_map(|(fingerprint, group)| {
+            if group.len() == 1 {
+                return with_detail(group[0]);
+            }
+            let expanded = model.expanded_groups.contains(&fingerprint);
+            let members = if expanded { group.clone() } else { Vec::new() };
+            std::iter::once(TableRow::Group {
+                fingerprint,
+                group,
+                expanded,
+            })
+            .chain(members.into_iter().map(TableRow::Channel))
+            .collect()
+        })
+        .collect()
+}
+
+// Buckets channels by fingerprint, keeping the order in which each bucket first appears
+fn group_by_fingerprint<'a>(
+    channels: &[&'a MarketChannel],
+) -> Vec<(String, Vec<&'a MarketChannel>)> {
+    let mut index = HashMap::<String, usize>::new();
+    let mut groups = Vec::<(String, Vec<&MarketChannel>)>::new();
+    for &channel in channels {
+        let fingerprint = channel.fingerprint();
+        match index.get(&fingerprint) {
+            Some(&i) => groups[i].1.push(channel),
+            None => {
+                index.insert(fingerprint.clone(), groups.len());
+                groups.push((fingerprint, vec![channel]));
+            }
+        }
+    }
+    groups
+}
+
+fn channel_group(
+    model: &Model,
+    fingerprint: &str,
+    group: &[&MarketChannel],
+    expanded: bool,
+) -> Node<Msg> {
+    let last_loaded = model.last_loaded;
+    let first = group[0];
+    let deposit_total = bignum::sum(group.iter().map(|c| &c.deposit_amount));
+    let paid_total = bignum::sum(group.iter().map(|c| c.status.balances_sum()));
+    let usd_total: f32 = group.iter().filter_map(|c| c.status.usd_estimate).sum();
+    let newest = group.iter().map(|c| &c.spec.created).max().unwrap_or(&first.spec.created);
+    let status = if group
+        .iter()
+        .all(|c| c.status.status_type == first.status.status_type)
+    {
+        format!("{:?}", &first.status.status_type)
+    } else {
+        "Mixed".to_owned()
+    };
+    tr![
+        class!["group"],
+        td![],
+        td![
+            span![class!["count-badge"], format!("{}×", group.len())],
+            first.id.chars().take(6).collect::<String>(),
+            if expanded { " ▾" } else { " ▸" },
+            span![class!["validator-host"], group_leaders(&model.labels, group)],
+        ],
+        td![usd_readable(usd_total)],
+        dai_cell(
+            &deposit_total,
+            group.iter().map(|c| channel_usd(c, &c.deposit_amount)).sum(),
+            &model.settings
+        ),
+        td![],
+        dai_cell(
+            &group.iter().map(|c| c.validator_fees()).sum::<BigNum>(),
+            group.iter().map(|c| channel_usd(c, &c.validator_fees())).sum(),
+            &model.settings
+        ),
+        if model.settings.show_rate_limits {
+            td![]
+        } else {
+            seed::empty()
+        },
+        // None if any of the group has no estimate, rather than a total that's silently short
+        dai_cell(
+            &paid_total,
+            group.iter().map(|c| channel_paid_usd(c)).sum(),
+            &model.settings
+        ),
+        paid_cell(&paid_total, &deposit_total, model.settings.paid_bars),
+        td![at_least(
+            group
+                .iter()
+                .flat_map(|c| c.status.balances.keys())
+                .collect::<HashSet<_>>()
+                .len(),
+            group.iter().any(|c| c.status.trimmed.is_some())
+        )],
+        td!["—"],
+        match model.filters.publisher_filter() {
+            Some(addr) => {
+                let balances = group
+                    .iter()
+                    .filter_map(|c| Some((c, filters::publisher_balance(c, &addr)?)))
+                    .collect::<Vec<_>>();
+                dai_cell(
+                    &bignum::sum(balances.iter().map(|(_, balance)| balance)),
+                    balances.iter().map(|(c, balance)| channel_usd(c, balance)).sum(),
+                    &model.settings,
+                )
+            }
+            None => seed::empty(),
+        },
+        td![status],
+        expiry_cell(group.iter().filter_map(|c| c.time_until_expiry(last_loaded)).min()),
+        time_cell(last_loaded, newest, model.settings.local_time),
+        age_cell(model.now, newest),
+        td![class!["preview"], {
+            match first.spec.ad_units.get(0) {
+                Some(unit) => unit_preview(&unit, &model.settings.ipfs_gateway),
+                None => seed::empty(),
+            }
+        }],
+        simple_ev(Ev::Click, Msg::ToggleGroup(fingerprint.to_owned()))
+    ]
+}
+
+// The full id, who's behind the channel and what it shows, across the whole table
+fn channel_detail_row(model: &Model, channel: &MarketChannel, columns: usize) -> Node<Msg> {
+    let validators = &channel.spec.validators;
+    let units = channel
+        .spec
+        .ad_units
+        .iter()
+        .map(|unit| unit.title.clone().unwrap_or_else(|| unit.ipfs.clone()))
+        .collect::<Vec<_>>();
+    tr![
+        class!["channel-detail-row"],
+        td![
+            attrs! { At::ColSpan => columns },
+            div![
+                "Channel ",
+                a![attrs! { At::Href => format!("/channel/{}", channel.id) }, channel.id.as_str()]
+            ],
+            div![format!("Advertiser {}", address_name(&model.labels, &channel.creator))],
+            if is_misconfigured(channel) {
+                div!["Validators missing"]
+            } else {
+                div![format!(
+                    "Validators {} (leader), {} (follower)",
+                    validator_name(&model.labels, &validators.leader().url),
+                    validator_name(&model.labels, &validators.follower().url)
+                )]
+            },
+            div![format!("Ad units: {}", units.join(", "))],
+        ]
+    ]
+}
+
+// The distinct leaders of a group, in order of appearance
+fn group_leaders(labels: &labels::Labels, group: &[&MarketChannel]) -> String {
+    let mut hosts = Vec::<String>::new();
+    for channel in group.iter().filter(|channel| !is_misconfigured(channel)) {
+        let host = validator_name(labels, &channel.spec.validators.leader().url);
+        if !hosts.contains(&host) {
+            hosts.push(host);
+        }
+    }
+    hosts.join(", ")
+}
+
+// As it appears in the spec, without the JSON quoting
+fn channel_nonce(channel: &MarketChannel) -> String {
+    serde_json::to_string(&channel.spec.nonce)
+        .map(|nonce| nonce.trim_matches('"').to_owned())
+        .unwrap_or_default()
+}
+
+// The label of a validator if known, otherwise its hostname
+fn validator_name(labels: &labels::Labels, url: &str) -> String {
+    labels
+        .name(url)
+        .map_or_else(|| validator::display_host(url), str::to_owned)
+}
+
+// The label of an address if known, otherwise the address shortened
+fn address_name(labels: &labels::Labels, addr: &str) -> String {
+    labels
+        .name(addr)
+        .map_or_else(|| short_address(addr), str::to_owned)
+}
+
+// A label with the raw value in the tooltip; unknown values render as they are
+fn labeled(labels: &labels::Labels, raw: &str) -> Node<Msg> {
+    match labels.name(raw) {
+        Some(name) => span![class!["label"], attrs! { At::Title => raw }, name],
+        None => span![raw],
+    }
+}
+
+fn top_earner_cell(labels: &labels::Labels, stats: Option<&ChannelStats>) -> Node<Msg> {
+    match stats.and_then(|s| Some((s.top_earner.as_ref()?, s.top_earner_share()?))) {
+        Some(((addr, earned), share)) => td![
+            class!(if share > 50.0 { "concentrated" } else { "" }),
+            attrs! { At::Title => format!(
+                "{}\nEarned: {}\nConcentration: {}",
+                match labels.name(addr) {
+                    Some(name) => format!("{} ({})", name, addr),
+                    None => addr.to_owned(),
+                },
+                exact_wei(earned),
+                concentration_label(stats.map_or(0.0, |s| s.concentration))
+            ) },
+            format!("{:.0}%", share)
+        ],
+        None => td!["—"],
+    }
+}
+
+// The index itself, with a rough reading of it
+fn concentration_label(concentration: f64) -> String {
+    let reading = if concentration > 0.5 {
+        "highly concentrated"
+    } else if concentration > 0.25 {
+        "concentrated"
+    } else {
+        "spread out"
+    };
+    format!("{:.2} ({})", concentration, reading)
+}
+
+// Counted in characters, so a malformed non-ASCII address can't split one in half
+fn short_address(addr: &str) -> String {
+    let chars = addr.char_indices().map(|(i, _)| i).collect::<Vec<_>>();
+    if chars.len() <= 10 {
+        return addr.to_owned();
+    }
+    let head = chars[5];
+    let tail = chars[chars.len() - 3];
+    format!("{}…{}", &addr[..head], &addr[tail..])
+}
+
+// Paid out as a percentage of the deposit, rounded half-up to 3 decimals
+// Both `paid_percent` and `paid_ratio` come from this, so the text and the bars agree
+fn paid_share(paid_total: &BigNum, deposit_amount: &BigNum) -> Option<String> {
+    bignum::ratio_string(&(paid_total * &BigNum::from(100)), deposit_amount, 3)
+}
+
+// Overpaid channels show as more than 100%
+fn paid_percent(paid_total: &BigNum, deposit_amount: &BigNum) -> String {
+    match paid_share(paid_total, deposit_amount) {
+        Some(percent) => format!("{}%", percent),
+        None => "—".to_owned(),
+    }
+}
+
+// Paid out as a share of the deposit, from 0 to 1; overpaid channels are clamped to 1
+fn paid_ratio(paid_total: &BigNum, deposit_amount: &BigNum) -> Option<f64> {
+    let percent = paid_share(paid_total, deposit_amount)?.parse::<f64>().ok()?;
+    Some((percent / 100.0).min(1.0))
+}
+
+// Roughly how many dollars were paid out: the USD estimate of the deposit times the paid share
+// None when there's no usable estimate, rather than showing $0.00 or NaN
+fn channel_paid_usd(channel: &MarketChannel) -> Option<f64> {
+    let usd_estimate = f64::from(channel.status.usd_estimate?);
+    if !usd_estimate.is_finite() || usd_estimate <= 0.0 {
+        return None;
+    }
+    let ratio = paid_ratio(&channel.status.balances_sum(), &channel.deposit_amount)?;
+    Some(usd_estimate * ratio)
+}
+
+// Any amount of the channel in dollars, in proportion to its deposit and USD estimate
+// Unlike channel_paid_usd not capped, and exact enough for amounts as small as the price
+fn channel_usd(channel: &MarketChannel, amount: &BigNum) -> Option<f64> {
+    let usd_estimate = f64::from(channel.status.usd_estimate?);
+    if !usd_estimate.is_finite() || usd_estimate <= 0.0 || channel.deposit_amount == BigNum::from(0) {
+        return None;
+    }
+    Some(usd_estimate * amount.to_f64()? / channel.deposit_amount.to_f64()?)
+}
+
+// Either the percentage, or a bar with the percentage in its tooltip
+fn paid_cell(paid_total: &BigNum, deposit_amount: &BigNum, as_bar: bool) -> Node<Msg> {
+    let percent = paid_percent(paid_total, deposit_amount);
+    match paid_ratio(paid_total, deposit_amount) {
+        Some(ratio) if as_bar => td![
+            attrs! { At::Title => percent },
+            div![
+                class!["paid-bar"],
+                div![
+                    class!["paid-bar-fill"],
+                    attrs! { At::Style => format!("width: {:.1}%", ratio * 100.0) }
+                ]
+            ]
+        ],
+        _ => td![percent],
+    }
 }
 
-fn channel(last_loaded: i64, channel: &MarketChannel) -> Node<Msg> {
+fn channel(model: &Model, channel: &MarketChannel) -> Node<Msg> {
+    let precision = model.settings.dai_precision;
+    let last_loaded = model.last_loaded;
     let deposit_amount = &channel.deposit_amount;
     let paid_total = channel.status.balances_sum();
-    let url = format!(
-        "{}/channel/{}/status",
-        &channel.spec.validators.leader().url,
-        channel.id
-    );
-    let id_prefix = channel.id.chars().take(6).collect::<String>();
+    let id_prefix = channel.id_prefix();
     // This has a tiny issue: when you go back to the explorer after being in another window,
     // stuff will be not-recent until we get the latest status
     tr![
@@ -434,39 +3904,146 @@ fn channel(last_loaded: i64, channel: &MarketChannel) -> Node<Msg> {
                 "recent"
             }
         ),
-        td![a![
-            attrs! {At::Href => url; At::Target => "_blank"},
-            id_prefix
-        ]],
-        td![match channel.status.usd_estimate.as_ref() {
-            Some(usd_estimate) => format!("${:.2}", &usd_estimate),
+        td![checkbox(
+            model.compare.contains(&channel.id),
+            Msg::ToggleCompare(channel.id.clone())
+        )],
+        td![
+            match channel.name(&model.labels) {
+                Some(name) => vec![
+                    a![
+                        class!["channel-title"],
+                        attrs! {At::Href => format!("/channel/{}", channel.id); At::Title => channel.id},
+                        name.as_str()
+                    ],
+                    // A name from the creator's label already ends with it
+                    if name.ends_with(&id_prefix) {
+                        seed::empty()
+                    } else {
+                        span![class!["channel-id"], id_prefix]
+                    },
+                ],
+                None => vec![a![
+                    attrs! {At::Href => format!("/channel/{}", channel.id)},
+                    id_prefix
+                ]],
+            },
+            duplicate_badge(&model.duplicates, &channel.id),
+            owner_mismatch_badge(&model.owner_mismatches, &channel.id),
+            match model.burn_rates.fast(&channel.id) {
+                Some(times) => span![
+                    class!["fast-burn"],
+                    attrs! { At::Title => format!("Paid out {:.0}× as fast as the median campaign since the last refresh", times) },
+                    "🔥"
+                ],
+                None => seed::empty(),
+            },
+            if all_units_archived(channel) {
+                span![
+                    class!["badge archived"],
+                    attrs! { At::Title => "Every ad unit of this campaign has been archived" },
+                    "archived"
+                ]
+            } else {
+                seed::empty()
+            },
+            " ",
+            match channel_status_url(channel) {
+                Some(url) => status_json_link(url),
+                None => seed::empty(),
+            },
+            if is_misconfigured(channel) {
+                span![
+                    class!["badge misconfigured"],
+                    attrs! { At::Title => format!(
+                        "Invalid validator URL: leader {}, follower {}",
+                        channel.spec.validators.leader().url,
+                        channel.spec.validators.follower().url
+                    ) },
+                    "misconfigured"
+                ]
+            } else {
+                seed::empty()
+            },
+            " ",
+            a![
+                class!["raw-json"],
+                attrs! {At::Title => "The channel as received from the market"},
+                "raw",
+                simple_ev(Ev::Click, Msg::OpenRawJson(channel.id.clone()))
+            ],
+            match filters::search_snippet(ad_unit_text(model, channel), &model.filters.search) {
+                Some(snippet) => div![
+                    class!["search-snippet"],
+                    attrs! { At::Title => "Matched in the ad units" },
+                    snippet
+                ],
+                None => seed::empty(),
+            }
+        ],
+        td![match channel.status.usd_estimate {
+            Some(usd_estimate) => usd_readable(usd_estimate),
             None => "N/A".to_string(),
         }],
-        td![dai_readable(deposit_amount)],
-        //td![dai_readable(
-        //    &(&channel.spec.min_per_impression * &1000.into())
-        //)],
-        td![dai_readable(&paid_total)],
+        dai_cell(deposit_amount, channel_usd(channel, deposit_amount), &model.settings),
         td![{
-            let base = 100_000_u64;
-            let paid_units = (&paid_total * &base.into()).div_floor(deposit_amount);
-            let paid_hundreds = paid_units.to_f64().unwrap_or(base as f64) / (base as f64 / 100.0);
-            format!("{:.3}%", paid_hundreds)
+            let cpm = channel.price_per_impression() * &1000.into();
+            match model.settings.amount_unit {
+                AmountUnit::Usd => approximate_usd(channel_usd(channel, &cpm)),
+                unit => format_amount(&cpm, unit, precision, &Locale::en),
+            }
         }],
+        {
+            let fees = channel.validator_fees();
+            dai_cell(&fees, channel_usd(channel, &fees), &model.settings)
+        },
+        if model.settings.show_rate_limits {
+            td![
+                class!["rate-limit"],
+                model.rate_limits.get(&channel.id).map_or("—", String::as_str)
+            ]
+        } else {
+            seed::empty()
+        },
+        dai_cell(&paid_total, channel_paid_usd(channel), &model.settings),
+        paid_cell(&paid_total, deposit_amount, model.settings.paid_bars),
+        td![channel.status.earner_count().to_string()],
+        top_earner_cell(&model.labels, model.channel_stats.get(&channel.id)),
+        match model.filters.publisher_filter() {
+            Some(addr) => match filters::publisher_balance(channel, &addr) {
+                Some(amount) => dai_cell(amount, channel_usd(channel, amount), &model.settings),
+                None => td!["—"],
+            },
+            None => seed::empty(),
+        },
         //td![
         //    (deposit_amount / &channel.spec.min_per_impression)
         //        .to_u64()
         //        .unwrap_or(0)
         //        .to_formatted_string(&Locale::en)
         //],
-        td![format!("{:?}", &channel.status.status_type)],
-        td![time_diff(last_loaded, &channel.spec.created)],
-        //td![time_diff(last_loaded, &channel.status.last_checked)],
+        td![
+            span![
+                class![status_class(&channel.status.status_type)],
+                format!("{:?}", &channel.status.status_type)
+            ],
+            if model.settings.check_validators && !is_misconfigured(channel) {
+                vec![
+                    health_dot(model, &channel.spec.validators.leader().url),
+                    health_dot(model, &channel.spec.validators.follower().url),
+                ]
+            } else {
+                vec![]
+            }
+        ],
+        expiry_cell(channel.time_until_expiry(last_loaded)),
+        time_cell(last_loaded, &channel.spec.created, model.settings.local_time),
+        age_cell(model.now, &channel.spec.created),
         td![class!["preview"], {
             match channel.spec.ad_units.get(0) {
                 Some(unit) => a![
                     attrs! { At::Href => &unit.target_url; At::Target => "_blank" },
-                    unit_preview(&unit)
+                    unit_preview(&unit, &model.settings.ipfs_gateway)
                 ],
                 None => seed::empty(),
             }
@@ -474,48 +4051,188 @@ fn channel(last_loaded: i64, channel: &MarketChannel) -> Node<Msg> {
     ]
 }
 
-fn unit_preview(unit: &AdUnit) -> Node<Msg> {
+// How long ago the campaign was created, e.g. "12d 3h"; `now` is in ms
+fn age_cell(now: f64, created: &DateTime<Utc>) -> Node<Msg> {
+    let age = Duration::milliseconds(now as i64 - created.timestamp_millis());
+    // Our clock may be a bit behind
+    td![duration::compact(age.max(Duration::zero()))]
+}
+
+fn expiry_cell(left: Option<Duration>) -> Node<Msg> {
+    td![match left {
+        Some(left) => duration::compact(left),
+        None => "—".to_owned(),
+    }]
+}
+
+fn health_dot(model: &Model, raw_url: &str) -> Node<Msg> {
+    let url = validator::normalize_url(raw_url);
+    let health = url.base.as_ref().and_then(|base| model.validator_health.get(base));
+    let (class_name, state) = match health {
+        Some(true) => ("health-dot reachable", "reachable"),
+        Some(false) => ("health-dot unreachable", "unreachable"),
+        None => ("health-dot unknown", "unknown"),
+    };
+    span![
+        class![class_name],
+        attrs! { At::Title => format!("{}: {}", model.labels.name(raw_url).unwrap_or(&url.host), state) }
+    ]
+}
+
+fn unit_preview(unit: &AdUnit, gateway: &ipfs::Gateway) -> Node<Msg> {
+    let src = match ipfs::media_url(&unit.media_url, gateway) {
+        Some(src) => src,
+        None => return seed::empty(),
+    };
     if unit.media_mime.starts_with("video/") {
         video![
-            attrs! { At::Src => to_http_url(&unit.media_url); At::AutoPlay => true; At::Loop => true; At::Muted => true }
+            attrs! { At::Src => src; At::AutoPlay => true; At::Loop => true; At::Muted => true }
         ]
     } else {
-        img![attrs! { At::Src => to_http_url(&unit.media_url) }]
+        img![attrs! { At::Src => src }]
     }
 }
 
-fn to_http_url(url: &str) -> String {
-    if url.starts_with("ipfs://") {
-        url.replace("ipfs://", IPFS_GATEWAY)
-    } else {
-        url.to_owned()
+fn status_json_link(url: String) -> Node<Msg> {
+    a![
+        class!["status-json"],
+        attrs! {At::Href => url; At::Target => "_blank"; At::Title => "Raw validator status JSON"},
+        "{ }"
+    ]
+}
+
+fn channel_status_url(channel: &MarketChannel) -> Option<String> {
+    let base = validator::normalize_url(&channel.spec.validators.leader().url).base?;
+    Some(format!("{}/channel/{}/status", base, channel.id))
+}
+
+
+fn time_diff(now_seconds: i64, t: &DateTime<Utc>, local: bool) -> String {
+    let elapsed = Duration::seconds(now_seconds - t.timestamp());
+    match elapsed {
+        // Our clock may be a bit behind
+        x if x < Duration::zero() => "just now".to_string(),
+        x if x < Duration::days(1) => duration::humanize_past(x),
+        _ => timestamps::format(t, local, "%Y-%m-%d"),
+    }
+}
+
+// A relative time, with the exact one in the tooltip
+fn time_cell(now_seconds: i64, t: &DateTime<Utc>, local: bool) -> Node<Msg> {
+    td![
+        attrs! { At::Title => timestamps::exact(t, local) },
+        time_diff(now_seconds, t, local)
+    ]
+}
+
+// "~$1.23", or "N/A" when the channel has no usable USD estimate
+fn approximate_usd(usd: Option<f64>) -> String {
+    usd.map_or("N/A".to_owned(), |usd| format!("~{}", usd_readable(usd as f32)))
+}
+
+fn usd_readable(usd: f32) -> String {
+    if !usd.is_finite() || usd < 0.0 {
+        return "—".to_owned();
     }
+    let cents = (f64::from(usd) * 100.0).round() as u64;
+    format!(
+        "${}.{:02}",
+        (cents / 100).to_formatted_string(&Locale::en),
+        cents % 100
+    )
 }
 
-fn time_diff(now_seconds: i64, t: &DateTime<Utc>) -> String {
-    let time_diff = now_seconds - t.timestamp();
-    match time_diff {
-        x if x < 0 => "just now".to_string(),
-        x if x < 60 => format!("{} seconds ago", x),
-        x if x < 3600 => format!("{} minutes ago", x / 60),
-        x if x < 86400 => format!("{} hours ago", x / 3600),
-        _ => format!("{}", t.format("%Y-%m-%d")), // %T if we want time
+// `precision` is the number of decimals shown, at most 18
+// Rounded half-up, in integer math, so large amounts don't lose digits to a float
+fn dai_readable(bal: &BigNum, precision: u8) -> String {
+    let one_dai = BigNum::from(10u64.pow(18));
+    match bignum::ratio_string(bal, &one_dai, u32::from(precision.min(18))) {
+        Some(amount) => format!("{} DAI", amount),
+        None => ">max".to_owned(),
     }
 }
 
-fn dai_readable(bal: &BigNum) -> String {
-    // 10 ** 16`
-    match bal.div_floor(&10_000_000_000_000_000u64.into()).to_f64() {
-        Some(hundreds) => format!("{:.2} DAI", hundreds / 100.0),
+// e.g. "1.23M DAI"; the largest of K/M/B whose rounded value is at least 1, so 999,999 DAI is
+// "1.00M DAI" rather than "1000.00K DAI"; smaller amounts keep two decimals
+fn abbreviate_dai(bal: &BigNum) -> String {
+    const SUFFIXES: [(u32, &str); 3] = [(9, "B"), (6, "M"), (3, "K")];
+    let one_dai = BigNum::from(10u64.pow(18));
+    for &(exponent, suffix) in SUFFIXES.iter() {
+        let unit = &one_dai * &BigNum::from(10u64.pow(exponent));
+        match bignum::ratio_string(bal, &unit, 2) {
+            Some(amount) if !amount.starts_with("0.") => {
+                return format!("{}{} DAI", amount, suffix);
+            }
+            Some(_) => continue,
+            None => return ">max".to_owned(),
+        }
+    }
+    match bignum::ratio_string(bal, &one_dai, 2) {
+        Some(amount) => format!("{} DAI", amount),
         None => ">max".to_owned(),
     }
 }
 
+// An amount in the unit of the settings; `usd` is the same amount in dollars, shown instead when
+// the unit is USD, since format_amount can't convert a bare amount
+fn amount_text(bal: &BigNum, usd: Option<f64>, settings: &Settings) -> String {
+    unit_amount_text(bal, usd, settings.amount_unit, settings.dai_precision)
+}
+
+fn unit_amount_text(bal: &BigNum, usd: Option<f64>, unit: AmountUnit, precision: u8) -> String {
+    match unit {
+        AmountUnit::Usd => approximate_usd(usd),
+        unit => format_amount(bal, unit, precision, &Locale::en),
+    }
+}
+
+// An amount that belongs to no one channel, so there's no estimate to put it in dollars with; in
+// USD it's shown in DAI, labelled as such
+fn plain_amount_text(bal: &BigNum, settings: &Settings) -> String {
+    format_amount(bal, settings.amount_unit, settings.dai_precision, &Locale::en)
+}
+
+// A column of such an amount, in the tables of aggregates
+fn plain_amount_cell(bal: &BigNum, settings: &Settings) -> Node<Msg> {
+    td![amount_value(plain_amount_text(bal, settings), bal)]
+}
+
+// `text` showing `bal`, with the exact wei on hover, as every amount has
+fn amount_value(text: String, bal: &BigNum) -> Node<Msg> {
+    span![attrs! { At::Title => exact_wei(bal) }, text]
+}
+
+// An amount of `channel`, converted with its own USD estimate when the unit is USD
+fn channel_amount_text(channel: &MarketChannel, bal: &BigNum, settings: &Settings) -> String {
+    amount_text(bal, channel_usd(channel, bal), settings)
+}
+
+// An amount column in the unit of the settings; DAI is abbreviated if the settings say so,
+// with the full amount on hover, and the exact wei are always on hover
+// `usd` is the same amount in dollars, shown instead when the unit is USD
+fn dai_cell(bal: &BigNum, usd: Option<f64>, settings: &Settings) -> Node<Msg> {
+    let full = format_amount(bal, settings.amount_unit, settings.dai_precision, &Locale::en);
+    match settings.amount_unit {
+        AmountUnit::Wei => td![full],
+        AmountUnit::Usd => td![
+            attrs! { At::Title => format!("{}\n{}", full, exact_wei(bal)) },
+            approximate_usd(usd)
+        ],
+        AmountUnit::Dai if settings.compact_dai => td![
+            attrs! { At::Title => format!("{}\n{}", full, exact_wei(bal)) },
+            abbreviate_dai(bal)
+        ],
+        _ => td![attrs! { At::Title => exact_wei(bal) }, full],
+    }
+}
+
 // Router
 fn routes(url: seed::Url) -> Msg {
     match url.path.get(0).map(|x| x.as_ref()) {
         Some("channels") => Msg::Load(ActionLoad::ChannelsActive),
         Some("channels-all") => Msg::Load(ActionLoad::ChannelsAll),
+        Some("months") => Msg::Load(ActionLoad::Cohorts),
+        Some("slots") => Msg::Load(ActionLoad::Slots),
         Some("channel") => match url.path.get(1) {
             Some(id) => Msg::Load(ActionLoad::ChannelDetail(id.to_string())),
             None => Msg::Load(ActionLoad::Summary),
@@ -524,12 +4241,30 @@ fn routes(url: seed::Url) -> Msg {
     }
 }
 
+// `labels_url` optionally points to a JSON object of extra display names, see labels.rs
 #[wasm_bindgen]
-pub fn render() {
+pub fn render(labels_url: Option<String>) {
     let state = seed::App::build(
         |url, orders| {
             orders.send_msg(routes(url));
-            Model::default()
+            let mut model = Model {
+                settings: settings::load_settings(),
+                filters: settings::load_filters(),
+                now: js_sys::Date::now(),
+                next_refresh_at: js_sys::Date::now() + f64::from(REFRESH_MS),
+                offline: !seed::window().navigator().on_line(),
+                log_updates: debug::logging_requested(),
+                debug_open: seed::window()
+                    .location()
+                    .hash()
+                    .map_or(false, |hash| hash == debug::HASH),
+                ..Model::default()
+            };
+            let search = seed::window().location().search().unwrap_or_default();
+            for warning in restore_view(&mut model, &search) {
+                log!(warning);
+            }
+            model
         },
         update,
         view,
@@ -538,5 +4273,1033 @@ pub fn render() {
     .finish()
     .run();
 
-    seed::set_interval(Box::new(move || state.update(Msg::Refresh)), REFRESH_MS);
+    let keydown_state = state.clone();
+    let on_keydown = Closure::wrap(Box::new(move |ev: web_sys::KeyboardEvent| {
+        if let Some(msg) = handle_keydown(&ev) {
+            keydown_state.update(msg);
+        }
+    }) as Box<dyn FnMut(web_sys::KeyboardEvent)>);
+    seed::window()
+        .add_event_listener_with_callback("keydown", on_keydown.as_ref().unchecked_ref())
+        .expect("add keydown listener");
+    on_keydown.forget();
+
+    for &(event, online) in &[("online", true), ("offline", false)] {
+        let connectivity_state = state.clone();
+        let on_change = Closure::wrap(Box::new(move || {
+            connectivity_state.update(Msg::ConnectivityChanged(online));
+        }) as Box<dyn FnMut()>);
+        seed::window()
+            .add_event_listener_with_callback(event, on_change.as_ref().unchecked_ref())
+            .expect("add connectivity listener");
+        on_change.forget();
+    }
+
+    if let Some(url) = labels_url {
+        state.update(Msg::LoadLabels(url));
+    }
+
+    if presentation::requested() {
+        state.update(Msg::TogglePresentation);
+    }
+
+    seed::set_interval(
+        Box::new(move || state.update(Msg::Tick(js_sys::Date::now()))),
+        TICK_MS,
+    );
+}
+
+// Global shortcuts: "/" focuses the search box, Escape clears and leaves it (or closes the raw JSON
+// modal), Shift+D toggles the debug panel
+fn handle_keydown(ev: &web_sys::KeyboardEvent) -> Option<Msg> {
+    let focused = seed::document().active_element();
+    let in_search = focused.as_ref().map_or(false, |el| el.id() == SEARCH_INPUT_ID);
+    let in_other_field = focused.as_ref().map_or(false, |el| {
+        match &el.tag_name().to_uppercase() as &str {
+            "INPUT" | "TEXTAREA" | "SELECT" => !in_search,
+            _ => false,
+        }
+    });
+    match &ev.key() as &str {
+        "D" if !in_search && !in_other_field => Some(Msg::ToggleDebug),
+        "/" if !in_search && !in_other_field => {
+            ev.prevent_default();
+            search_input()?.focus().ok();
+            None
+        }
+        "Escape" if in_search => {
+            let search = search_input()?;
+            search.set_value("");
+            search.blur().ok();
+            Some(Msg::SearchChanged(String::new()))
+        }
+        "Escape" => Some(Msg::CloseRawJson),
+        _ => None,
+    }
+}
+
+fn search_input() -> Option<web_sys::HtmlInputElement> {
+    seed::document()
+        .get_element_by_id(SEARCH_INPUT_ID)?
+        .dyn_into::<web_sys::HtmlInputElement>()
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_the_latest_search_is_applied() {
+        let mut model = Model::default();
+        let first = schedule_search(&mut model, "a".to_owned());
+        let second = schedule_search(&mut model, "ad".to_owned());
+        assert_ne!(first, second);
+
+        // The first keystroke's timeout fires while the second one is pending
+        assert!(!apply_search(&mut model, first));
+        assert_eq!(model.filters.search, "");
+
+        assert!(apply_search(&mut model, second));
+        assert_eq!(model.filters.search, "ad");
+    }
+
+    #[test]
+    fn a_stale_search_does_not_undo_a_newer_one() {
+        let mut model = Model::default();
+        let first = schedule_search(&mut model, "adex".to_owned());
+        assert!(apply_search(&mut model, first));
+        let second = schedule_search(&mut model, "".to_owned());
+        assert!(apply_search(&mut model, second));
+
+        // A late duplicate of the first timeout
+        assert!(!apply_search(&mut model, first));
+        assert_eq!(model.filters.search, "");
+    }
+
+    fn wei(amount: &str) -> BigNum {
+        fixtures::big(amount)
+    }
+
+    #[test]
+    fn dai_readable_rounds_half_up_at_the_last_decimal() {
+        assert_eq!(dai_readable(&wei("1005000000000000000"), 2), "1.01 DAI");
+        assert_eq!(dai_readable(&wei("1004999999999999999"), 2), "1.00 DAI");
+        assert_eq!(dai_readable(&wei("1999000000000000000"), 2), "2.00 DAI");
+        assert_eq!(dai_readable(&wei("5000000000000000"), 2), "0.01 DAI");
+        assert_eq!(dai_readable(&wei("4999999999999999"), 2), "0.00 DAI");
+        assert_eq!(dai_readable(&wei("0"), 2), "0.00 DAI");
+    }
+
+    #[test]
+    fn dai_readable_at_precision_0_and_6() {
+        assert_eq!(dai_readable(&wei("1499999999999999999"), 0), "1 DAI");
+        assert_eq!(dai_readable(&wei("1500000000000000000"), 0), "2 DAI");
+        assert_eq!(dai_readable(&wei("0"), 0), "0 DAI");
+        assert_eq!(dai_readable(&wei("1234567500000000000"), 6), "1.234568 DAI");
+        assert_eq!(dai_readable(&wei("1234567499999999999"), 6), "1.234567 DAI");
+        assert_eq!(dai_readable(&wei("500000000000"), 6), "0.000001 DAI");
+    }
+
+    #[test]
+    fn dai_readable_keeps_every_digit_of_large_amounts() {
+        // 123,456,789,012,345.67 DAI; an f64 only has room for about 16 significant digits
+        assert_eq!(
+            dai_readable(&wei("123456789012345670000000000000000"), 2),
+            "123456789012345.67 DAI"
+        );
+        assert_eq!(dai_readable(&wei("1"), 18), "0.000000000000000001 DAI");
+    }
+
+    fn with_stats(model: &mut Model, channels: &[MarketChannel]) {
+        model.channel_stats = channels
+            .iter()
+            .map(|c| (c.id.clone(), ChannelStats::from_status(&c.status)))
+            .collect();
+    }
+
+    fn sorted_ids(model: &Model, channels: &mut Vec<MarketChannel>) -> Vec<String> {
+        channels.sort_by(|x, y| compare_channels(model, x, y));
+        channels.iter().map(|c| c.id.clone()).collect()
+    }
+
+    #[test]
+    fn sort_by_publisher_count_breaks_ties_by_paid() {
+        let mut channels = vec![
+            fixtures::channel_with_balances("a", &[("0x1", 10), ("0x2", 10), ("0x3", 10)]),
+            fixtures::channel_with_balances("b", &[("0x1", 5000)]),
+            fixtures::channel_with_balances("c", &[("0x1", 10), ("0x2", 10), ("0x3", 20)]),
+            fixtures::channel_with_balances("d", &[("0x1", 5)]),
+            fixtures::channel_with_balances("e", &[]),
+        ];
+        let mut model = Model::default();
+        model.settings.sort = ChannelSort::PublisherCount;
+        with_stats(&mut model, &channels);
+        assert_eq!(sorted_ids(&model, &mut channels), vec!["c", "a", "b", "d", "e"]);
+    }
+
+    #[test]
+    fn ties_are_sorted_by_id_whatever_the_input_order() {
+        let sorts = [
+            ChannelSort::Deposit,
+            ChannelSort::Status,
+            ChannelSort::Created,
+            ChannelSort::PublisherCount,
+            ChannelSort::Price,
+            ChannelSort::Expiry,
+            ChannelSort::Age,
+            ChannelSort::Fees,
+        ];
+        // The same campaign under different ids, so every sort key is tied
+        let ids = ["0x04", "0x02", "0x01", "0x03"];
+        for &sort in sorts.iter() {
+            let mut model = Model::default();
+            model.settings.sort = sort;
+            for shift in 0..ids.len() {
+                let mut channels = ids
+                    .iter()
+                    .cycle()
+                    .skip(shift)
+                    .take(ids.len())
+                    .map(|id| fixtures::channel(id))
+                    .collect::<Vec<_>>();
+                with_stats(&mut model, &channels);
+                assert_eq!(
+                    sorted_ids(&model, &mut channels),
+                    vec!["0x01", "0x02", "0x03", "0x04"],
+                    "{:?}, shifted by {}",
+                    sort,
+                    shift
+                );
+            }
+        }
+    }
+
+    fn with_leader_url(url: &str) -> MarketChannel {
+        let mut json = fixtures::channel_json("0xabc");
+        json["spec"]["validators"][0]["url"] = serde_json::json!(url);
+        fixtures::parse(json)
+    }
+
+    #[test]
+    fn status_url_is_built_on_valid_validator_urls() {
+        assert_eq!(
+            channel_status_url(&with_leader_url("https://tom.adex.network/")),
+            Some("https://tom.adex.network/channel/0xabc/status".to_owned())
+        );
+        assert_eq!(
+            channel_status_url(&with_leader_url(" http://localhost:8005 ")),
+            Some("http://localhost:8005/channel/0xabc/status".to_owned())
+        );
+    }
+
+    #[test]
+    fn no_status_url_on_malformed_validator_urls() {
+        for url in &[
+            "",
+            "tom.adex.network",
+            "ftp://tom.adex.network",
+            "javascript:alert(1)",
+            "https://",
+            "https://tom adex.network",
+            "https://tom.adex.network:port",
+            "https://<script>.network",
+        ] {
+            assert_eq!(channel_status_url(&with_leader_url(url)), None, "{}", url);
+        }
+    }
+
+    // Serves campaigns the way the market does, from memory
+    struct MockMarket {
+        channels: Vec<serde_json::Value>,
+    }
+
+    impl MockMarket {
+        fn with_channels(count: usize) -> Self {
+            MockMarket {
+                channels: (0..count)
+                    .map(|i| fixtures::channel_json(&format!("0x{:04x}", i)))
+                    .collect(),
+            }
+        }
+
+        // `?limit=CAMPAIGNS_PAGE_SIZE&skip=skip`
+        fn page(&self, skip: usize) -> PageResponse {
+            let start = skip.min(self.channels.len());
+            let end = (skip + CAMPAIGNS_PAGE_SIZE).min(self.channels.len());
+            PageResponse::Body {
+                etag: None,
+                body: serde_json::to_string(&self.channels[start..end]).unwrap(),
+            }
+        }
+
+        // `?all`
+        fn all(&self) -> Vec<MarketChannel> {
+            serde_json::from_str(&serde_json::to_string(&self.channels).unwrap()).unwrap()
+        }
+    }
+
+    // Runs a paged load against the market until it's no longer fetching pages
+    fn load_pages(model: &mut Model, market: &MockMarket) -> PageStep {
+        assert!(start_paging(model));
+        let mut skip = 0;
+        loop {
+            match next_page_step(skip, market.page(skip), model) {
+                PageStep::Fetch { skip: next, .. } => skip = next,
+                step => return step,
+            }
+        }
+    }
+
+    fn as_json(channels: &[MarketChannel]) -> serde_json::Value {
+        serde_json::to_value(channels).unwrap()
+    }
+
+    #[test]
+    fn merged_pages_equal_the_full_listing() {
+        // A short last page, an empty last page and a single short page
+        let sizes = [CAMPAIGNS_PAGE_SIZE * 2 + 50, CAMPAIGNS_PAGE_SIZE * 2, 7, 0];
+        for &size in sizes.iter() {
+            let market = MockMarket::with_channels(size);
+            let mut model = Model::default();
+            match load_pages(&mut model, &market) {
+                PageStep::Loaded(channels) => {
+                    assert_eq!(as_json(&channels), as_json(&market.all()), "{}", size)
+                }
+                _ => panic!("the load of {} campaigns did not complete", size),
+            }
+            assert!(model.campaigns_paging.is_none());
+        }
+    }
+
+    #[test]
+    fn the_first_page_is_shown_before_the_rest() {
+        let market = MockMarket::with_channels(CAMPAIGNS_PAGE_SIZE + 1);
+        let mut model = Model::default();
+        assert!(start_paging(&mut model));
+        match next_page_step(0, market.page(0), &mut model) {
+            PageStep::Fetch { skip, render } => {
+                assert_eq!(skip, CAMPAIGNS_PAGE_SIZE);
+                assert!(render);
+            }
+            _ => panic!("expected the second page to be fetched"),
+        }
+        assert!(model.channels_partial);
+        assert_eq!(channel_count(&model), CAMPAIGNS_PAGE_SIZE);
+    }
+
+    #[test]
+    fn reloading_the_same_pages_is_unchanged() {
+        let market = MockMarket::with_channels(CAMPAIGNS_PAGE_SIZE + 30);
+        let mut model = Model::default();
+        match load_pages(&mut model, &market) {
+            PageStep::Loaded(channels) => set_channels(&mut model, channels),
+            _ => panic!("the first load did not complete"),
+        }
+        model.channels_partial = false;
+        match load_pages(&mut model, &market) {
+            PageStep::Unchanged => {}
+            _ => panic!("expected the second load to be unchanged"),
+        }
+    }
+
+    #[test]
+    fn a_failed_page_is_retried_alone() {
+        let market = MockMarket::with_channels(CAMPAIGNS_PAGE_SIZE + 30);
+        let mut model = Model::default();
+        assert!(start_paging(&mut model));
+        next_page_step(0, market.page(0), &mut model);
+        let failure = PageResponse::Failed(FetchError::Http(503, "Service Unavailable".to_owned()));
+        match next_page_step(CAMPAIGNS_PAGE_SIZE, failure, &mut model) {
+            PageStep::Retry { skip, .. } => assert_eq!(skip, CAMPAIGNS_PAGE_SIZE),
+            _ => panic!("expected the page to be retried"),
+        }
+        match next_page_step(CAMPAIGNS_PAGE_SIZE, market.page(CAMPAIGNS_PAGE_SIZE), &mut model) {
+            PageStep::Loaded(channels) => assert_eq!(as_json(&channels), as_json(&market.all())),
+            _ => panic!("the load did not complete"),
+        }
+    }
+
+    #[test]
+    fn a_page_that_keeps_failing_fails_the_load() {
+        let mut model = Model::default();
+        assert!(start_paging(&mut model));
+        let failure = || PageResponse::Failed(FetchError::Timeout);
+        for _ in 0..CAMPAIGNS_PAGE_RETRIES {
+            match next_page_step(0, failure(), &mut model) {
+                PageStep::Retry { skip, .. } => assert_eq!(skip, 0),
+                _ => panic!("expected the page to be retried"),
+            }
+        }
+        match next_page_step(0, failure(), &mut model) {
+            PageStep::Failed(_) => {}
+            _ => panic!("expected the load to fail"),
+        }
+        assert!(model.campaigns_paging.is_none());
+    }
+
+    #[test]
+    fn an_unparseable_page_fails_without_retrying() {
+        let mut model = Model::default();
+        assert!(start_paging(&mut model));
+        let resp = PageResponse::Body {
+            etag: None,
+            body: r#"[{"id": "0x01"}]"#.to_owned(),
+        };
+        match next_page_step(0, resp, &mut model) {
+            PageStep::Failed(FetchError::Deserialize(_)) => {}
+            _ => panic!("expected a deserialize failure"),
+        }
+        assert_eq!(model.parse_failures, 1);
+        assert!(model.campaigns_paging.is_none());
+    }
+
+    // `dai` whole DAI and `wei` more
+    fn dai(dai: u64, wei: u64) -> BigNum {
+        &(&BigNum::from(dai) * &BigNum::from(10u64.pow(18))) + &BigNum::from(wei)
+    }
+
+    #[test]
+    fn abbreviate_dai_switches_suffix_once_the_rounded_value_reaches_1() {
+        let cents = 10u64.pow(16);
+        let cases = [
+            (dai(0, 0), "0.00 DAI"),
+            (dai(12, 34 * cents), "12.34 DAI"),
+            (dai(994, 99 * cents), "994.99 DAI"),
+            (dai(995, 0), "1.00K DAI"),
+            (dai(1000, 0), "1.00K DAI"),
+            (dai(12_345, 0), "12.35K DAI"),
+            (dai(994_999, 0), "995.00K DAI"),
+            (dai(995_000, 0), "1.00M DAI"),
+            (dai(999_999, 0), "1.00M DAI"),
+            (dai(1_500_000_000, 0), "1.50B DAI"),
+            (dai(1_000_000_000_000, 0), "1000.00B DAI"),
+        ];
+        for (amount, expected) in cases.iter() {
+            assert_eq!(abbreviate_dai(amount), *expected);
+        }
+    }
+
+    #[test]
+    fn abbreviate_dai_beyond_a_u64_of_billions() {
+        let huge = &dai(u64::max_value(), 0) * &BigNum::from(10u64.pow(10));
+        assert_eq!(abbreviate_dai(&huge), ">max");
+    }
+
+    #[test]
+    fn ages_change_once_a_minute() {
+        let minute = 60_000.0;
+        assert!(!ages_changed(10.0 * minute, 10.0 * minute + 59_999.0));
+        assert!(ages_changed(10.0 * minute + 59_999.0, 11.0 * minute));
+        // Ticks can be late, or skipped while the tab was in the background
+        assert!(ages_changed(10.0 * minute + 30_000.0, 12.0 * minute + 10_000.0));
+        assert!(!ages_changed(10.0 * minute, 10.0 * minute));
+    }
+
+    #[test]
+    fn stale_message_moves_on_with_the_age() {
+        let mut model = Model::default();
+        // Not on a whole minute, like the load times
+        let at = fixtures::CREATED_MS as f64 + 12_345.0;
+        model.last_success = Some(at);
+        model.now = at + STALE_MS;
+        assert_eq!(stale_message(&model), None);
+        model.now = at + STALE_MS + 1.0;
+        assert_eq!(
+            stale_message(&model),
+            Some("This data is stale: it was last updated 2 minutes ago (13:20 UTC)".to_owned())
+        );
+        model.now = at + 3.0 * 60_000.0 - 1.0;
+        assert!(stale_message(&model).unwrap().contains(" 2 minutes ago "));
+        model.now = at + 3.0 * 60_000.0;
+        assert!(stale_message(&model).unwrap().contains(" 3 minutes ago "));
+    }
+
+    #[test]
+    fn heartbeat_ages_are_timely_text() {
+        let mut model = Model::default();
+        let signed = Utc.timestamp_millis(fixtures::CREATED_MS);
+        model.heartbeats = Some((
+            "a".to_owned(),
+            Heartbeats {
+                leader: Heartbeat::Signed(signed),
+                follower: Heartbeat::Missing,
+            },
+        ));
+        model.now = fixtures::CREATED_MS as f64 + 5_000.0;
+        let before = timely_text(&model);
+        assert_eq!(
+            before.1,
+            Some([("5 seconds ago".to_owned(), false), ("none".to_owned(), true)])
+        );
+        model.now += 999.0;
+        assert_eq!(timely_text(&model), before);
+        model.now += 1.0;
+        assert_ne!(timely_text(&model), before);
+    }
+
+    #[test]
+    fn sort_by_expiry_puts_expired_and_unknown_last() {
+        let expiring = |id: &str, valid_until: serde_json::Value| {
+            let mut json = fixtures::channel_json(id);
+            json["validUntil"] = valid_until;
+            fixtures::parse(json)
+        };
+        let now = fixtures::VALID_UNTIL;
+        let mut channels = vec![
+            expiring("none", serde_json::Value::Null),
+            expiring("later", (now + 7200).into()),
+            expiring("expired", (now - 60).into()),
+            expiring("soon", (now + 60).into()),
+        ];
+        let mut model = Model::default();
+        model.settings.sort = ChannelSort::Expiry;
+        model.last_loaded = now;
+        assert_eq!(
+            sorted_ids(&model, &mut channels),
+            vec!["soon", "later", "expired", "none"]
+        );
+    }
+
+    fn row_keys(model: &Model) -> Vec<String> {
+        let channels = match &model.market_channels {
+            Ready(channels) => channels.iter().collect::<Vec<_>>(),
+            Loading => Vec::new(),
+        };
+        table_rows(model, &channels).iter().map(TableRow::key).collect()
+    }
+
+    // Runs in the browser with `cargo make test_h firefox`, as well as natively
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    fn an_expanded_group_stays_expanded_after_a_refresh() {
+        // Two copies of one campaign, and another one
+        let copy = |id: &str| {
+            let mut json = fixtures::channel_json(id);
+            json["spec"]["title"] = serde_json::json!("Republished");
+            json
+        };
+        let load = vec![copy("0xa1"), copy("0xa2"), fixtures::channel_json("0xb1")];
+
+        let mut model = Model::default();
+        model.settings.group_duplicates = true;
+        set_channels(&mut model, load.iter().cloned().map(fixtures::parse).collect());
+        let fingerprint = fixtures::parse(copy("0xa1")).fingerprint();
+        model.expanded_groups.insert(fingerprint.clone());
+        let before = row_keys(&model);
+        assert_eq!(before.len(), 4);
+        assert!(before.contains(&"row-0xa1".to_owned()));
+
+        // The refresh brings new balances for the same campaigns
+        let refreshed = load
+            .into_iter()
+            .map(|mut json| {
+                json["status"]["lastApprovedBalances"] = serde_json::json!({ "0x1": "1000" });
+                fixtures::parse(json)
+            })
+            .collect();
+        set_channels(&mut model, refreshed);
+        assert!(model.expanded_groups.contains(&fingerprint));
+        assert_eq!(row_keys(&model), before);
+    }
+
+    #[test]
+    fn short_address_keeps_both_ends() {
+        assert_eq!(
+            short_address("0xb7d3f81e857692d13e9d63b232a90f4a1793189e"),
+            "0xb7d…89e"
+        );
+        assert_eq!(short_address("0x12345678"), "0x12345678");
+        assert_eq!(short_address(""), "");
+    }
+
+    #[test]
+    fn short_address_does_not_split_characters() {
+        assert_eq!(short_address("0xäöüäöüäöüäöü"), "0xäöü…äöü");
+        assert_eq!(short_address("ÿÿÿÿÿÿÿÿÿÿÿ"), "ÿÿÿÿÿ…ÿÿÿ");
+        assert_eq!(short_address("ÿÿÿÿÿÿÿÿÿÿ"), "ÿÿÿÿÿÿÿÿÿÿ");
+    }
+
+    #[test]
+    fn paid_percent_and_paid_ratio_round_the_same_way() {
+        let deposit = BigNum::from(3);
+        assert_eq!(paid_percent(&BigNum::from(1), &deposit), "33.333%");
+        let ratio = paid_ratio(&BigNum::from(1), &deposit).unwrap();
+        assert!((ratio - 0.33333).abs() < 1e-12);
+        assert_eq!(paid_percent(&BigNum::from(2), &deposit), "66.667%");
+        let ratio = paid_ratio(&BigNum::from(2), &deposit).unwrap();
+        assert!((ratio - 0.66667).abs() < 1e-12);
+        // Just short of everything still shows as all of it in both
+        let deposit = dai(1000, 0);
+        let almost = &deposit - &BigNum::from(1);
+        assert_eq!(paid_percent(&almost, &deposit), "100.000%");
+        assert_eq!(paid_ratio(&almost, &deposit), Some(1.0));
+    }
+
+    #[test]
+    fn paid_ratio_clamps_overpaid_channels() {
+        let deposit = dai(1000, 0);
+        let overpaid = dai(1500, 0);
+        assert_eq!(paid_percent(&overpaid, &deposit), "150.000%");
+        assert_eq!(paid_ratio(&overpaid, &deposit), Some(1.0));
+        assert_eq!(paid_percent(&overpaid, &BigNum::from(0)), "—");
+        assert_eq!(paid_ratio(&overpaid, &BigNum::from(0)), None);
+    }
+
+    #[test]
+    fn share_link_carries_the_sort_and_the_filters() {
+        let mut model = Model::default();
+        model.settings.sort = ChannelSort::Expiry;
+        model.filters.search = "ad ex".to_owned();
+        model.filters.publisher = fixtures::LEADER.to_owned();
+        model.filters.status.insert(MarketStatusType::Active);
+        model.filters.status.insert(MarketStatusType::Exhausted);
+        model.filters.set_checked_from("2019-06-01".to_owned());
+        model.filters.set_usd_max("500".to_owned());
+        model.filters.tag = "crypto".to_owned();
+        model.filters.tag_min_score = 50.0;
+        model.filters.hide_unpaid = true;
+        let link = share_link_from("https://explorer.adex.network/channels?debug#debug", &model);
+        assert_eq!(
+            link,
+            format!(
+                "https://explorer.adex.network/channels?sort=expiry&q=ad%20ex&publisher={}\
+                 &checked_from=2019-06-01&usd_max=500&tag=crypto&tag_score=50\
+                 &hide_unpaid=1&status=Active&status=Exhausted",
+                fixtures::LEADER
+            )
+        );
+
+        let mut restored = Model::default();
+        let search = &link[link.find('?').unwrap()..];
+        assert!(restore_view(&mut restored, search).is_empty());
+        assert_eq!(restored.settings.sort, ChannelSort::Expiry);
+        assert_eq!(restored.search_input, "ad ex");
+        assert_eq!(restored.filters.to_query(), model.filters.to_query());
+        assert_eq!(restored.filters.checked_error(), None);
+        assert!(restored.filters.hide_unpaid);
+        assert_eq!(restored.filters.active_count(), model.filters.active_count());
+    }
+
+    #[test]
+    fn share_link_without_filters_still_has_the_sort() {
+        let model = Model::default();
+        assert_eq!(
+            share_link_from("http://localhost:8000/", &model),
+            "http://localhost:8000/?sort=deposit"
+        );
+    }
+
+    #[test]
+    fn restore_view_skips_what_it_does_not_understand() {
+        let mut model = Model::default();
+        let warnings = restore_view(&mut model, "?status=Activ&status=Active&tag_score=x&present=1");
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(
+            model.filters.status,
+            std::iter::once(MarketStatusType::Active).collect()
+        );
+        assert_eq!(model.filters.tag_min_score, 0.0);
+        assert_eq!(model.settings.sort, ChannelSort::default());
+    }
+
+    #[test]
+    fn a_plain_visit_keeps_the_saved_filters() {
+        let mut model = Model::default();
+        model.filters.search = "ad ex".to_owned();
+        model.filters.hide_unpaid = true;
+        assert!(restore_view(&mut model, "?debug").is_empty());
+        assert_eq!(model.filters.search, "ad ex");
+        assert_eq!(model.search_input, "ad ex");
+        assert!(model.filters.hide_unpaid);
+
+        // A share link brings its own, even if that's none
+        assert!(restore_view(&mut model, "?sort=expiry").is_empty());
+        assert_eq!(model.filters.active_count(), 0);
+        assert_eq!(model.search_input, "");
+    }
+
+    #[test]
+    fn distinct_earners_counts_each_publisher_once_and_no_creators() {
+        let leader = fixtures::LEADER.to_lowercase();
+        let channels = vec![
+            fixtures::channel_with_balances("a", &[(fixtures::LEADER, 10), (fixtures::CREATOR, 5)]),
+            fixtures::channel_with_balances("b", &[(&leader, 20), (fixtures::FOLLOWER, 1)]),
+            fixtures::channel("c"),
+        ];
+        assert_eq!(distinct_earners(&channels), 2);
+        assert_eq!(distinct_earners(&channels[2..]), 0);
+    }
+
+    #[test]
+    fn all_units_archived_needs_every_unit_archived() {
+        let live = "QmcUVX7fvoLMM93uN2bD3wGTH8MXSxeL8hojYfL2Lhp7mR";
+        let archived = "QmYwcfTCE1SqHxLbTnaBx5bKoPpCw8jK2NsxcFLRM5AxQV";
+        let with_units = |units: serde_json::Value| {
+            let mut json = fixtures::channel_json("a");
+            json["spec"]["adUnits"] = units;
+            fixtures::parse(json)
+        };
+        assert!(all_units_archived(&with_units(serde_json::json!([
+            fixtures::archived_ad_unit(live),
+            fixtures::archived_ad_unit(archived)
+        ]))));
+        assert!(!all_units_archived(&with_units(serde_json::json!([
+            fixtures::ad_unit(live),
+            fixtures::archived_ad_unit(archived)
+        ]))));
+        // Nothing to serve either, but nothing was archived
+        assert!(!all_units_archived(&with_units(serde_json::json!([]))));
+        assert!(!all_units_archived(&fixtures::channel("b")));
+    }
+
+    #[test]
+    fn channels_without_validators_are_misconfigured_but_counted() {
+        let mut json = fixtures::channel_json("none");
+        json["spec"]["validators"] = serde_json::json!([]);
+        let without = fixtures::parse(json);
+        let with = fixtures::channel("some");
+        assert!(is_misconfigured(&without));
+        assert!(!is_misconfigured(&with));
+        assert_eq!(without.validator_fees(), BigNum::from(0));
+
+        // Deposits count, validators don't
+        let totals = Totals::of(&[without.clone(), with.clone()]);
+        assert_eq!(totals.deposit, dai(2000, 0));
+        let labels = labels::Labels::default();
+        assert_eq!(
+            group_leaders(&labels, &[&without, &with]),
+            group_leaders(&labels, &[&with])
+        );
+        assert_eq!(group_leaders(&labels, &[&without]), "");
+        assert_eq!(channel_status_url(&without), None);
+    }
+
+    #[test]
+    fn a_single_validator_is_misconfigured_too() {
+        let mut json = fixtures::channel_json("one");
+        json["spec"]["validators"].as_array_mut().unwrap().truncate(1);
+        let one = fixtures::parse(json);
+        assert!(is_misconfigured(&one));
+        assert_eq!(one.spec.validator_count, 1);
+        assert_eq!(one.spec.validators.leader().url, "https://tom.adex.network");
+    }
+
+    // A model that was last ticked at `now`, with the data loaded then
+    fn ticked_at(now: f64) -> Model {
+        let mut model = Model::default();
+        model.now = now;
+        model.last_success = Some(now);
+        model.next_refresh_at = now + f64::from(REFRESH_MS);
+        model
+    }
+
+    #[test]
+    fn tick_refreshes_when_it_is_time() {
+        let start = fixtures::CREATED_MS as f64;
+        let mut model = ticked_at(start);
+        let quiet = TickEffects {
+            refresh: false,
+            retry_page: None,
+            render: false,
+        };
+        assert_eq!(tick(&mut model, start + 1000.0), quiet);
+        assert_eq!(model.now, start + 1000.0);
+
+        let due = start + f64::from(REFRESH_MS);
+        assert!(tick(&mut model, due).refresh);
+        assert_eq!(model.next_refresh_at, due + f64::from(REFRESH_MS));
+        // And not again until the next one is due
+        assert!(!tick(&mut model, due + 1000.0).refresh);
+    }
+
+    #[test]
+    fn tick_does_not_refresh_offline_but_still_reschedules() {
+        let start = fixtures::CREATED_MS as f64;
+        let mut model = ticked_at(start);
+        model.offline = true;
+        let due = start + f64::from(REFRESH_MS);
+        assert!(!tick(&mut model, due).refresh);
+        assert_eq!(model.next_refresh_at, due + f64::from(REFRESH_MS));
+    }
+
+    #[test]
+    fn tick_takes_down_expired_notices() {
+        let start = fixtures::CREATED_MS as f64;
+        let mut model = ticked_at(start);
+        show_copy_notice(&mut model, "Copied".to_owned());
+        model.throttle_hint_until = Some(start + 500.0);
+
+        assert!(tick(&mut model, start + 500.0).render);
+        assert_eq!(model.throttle_hint_until, None);
+        assert_eq!(model.copy_notice, Some("Copied".to_owned()));
+
+        assert!(tick(&mut model, start + COPY_NOTICE_MS).render);
+        assert_eq!(model.copy_notice, None);
+        assert_eq!(model.copy_notice_until, None);
+        assert!(!tick(&mut model, start + COPY_NOTICE_MS + 1.0).render);
+    }
+
+    #[test]
+    fn tick_renders_when_the_data_turns_stale() {
+        let start = fixtures::CREATED_MS as f64 + 12_345.0;
+        let mut model = ticked_at(start);
+        // Keep the refresh out of the way, as if it kept failing
+        model.next_refresh_at = f64::MAX;
+        tick(&mut model, start + STALE_MS - 1.0);
+        assert!(!tick(&mut model, start + STALE_MS).render);
+        assert!(!is_stale(&model));
+        assert!(tick(&mut model, start + STALE_MS + 1.0).render);
+        assert!(is_stale(&model));
+        assert!(!tick(&mut model, start + STALE_MS + 2.0).render);
+    }
+
+    #[test]
+    fn a_failed_page_waits_longer_before_each_retry() {
+        let start = fixtures::CREATED_MS as f64;
+        let market = MockMarket::with_channels(CAMPAIGNS_PAGE_SIZE + 30);
+        let mut model = ticked_at(start);
+        assert!(start_paging(&mut model));
+        next_page_step(0, market.page(0), &mut model);
+        let failure = || PageResponse::Failed(FetchError::Timeout);
+
+        next_page_step(CAMPAIGNS_PAGE_SIZE, failure(), &mut model);
+        let first_retry = start + CAMPAIGNS_PAGE_RETRY_MS;
+        assert_eq!(model.page_retry_at, Some((CAMPAIGNS_PAGE_SIZE, first_retry)));
+        assert_eq!(tick(&mut model, first_retry - 1000.0).retry_page, None);
+        assert_eq!(tick(&mut model, first_retry).retry_page, Some(CAMPAIGNS_PAGE_SIZE));
+        assert_eq!(model.page_retry_at, None);
+
+        next_page_step(CAMPAIGNS_PAGE_SIZE, failure(), &mut model);
+        let second_retry = first_retry + 2.0 * CAMPAIGNS_PAGE_RETRY_MS;
+        assert_eq!(model.page_retry_at, Some((CAMPAIGNS_PAGE_SIZE, second_retry)));
+    }
+
+    #[test]
+    fn manual_refresh_gives_up_on_a_retrying_load() {
+        let start = fixtures::CREATED_MS as f64;
+        let market = MockMarket::with_channels(CAMPAIGNS_PAGE_SIZE + 30);
+        let mut model = ticked_at(start);
+        model.last_fetch_started = start;
+        assert!(start_paging(&mut model));
+        next_page_step(0, market.page(0), &mut model);
+        let failure = PageResponse::Failed(FetchError::Http(503, "Service Unavailable".to_owned()));
+        match next_page_step(CAMPAIGNS_PAGE_SIZE, failure, &mut model) {
+            PageStep::Retry { .. } => {}
+            _ => panic!("expected the page to be retried"),
+        }
+
+        // Too soon after the last fetch; the retry carries on
+        assert!(!manual_refresh(&mut model, start + 1000.0));
+        assert!(model.throttle_hint_until.is_some());
+        assert!(model.page_retry_at.is_some());
+
+        let generation = model.load_generation;
+        assert!(manual_refresh(&mut model, start + MANUAL_REFRESH_MIN_MS));
+        assert_eq!(model.load_generation, generation + 1);
+        assert!(model.campaigns_paging.is_none());
+        assert_eq!(model.page_retries, 0);
+        assert_eq!(model.page_retry_at, None);
+        assert_eq!(tick(&mut model, start + CAMPAIGNS_PAGE_RETRY_MS).retry_page, None);
+
+        // The refresh starts a new load from the first page, which succeeds
+        match load_pages(&mut model, &market) {
+            PageStep::Loaded(channels) => assert_eq!(as_json(&channels), as_json(&market.all())),
+            _ => panic!("the refreshed load did not complete"),
+        }
+    }
+
+    #[test]
+    fn manual_refresh_leaves_a_healthy_load_running() {
+        let start = fixtures::CREATED_MS as f64;
+        let market = MockMarket::with_channels(CAMPAIGNS_PAGE_SIZE + 30);
+        let mut model = ticked_at(start);
+        assert!(start_paging(&mut model));
+        next_page_step(0, market.page(0), &mut model);
+        let generation = model.load_generation;
+        assert!(manual_refresh(&mut model, start + MANUAL_REFRESH_MIN_MS));
+        assert_eq!(model.load_generation, generation);
+        assert!(model.campaigns_paging.is_some());
+    }
+
+    fn many_earners(id: &str) -> MarketChannel {
+        let balances = (0..TRIMMED_BALANCES_KEPT as u64 + 5)
+            .map(|i| (format!("0x{:02x}", i), i + 1))
+            .collect::<Vec<_>>();
+        let balances = balances.iter().map(|(addr, paid)| (addr.as_str(), *paid)).collect::<Vec<_>>();
+        fixtures::channel_with_balances(id, &balances)
+    }
+
+    #[test]
+    fn trimmed_channels_are_fetched_in_full() {
+        let mut model = Model::default();
+        model.settings.trim_balances = true;
+        set_channels(&mut model, vec![many_earners("0x01"), fixtures::channel("0x02")]);
+        assert!(full_channel(&model, "0x01").is_none());
+        assert_eq!(full_channel(&model, "0x02").map(|c| c.id.as_str()), Some("0x02"));
+
+        model.full_channel = Some(many_earners("0x01"));
+        let full = full_channel(&model, "0x01").expect("the fetched channel");
+        assert_eq!(full.status.balances.len(), TRIMMED_BALANCES_KEPT + 5);
+        assert!(full.status.trimmed.is_none());
+    }
+
+    #[test]
+    fn the_detail_page_shows_the_channel_fetched_on_its_own() {
+        let mut model = Model::default();
+        model.load_action = ActionLoad::ChannelDetail("0x01".to_owned());
+        assert!(detail_channel(&model).is_none());
+
+        // Listed before, e.g. when coming from the table
+        model.settings.trim_balances = true;
+        set_channels(&mut model, vec![many_earners("0x01"), fixtures::channel("0x02")]);
+        let listed = detail_channel(&model).expect("the listed channel");
+        assert!(listed.status.trimmed.is_some());
+
+        model.full_channel = Some(many_earners("0x01"));
+        let fetched = detail_channel(&model).expect("the fetched channel");
+        assert!(fetched.status.trimmed.is_none());
+
+        // Another channel's
+        model.load_action = ActionLoad::ChannelDetail("0x02".to_owned());
+        assert_eq!(detail_channel(&model).map(|c| c.id.as_str()), Some("0x02"));
+        model.load_action = ActionLoad::ChannelsActive;
+        assert!(detail_channel(&model).is_none());
+    }
+
+    #[test]
+    fn export_pages_add_up_to_the_full_listing() {
+        let market = MockMarket::with_channels(CAMPAIGNS_PAGE_SIZE * 2 + 3);
+        let mut model = Model::default();
+        model.export_pages = Some(Vec::new());
+        let mut skip = Some(0);
+        while let Some(next) = skip {
+            let page = match market.page(next) {
+                PageResponse::Body { body, .. } => serde_json::from_str(&body).unwrap(),
+                _ => panic!("the mock market always has a body"),
+            };
+            skip = add_export_page(&mut model, next, page);
+        }
+        assert_eq!(model.export_pages, Some(market.channels));
+    }
+
+    // Two copies of one campaign, and another one on its own
+    fn republished_and_solo() -> Vec<MarketChannel> {
+        let titled = |id: &str, title: &str| {
+            let mut json = fixtures::channel_json(id);
+            json["spec"]["title"] = serde_json::json!(title);
+            fixtures::parse(json)
+        };
+        vec![
+            titled("0xa1", "Republished"),
+            titled("0xa2", "Republished"),
+            titled("0xb1", "Solo"),
+        ]
+    }
+
+    fn searching(search: &str) -> Model {
+        let mut model = Model::default();
+        model.settings.group_duplicates = true;
+        model.settings.expand_search_matches = true;
+        model.filters.search = search.to_owned();
+        model
+    }
+
+    #[test]
+    fn a_single_search_match_gets_a_detail_row() {
+        // Searched before the channels are in; they're matched as soon as they are
+        let mut model = searching("solo");
+        set_channels(&mut model, republished_and_solo());
+        assert_eq!(row_keys(&model).len(), 3);
+        assert!(model.expanded_channels.contains("0xb1"));
+        assert_eq!(row_keys(&model).last().map(String::as_str), Some("detail-0xb1"));
+
+        // Without grouping, every match is on its own
+        model.filters.search = "republished".to_owned();
+        model.settings.group_duplicates = false;
+        expand_search_matches(&mut model);
+        let expanded = model.expanded_channels.iter().cloned().sorted().collect::<Vec<_>>();
+        assert_eq!(expanded, vec!["0xa1", "0xa2"]);
+    }
+
+    #[test]
+    fn search_matches_follow_the_filters() {
+        let mut channels = republished_and_solo();
+        channels[0].status.balances.insert("0x1".to_owned(), BigNum::from(10));
+        let fingerprint = channels[0].fingerprint();
+        let mut model = searching("republished");
+        set_channels(&mut model, channels);
+        assert!(model.expanded_groups.contains(&fingerprint));
+        assert!(model.expanded_channels.is_empty());
+
+        // Hiding the unpaid copy leaves the other one on its own
+        assert!(changes_what_is_shown(&Msg::ToggleHideUnpaid));
+        model.filters.hide_unpaid = true;
+        expand_search_matches(&mut model);
+        assert!(!model.expanded_groups.contains(&fingerprint));
+        assert_eq!(model.expanded_channels.iter().collect::<Vec<_>>(), vec!["0xa1"]);
+
+        // Clearing the search collapses everything it expanded
+        model.filters.search.clear();
+        expand_search_matches(&mut model);
+        assert!(model.expanded_groups.is_empty());
+        assert!(model.expanded_channels.is_empty());
+        assert!(!changes_what_is_shown(&Msg::ToggleDebug));
+    }
+
+    #[test]
+    fn search_expansion_is_capped() {
+        let mut model = searching("campaign");
+        set_channels(
+            &mut model,
+            (0..SEARCH_EXPANDED_MAX + 5)
+                .map(|i| fixtures::channel(&format!("0x{:02x}", i)))
+                .collect(),
+        );
+        // The copies of the fixture campaign are one group, so group them apart
+        model.settings.group_duplicates = false;
+        expand_search_matches(&mut model);
+        assert_eq!(model.expanded_channels.len(), SEARCH_EXPANDED_MAX);
+    }
+
+    #[test]
+    fn amounts_are_in_usd_only_with_an_estimate() {
+        let mut settings = Settings::default();
+        settings.amount_unit = AmountUnit::Usd;
+        let amount = wei("1234500000000000000000");
+        assert_eq!(amount_text(&amount, Some(10.0), &settings), "~$10.00");
+        assert_eq!(amount_text(&amount, None, &settings), "N/A");
+        // Nothing to convert it with, so it says it's DAI
+        assert_eq!(plain_amount_text(&amount, &settings), "1234.50 DAI");
+
+        settings.amount_unit = AmountUnit::Dai;
+        assert_eq!(amount_text(&amount, Some(10.0), &settings), "1234.50 DAI");
+        assert_eq!(plain_amount_text(&amount, &settings), "1234.50 DAI");
+    }
+
+    #[test]
+    fn impressions_are_totalled_with_a_delta() {
+        let mut channel = fixtures::channel("a");
+        channel
+            .status
+            .balances
+            .insert(fixtures::LEADER.to_owned(), fixtures::big("1000000000000000000"));
+        let previous = Totals::of(&[channel.clone(), fixtures::channel("b")]);
+        // 1 DAI at 0.001 DAI per impression
+        assert_eq!(previous.impressions, BigNum::from(1000));
+
+        channel
+            .status
+            .balances
+            .insert(fixtures::FOLLOWER.to_owned(), fixtures::big("41203000000000000000"));
+        let mut model = Model::default();
+        model.totals = Some(Totals::of(&[channel, fixtures::channel("b")]));
+        model.previous_totals = Some(previous);
+        // Faded
+        assert_eq!(totals_change(&model, |t| &t.impressions), None);
+        model.deltas_until = Some(DELTA_FADE_MS);
+        let (count, up) = totals_change(&model, |t| &t.impressions).unwrap();
+        assert!(up);
+        assert_eq!(impression_count(&count), "41,203");
+    }
 }
@@ -0,0 +1,104 @@
+// Presentation mode, for a wall display nobody touches: the page slowly scrolls down,
+// then jumps back to the top, so every channel comes into view eventually
+// Toggled from the channels page, or turned on right away with ?present=1 in the URL
+
+use std::cell::Cell;
+use std::rc::Rc;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsCast;
+
+const QUERY_PARAM: &str = "present";
+// How often the page moves, and by how much; together about 25 pixels per second
+const STEP_MS: i32 = 40;
+const STEP_PX: i32 = 1;
+// After the mouse or the keyboard was used, scrolling waits this long before resuming
+const PAUSE_MS: f64 = 10_000.0;
+// Any of these pauses it
+const INTERACTIONS: &[&str] = &["mousemove", "mousedown", "wheel", "keydown", "touchstart"];
+
+// The interval that scrolls the page and the listeners that pause it, installed by `start`
+// Nothing runs while not presenting: dropping the session clears the interval and removes them
+// Scrolling smoothly needs far more steps than Tick gives, hence an interval of its own
+pub struct Session {
+    interval: i32,
+    _on_step: Closure<dyn FnMut()>,
+    on_interaction: Closure<dyn FnMut()>,
+}
+
+impl Session {
+    pub fn start() -> Self {
+        let window = seed::window();
+        // No scrolling before this time, after the user did something
+        let paused_until = Rc::new(Cell::new(0.0));
+
+        let step_paused_until = Rc::clone(&paused_until);
+        let on_step = Closure::wrap(Box::new(move || {
+            if js_sys::Date::now() >= step_paused_until.get() {
+                step();
+            }
+        }) as Box<dyn FnMut()>);
+        let interval = window
+            .set_interval_with_callback_and_timeout_and_arguments_0(
+                on_step.as_ref().unchecked_ref(),
+                STEP_MS,
+            )
+            .expect("set presentation interval");
+
+        let on_interaction = Closure::wrap(Box::new(move || {
+            paused_until.set(js_sys::Date::now() + PAUSE_MS);
+        }) as Box<dyn FnMut()>);
+        for &event in INTERACTIONS {
+            window
+                .add_event_listener_with_callback(event, on_interaction.as_ref().unchecked_ref())
+                .expect("add interaction listener");
+        }
+
+        Session {
+            interval,
+            _on_step: on_step,
+            on_interaction,
+        }
+    }
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        let window = seed::window();
+        window.clear_interval_with_handle(self.interval);
+        for &event in INTERACTIONS {
+            let _ = window.remove_event_listener_with_callback(
+                event,
+                self.on_interaction.as_ref().unchecked_ref(),
+            );
+        }
+    }
+}
+
+// Whether ?present=1 (or just ?present) is in the query string
+pub fn requested() -> bool {
+    seed::window().location().search().map_or(false, |search| {
+        search.trim_start_matches('?').split('&').any(|param| {
+            let mut parts = param.splitn(2, '=');
+            parts.next() == Some(QUERY_PARAM)
+                && parts.next().map_or(true, |value| value != "0" && value != "false")
+        })
+    })
+}
+
+fn step() {
+    let window = seed::window();
+    let scrolled = window.scroll_y().unwrap_or(0.0);
+    let viewport = window
+        .inner_height()
+        .ok()
+        .and_then(|height| height.as_f64())
+        .unwrap_or(0.0);
+    let page = seed::document()
+        .document_element()
+        .map_or(0.0, |root| f64::from(root.scroll_height()));
+    if scrolled + viewport >= page - 1.0 {
+        window.scroll_to_with_x_and_y(0.0, 0.0);
+    } else {
+        window.scroll_by_with_x_and_y(0.0, f64::from(STEP_PX));
+    }
+}
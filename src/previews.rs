@@ -0,0 +1,168 @@
+// Ad unit media on the detail page is only fetched when asked for, a few at a time, so a campaign
+// with dozens of units doesn't fire all of them at the gateway at once
+use serde::Serialize;
+use std::collections::{HashSet, VecDeque};
+
+pub const MAX_IN_FLIGHT: usize = 3;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PreviewState {
+    NotRequested,
+    Waiting,
+    Loading,
+    Loaded,
+    Failed,
+}
+
+// Keyed by the media URL, so switching the gateway starts over
+#[derive(Serialize, Default, Clone, Debug)]
+pub struct Previews {
+    waiting: VecDeque<String>,
+    in_flight: HashSet<String>,
+    loaded: HashSet<String>,
+    failed: HashSet<String>,
+}
+
+impl Previews {
+    pub fn state(&self, src: &str) -> PreviewState {
+        if self.loaded.contains(src) {
+            PreviewState::Loaded
+        } else if self.failed.contains(src) {
+            PreviewState::Failed
+        } else if self.in_flight.contains(src) {
+            PreviewState::Loading
+        } else if self.waiting.iter().any(|waiting| waiting == src) {
+            PreviewState::Waiting
+        } else {
+            PreviewState::NotRequested
+        }
+    }
+
+    pub fn request(&mut self, src: String) {
+        if self.state(&src) == PreviewState::NotRequested {
+            self.waiting.push_back(src);
+            self.start_waiting();
+        }
+    }
+
+    // The image or video fired `load` or `error`; that frees a slot for the next one
+    pub fn finished(&mut self, src: &str, ok: bool) {
+        if !self.in_flight.remove(src) {
+            return;
+        }
+        if ok {
+            self.loaded.insert(src.to_owned());
+        } else {
+            self.failed.insert(src.to_owned());
+        }
+        self.start_waiting();
+    }
+
+    // Media that's no longer on the page never reports back, so leaving the page must not keep
+    // its slots taken; what did load stays loaded
+    pub fn cancel_pending(&mut self) {
+        self.waiting.clear();
+        self.in_flight.clear();
+    }
+
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.len()
+    }
+
+    fn start_waiting(&mut self) {
+        while self.in_flight.len() < MAX_IN_FLIGHT {
+            match self.waiting.pop_front() {
+                Some(src) => {
+                    self.in_flight.insert(src);
+                }
+                None => break,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn src(i: usize) -> String {
+        format!("https://ipfs.io/ipfs/Qm{}", i)
+    }
+
+    fn requested(count: usize) -> Previews {
+        let mut previews = Previews::default();
+        for i in 0..count {
+            previews.request(src(i));
+        }
+        previews
+    }
+
+    #[test]
+    fn only_a_few_load_at_once() {
+        let previews = requested(MAX_IN_FLIGHT + 2);
+        assert_eq!(previews.in_flight(), MAX_IN_FLIGHT);
+        for i in 0..MAX_IN_FLIGHT {
+            assert_eq!(previews.state(&src(i)), PreviewState::Loading);
+        }
+        assert_eq!(previews.state(&src(MAX_IN_FLIGHT)), PreviewState::Waiting);
+        assert_eq!(previews.state(&src(MAX_IN_FLIGHT + 1)), PreviewState::Waiting);
+        assert_eq!(previews.state(&src(99)), PreviewState::NotRequested);
+    }
+
+    #[test]
+    fn requesting_twice_queues_once() {
+        let mut previews = requested(MAX_IN_FLIGHT + 1);
+        previews.request(src(MAX_IN_FLIGHT));
+        previews.request(src(0));
+        previews.finished(&src(0), true);
+        previews.finished(&src(MAX_IN_FLIGHT), true);
+        assert_eq!(previews.in_flight(), MAX_IN_FLIGHT - 1);
+    }
+
+    #[test]
+    fn a_finished_preview_starts_the_next_waiting_one() {
+        let mut previews = requested(MAX_IN_FLIGHT + 2);
+        previews.finished(&src(1), true);
+        assert_eq!(previews.state(&src(1)), PreviewState::Loaded);
+        assert_eq!(previews.state(&src(MAX_IN_FLIGHT)), PreviewState::Loading);
+        assert_eq!(previews.state(&src(MAX_IN_FLIGHT + 1)), PreviewState::Waiting);
+        assert_eq!(previews.in_flight(), MAX_IN_FLIGHT);
+    }
+
+    #[test]
+    fn a_failed_preview_frees_its_slot_too() {
+        let mut previews = requested(MAX_IN_FLIGHT + 1);
+        previews.finished(&src(0), false);
+        assert_eq!(previews.state(&src(0)), PreviewState::Failed);
+        assert_eq!(previews.state(&src(MAX_IN_FLIGHT)), PreviewState::Loading);
+        // A failed preview isn't retried by asking again
+        previews.request(src(0));
+        assert_eq!(previews.state(&src(0)), PreviewState::Failed);
+    }
+
+    #[test]
+    fn reports_for_media_not_in_flight_are_ignored() {
+        let mut previews = requested(MAX_IN_FLIGHT + 1);
+        previews.finished(&src(MAX_IN_FLIGHT), true);
+        assert_eq!(previews.state(&src(MAX_IN_FLIGHT)), PreviewState::Waiting);
+        assert_eq!(previews.in_flight(), MAX_IN_FLIGHT);
+    }
+
+    #[test]
+    fn leaving_the_page_cancels_the_pending_ones_and_keeps_the_loaded() {
+        let mut previews = requested(MAX_IN_FLIGHT + 2);
+        previews.finished(&src(0), true);
+        previews.cancel_pending();
+        assert_eq!(previews.in_flight(), 0);
+        assert_eq!(previews.state(&src(0)), PreviewState::Loaded);
+        for i in 1..MAX_IN_FLIGHT + 2 {
+            assert_eq!(previews.state(&src(i)), PreviewState::NotRequested);
+        }
+        // A late report from a cancelled preview doesn't take a slot
+        previews.finished(&src(1), true);
+        assert_eq!(previews.state(&src(1)), PreviewState::NotRequested);
+        // Coming back starts them over
+        previews.request(src(1));
+        assert_eq!(previews.state(&src(1)), PreviewState::Loading);
+    }
+}
@@ -0,0 +1,121 @@
+// Reading and writing query strings, for share links that bring back what was on the screen
+// Plain Rust rather than the browser's URI functions, so it works (and is tested) natively
+
+// The name=value pairs of a query string, decoded; a leading '?' is fine, a name without a value
+// has an empty one
+pub fn parse(search: &str) -> Vec<(String, String)> {
+    search
+        .trim_start_matches('?')
+        .split('&')
+        .filter(|param| !param.is_empty())
+        .map(|param| {
+            let mut parts = param.splitn(2, '=');
+            let name = decode(parts.next().unwrap_or_default());
+            let value = decode(parts.next().unwrap_or_default());
+            (name, value)
+        })
+        .collect()
+}
+
+// Without the leading '?'; empty when there are no pairs
+pub fn encode(pairs: &[(&str, String)]) -> String {
+    pairs
+        .iter()
+        .map(|(name, value)| format!("{}={}", encode_component(name), encode_component(value)))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+// Everything but the unreserved characters of RFC 3986 is percent-encoded
+fn encode_component(component: &str) -> String {
+    component
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+// '+' is a space, as forms send it; a malformed escape is kept as it is rather than failing
+fn decode(component: &str) -> String {
+    let bytes = component.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => decoded.push(b' '),
+            b'%' => match bytes.get(i + 1..i + 3).and_then(hex_byte) {
+                Some(byte) => {
+                    decoded.push(byte);
+                    i += 2;
+                }
+                None => decoded.push(b'%'),
+            },
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+// Exactly two hex digits; `from_str_radix` alone would take a sign too
+fn hex_byte(digits: &[u8]) -> Option<u8> {
+    if !digits.iter().all(u8::is_ascii_hexdigit) {
+        return None;
+    }
+    u8::from_str_radix(std::str::from_utf8(digits).ok()?, 16).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_decodes_names_and_values() {
+        assert_eq!(
+            parse("?q=ad+ex%20network&status=Active&present"),
+            vec![
+                ("q".to_owned(), "ad ex network".to_owned()),
+                ("status".to_owned(), "Active".to_owned()),
+                ("present".to_owned(), "".to_owned()),
+            ]
+        );
+        assert_eq!(parse(""), vec![]);
+        assert_eq!(parse("?"), vec![]);
+    }
+
+    #[test]
+    fn parse_keeps_malformed_escapes() {
+        assert_eq!(
+            parse("q=100%&r=%zz&s=%4&t=%+5"),
+            vec![
+                ("q".to_owned(), "100%".to_owned()),
+                ("r".to_owned(), "%zz".to_owned()),
+                ("s".to_owned(), "%4".to_owned()),
+                ("t".to_owned(), "% 5".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn encode_round_trips_through_parse() {
+        let pairs = vec![
+            ("q", "a&b=c #1 +ü".to_owned()),
+            ("usd_min", "10.5".to_owned()),
+            ("tag", "".to_owned()),
+        ];
+        let encoded = encode(&pairs);
+        assert_eq!(encoded, "q=a%26b%3Dc%20%231%20%2B%C3%BC&usd_min=10.5&tag=");
+        let parsed = parse(&encoded);
+        assert_eq!(
+            parsed,
+            pairs
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.clone()))
+                .collect::<Vec<_>>()
+        );
+    }
+}
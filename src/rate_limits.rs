@@ -0,0 +1,96 @@
+// One-line summaries of who may submit events to a channel and how often, from the spec's
+// `eventSubmission` rules, e.g. "1/ip/60s" or "uid-based"
+use super::types::MarketChannel;
+use serde_json::Value;
+
+// None if the spec has no `eventSubmission` at all, which isn't the same as having no rules
+// Read through serde, like the targeting scores, so it doesn't depend on the domain type
+pub fn channel_summary(channel: &MarketChannel) -> Option<String> {
+    let spec = serde_json::to_value(&channel.spec).ok()?;
+    let event_submission = spec.get("eventSubmission")?;
+    Some(summary(event_submission))
+}
+
+// Rules are joined with "; "; anything we don't understand is shown as the JSON it came as
+pub fn summary(event_submission: &Value) -> String {
+    let rules = match event_submission.get("allow").and_then(Value::as_array) {
+        Some(rules) => rules,
+        None => return event_submission.to_string(),
+    };
+    if rules.is_empty() {
+        return "none".to_owned();
+    }
+    rules.iter().map(rule_summary).collect::<Vec<_>>().join("; ")
+}
+
+fn rule_summary(rule: &Value) -> String {
+    let uids = rule.get("uids").filter(|uids| !uids.is_null());
+    let rate_limit = rule.get("rateLimit").filter(|limit| !limit.is_null());
+    match (uids, rate_limit) {
+        (_, Some(limit)) => match (
+            limit.get("type").and_then(Value::as_str),
+            limit.get("timeFrame").and_then(Value::as_u64),
+        ) {
+            (Some(kind), Some(time_frame)) => format!("1/{}/{}", kind, time_frame_label(time_frame)),
+            _ => rule.to_string(),
+        },
+        (Some(uids), None) if uids.is_array() => "uid-based".to_owned(),
+        (None, None) if rule.as_object().map_or(false, |rule| rule.is_empty()) => "unlimited".to_owned(),
+        _ => rule.to_string(),
+    }
+}
+
+// Time frames are in ms; "60s" or "2h" when it divides evenly
+fn time_frame_label(ms: u64) -> String {
+    match ms {
+        ms if ms % 3_600_000 == 0 && ms > 0 => format!("{}h", ms / 3_600_000),
+        ms if ms % 1000 == 0 => format!("{}s", ms / 1000),
+        ms => format!("{}ms", ms),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+    use serde_json::json;
+
+    #[test]
+    fn summary_of_each_kind_of_rule() {
+        let cases = vec![
+            (json!({ "allow": [] }), "none"),
+            (
+                json!({ "allow": [{ "rateLimit": { "type": "ip", "timeFrame": 60_000 } }] }),
+                "1/ip/60s",
+            ),
+            (
+                json!({ "allow": [{ "rateLimit": { "type": "uid", "timeFrame": 7_200_000 } }] }),
+                "1/uid/2h",
+            ),
+            (
+                json!({ "allow": [{ "rateLimit": { "type": "ip", "timeFrame": 1500 } }] }),
+                "1/ip/1500ms",
+            ),
+            (json!({ "allow": [{ "uids": [fixtures::CREATOR] }] }), "uid-based"),
+            (json!({ "allow": [{}] }), "unlimited"),
+            (
+                json!({ "allow": [
+                    { "uids": [fixtures::CREATOR, fixtures::LEADER] },
+                    { "rateLimit": { "type": "ip", "timeFrame": 0 } }
+                ] }),
+                "uid-based; 1/ip/0s",
+            ),
+        ];
+        for (event_submission, expected) in cases {
+            assert_eq!(summary(&event_submission), expected, "{}", event_submission);
+        }
+    }
+
+    #[test]
+    fn summary_shows_what_it_does_not_understand_as_json() {
+        assert_eq!(summary(&json!({ "deny": [] })), r#"{"deny":[]}"#);
+        let odd = json!({ "allow": [{ "rateLimit": { "type": "ip" } }] });
+        assert_eq!(summary(&odd), r#"{"rateLimit":{"type":"ip"}}"#);
+        assert_eq!(summary(&json!({ "allow": [{ "extra": 1 }] })), r#"{"extra":1}"#);
+    }
+}
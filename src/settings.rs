@@ -0,0 +1,249 @@
+use super::amounts::AmountUnit;
+use super::config::{DataScope, Environment};
+use super::filters::Filters;
+use super::ipfs::Gateway;
+use super::query;
+use super::types::ChannelSort;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+const STORAGE_KEY: &str = "adex-explorer-settings";
+// The filters are kept apart, as the query of a share link, see `Filters::to_query`
+const FILTERS_KEY: &str = "adex-explorer-filters";
+// Bumped whenever a stored field changes meaning, so `load_settings` can convert older blobs
+// Adding a field doesn't need a bump: it just gets its default
+const SETTINGS_VERSION: u32 = 1;
+pub const MAX_DAI_PRECISION: u8 = 6;
+
+static STORAGE_WARNED: AtomicBool = AtomicBool::new(false);
+
+// Everything the user can tweak in the settings panel
+// All of it gets persisted in localStorage as one versioned blob
+// Fields missing from an older blob get their default value, unknown ones are ignored, and so are
+// values that don't parse, see `parse_stored`
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct Settings {
+    pub sort: ChannelSort,
+    pub env: Environment,
+    // Whether the market is asked for active campaigns only or for all of them
+    pub data_scope: DataScope,
+    pub group_duplicates: bool,
+    // Expand the duplicate groups that have a search match in them while searching
+    pub expand_search_matches: bool,
+    pub check_validators: bool,
+    // Keep only the biggest earners of each channel in memory; the publisher filter needs them all
+    pub trim_balances: bool,
+    // Expired and exhausted campaigns are hidden from /channels unless this is set
+    pub show_ended: bool,
+    // Show timestamps in the browser's timezone rather than UTC
+    pub local_time: bool,
+    // Show the paid share of each channel as a bar instead of a percentage
+    pub paid_bars: bool,
+    // Show large DAI amounts as e.g. "1.23M DAI" in the table
+    pub compact_dai: bool,
+    // Show the event submission rules of each channel as a column, see rate_limits.rs
+    pub show_rate_limits: bool,
+    // Where ad unit media on IPFS is loaded from
+    pub ipfs_gateway: Gateway,
+    // Decimals shown for DAI amounts, 0 to MAX_DAI_PRECISION
+    pub dai_precision: u8,
+    // What the amounts in the table are shown in
+    pub amount_unit: AmountUnit,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            sort: ChannelSort::default(),
+            env: Environment::default(),
+            data_scope: DataScope::default(),
+            group_duplicates: false,
+            expand_search_matches: false,
+            check_validators: false,
+            trim_balances: false,
+            show_ended: false,
+            local_time: false,
+            paid_bars: false,
+            compact_dai: false,
+            show_rate_limits: false,
+            ipfs_gateway: Gateway::default(),
+            dai_precision: 2,
+            amount_unit: AmountUnit::default(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Stored<'a> {
+    version: u32,
+    settings: &'a Settings,
+}
+
+pub fn load_settings() -> Settings {
+    read(STORAGE_KEY).map_or_else(Settings::default, |json| parse_stored(&json))
+}
+
+// What `save_settings` wrote, by this or an older version
+pub fn parse_stored(json: &str) -> Settings {
+    let (version, settings) = match serde_json::from_str::<Value>(json) {
+        Ok(Value::Object(mut envelope)) if envelope.contains_key("version") => (
+            envelope.get("version").and_then(|v| v.as_u64()).unwrap_or(0),
+            envelope.remove("settings").unwrap_or(Value::Null),
+        ),
+        // Before the version was added, the settings were stored as they are
+        Ok(settings) => (0, settings),
+        Err(_) => return Settings::default(),
+    };
+    field_by_field(migrate(version, settings))
+}
+
+// Nothing to convert yet: version 0 only lacked the envelope
+fn migrate(_version: u64, settings: Value) -> Value {
+    settings
+}
+
+// Each stored value is only taken if it parses, so one that doesn't (say a variant a newer
+// version added) resets that setting alone rather than all of them
+fn field_by_field(stored: Value) -> Settings {
+    let fields = match stored {
+        Value::Object(fields) => fields,
+        _ => return Settings::default(),
+    };
+    let mut settings = serde_json::to_value(Settings::default()).unwrap_or(Value::Null);
+    for (name, value) in fields {
+        if let Some(field) = settings.get_mut(&name) {
+            let default = std::mem::replace(field, value);
+            if serde_json::from_value::<Settings>(settings.clone()).is_err() {
+                settings[&name] = default;
+            }
+        }
+    }
+    serde_json::from_value(settings).unwrap_or_default()
+}
+
+pub fn save_settings(settings: &Settings) {
+    if let Some(storage) = storage() {
+        let stored = Stored {
+            version: SETTINGS_VERSION,
+            settings,
+        };
+        if let Ok(json) = serde_json::to_string(&stored) {
+            storage.set_item(STORAGE_KEY, &json).map_err(warn_once).ok();
+        }
+    }
+}
+
+pub fn load_filters() -> Filters {
+    read(FILTERS_KEY).map_or_else(Filters::default, |stored| {
+        Filters::from_query(&query::parse(&stored)).0
+    })
+}
+
+pub fn save_filters(filters: &Filters) {
+    if let Some(storage) = storage() {
+        let stored = query::encode(&filters.to_query());
+        storage.set_item(FILTERS_KEY, &stored).map_err(warn_once).ok();
+    }
+}
+
+pub fn clear_settings() {
+    if let Some(storage) = storage() {
+        storage.remove_item(STORAGE_KEY).map_err(warn_once).ok();
+    }
+}
+
+fn read(key: &str) -> Option<String> {
+    storage()
+        .and_then(|storage| storage.get_item(key).map_err(warn_once).ok())
+        .and_then(|item| item)
+}
+
+// Private browsing and sandboxed iframes throw on any localStorage access, and a full storage
+// throws on writes; the settings then only last for the session
+// Not seed::storage, which unwraps those errors
+fn storage() -> Option<web_sys::Storage> {
+    seed::window().local_storage().map_err(warn_once).ok().and_then(|storage| storage)
+}
+
+fn warn_once(reason: wasm_bindgen::JsValue) {
+    if !STORAGE_WARNED.swap(true, Ordering::Relaxed) {
+        log!("Settings can't be saved, localStorage is unavailable:", reason);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_what_save_settings_writes() {
+        let settings = Settings {
+            sort: ChannelSort::Expiry,
+            env: Environment::Custom("https://market.example.com".to_owned()),
+            show_ended: true,
+            dai_precision: 4,
+            amount_unit: AmountUnit::Usd,
+            ..Settings::default()
+        };
+        let json = serde_json::to_string(&Stored {
+            version: SETTINGS_VERSION,
+            settings: &settings,
+        })
+        .expect("serializes");
+        let parsed = parse_stored(&json);
+        assert_eq!(parsed.sort, ChannelSort::Expiry);
+        assert_eq!(parsed.env, settings.env);
+        assert!(parsed.show_ended);
+        assert_eq!(parsed.dai_precision, 4);
+        assert_eq!(parsed.amount_unit, AmountUnit::Usd);
+    }
+
+    #[test]
+    fn reads_a_legacy_blob_without_the_envelope() {
+        let parsed = parse_stored(r#"{"sort":"Created","show_ended":true,"local_time":true}"#);
+        assert_eq!(parsed.sort, ChannelSort::Created);
+        assert!(parsed.show_ended);
+        assert!(parsed.local_time);
+    }
+
+    #[test]
+    fn missing_fields_get_their_default() {
+        let parsed = parse_stored(r#"{"version":1,"settings":{"paid_bars":true}}"#);
+        assert!(parsed.paid_bars);
+        assert_eq!(parsed.dai_precision, 2);
+        assert_eq!(parsed.amount_unit, AmountUnit::Dai);
+        assert_eq!(parsed.env, Environment::Mainnet);
+    }
+
+    #[test]
+    fn unknown_fields_are_ignored() {
+        let parsed = parse_stored(
+            r#"{"version":1,"settings":{"compact_dai":true,"dark_mode":true},"theme":"dark"}"#,
+        );
+        assert!(parsed.compact_dai);
+    }
+
+    #[test]
+    fn a_value_that_does_not_parse_only_resets_its_own_setting() {
+        let parsed = parse_stored(
+            r#"{"version":1,"settings":{
+                "amount_unit":"Euro","sort":"Expiry","dai_precision":"many","show_ended":true
+            }}"#,
+        );
+        assert_eq!(parsed.amount_unit, AmountUnit::Dai);
+        assert_eq!(parsed.dai_precision, 2);
+        assert_eq!(parsed.sort, ChannelSort::Expiry);
+        assert!(parsed.show_ended);
+    }
+
+    #[test]
+    fn anything_else_is_the_defaults() {
+        for json in ["", "not json", "[1,2]", "null", r#"{"version":1}"#].iter() {
+            let parsed = parse_stored(json);
+            assert_eq!(parsed.sort, ChannelSort::Deposit, "{:?}", json);
+            assert!(!parsed.show_ended, "{:?}", json);
+        }
+    }
+}
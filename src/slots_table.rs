@@ -0,0 +1,74 @@
+use super::{time_cell, types, Msg};
+
+use lazysort::*;
+use seed::prelude::*;
+use std::cmp::Ordering;
+use types::{AdSlot, ChannelSort};
+
+pub fn slots_table(last_loaded: i64, slots: &[AdSlot], sort: ChannelSort, local_time: bool) -> Node<Msg> {
+    let header = tr![
+        td!["Owner"],
+        td!["Type"],
+        td!["Created"],
+        td!["Tags"]
+    ];
+
+    table![std::iter::once(header)
+        .chain(
+            slots
+                .iter()
+                .sorted_by(|x, y| compare_slots(sort, x, y))
+                .map(|slot| {
+                    tr![
+                        td![slot.owner.clone()],
+                        td![slot.slot_type.clone()],
+                        time_cell(last_loaded, &slot.created, local_time),
+                        td![slot.tags.len().to_string()],
+                    ]
+                })
+        )
+        .collect::<Vec<Node<Msg>>>()]
+}
+
+// The channels sort, as far as it applies to slots: oldest first for Age, newest first otherwise
+// Ties are broken by the IPFS hash, like the channels are by id
+fn compare_slots(sort: ChannelSort, x: &AdSlot, y: &AdSlot) -> Ordering {
+    match sort {
+        ChannelSort::Age => x.created.cmp(&y.created),
+        _ => y.created.cmp(&x.created),
+    }
+    .then_with(|| x.ipfs.cmp(&y.ipfs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn slot(ipfs: &str, created_ms: i64) -> AdSlot {
+        AdSlot {
+            owner: "0x1".to_owned(),
+            slot_type: "legacy_300x250".to_owned(),
+            ipfs: ipfs.to_owned(),
+            created: Utc.timestamp_millis(created_ms),
+            tags: vec![],
+        }
+    }
+
+    fn sorted(sort: ChannelSort, slots: &[AdSlot]) -> Vec<String> {
+        slots
+            .iter()
+            .sorted_by(|x, y| compare_slots(sort, x, y))
+            .map(|slot| slot.ipfs.clone())
+            .collect()
+    }
+
+    #[test]
+    fn slots_follow_the_channels_sort() {
+        let slots = vec![slot("b", 2000), slot("c", 1000), slot("a", 2000), slot("d", 3000)];
+        assert_eq!(sorted(ChannelSort::Created, &slots), vec!["d", "a", "b", "c"]);
+        assert_eq!(sorted(ChannelSort::Age, &slots), vec!["c", "a", "b", "d"]);
+        // Sorts that mean nothing for a slot fall back to newest first
+        assert_eq!(sorted(ChannelSort::Deposit, &slots), sorted(ChannelSort::Created, &slots));
+    }
+}
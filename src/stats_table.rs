@@ -1,4 +1,4 @@
-use super::{dai_readable, types, Msg};
+use super::{bignum, plain_amount_cell, settings::Settings, types, Msg};
 
 use adex_domain::BigNum;
 use lazysort::*;
@@ -6,47 +6,52 @@ use seed::prelude::*;
 use std::collections::HashMap;
 use types::{MarketChannel, MarketStatusType};
 
-pub fn ad_unit_stats_table(channels: &[&MarketChannel]) -> Node<Msg> {
-    let units_by_type = channels
+// The channels behind each ad unit type, once per unit of that type
+fn units_by_type<'a>(channels: &[&'a MarketChannel]) -> HashMap<&'a str, Vec<&'a MarketChannel>> {
+    channels
         .iter()
-        .flat_map(|channel| {
+        .flat_map(|&channel| {
             channel
                 .spec
                 .ad_units
                 .iter()
-                .map(move |unit| (&unit.ad_type, channel))
+                // Archived units can't serve, whatever the campaign says
+                .filter(|unit| !unit.archived)
+                .map(move |unit| (unit.ad_type.as_str(), channel))
         })
         .fold(
             HashMap::<&str, Vec<&MarketChannel>>::new(),
             |mut by_type, (ad_type, channel)| {
                 by_type
-                    .entry(&ad_type)
+                    .entry(ad_type)
                     .or_insert_with(Vec::new)
                     .push(channel);
 
                 by_type
             },
-        );
+        )
+}
+
+pub fn ad_unit_stats_table(channels: &[&MarketChannel], settings: &Settings) -> Node<Msg> {
+    let units_by_type = units_by_type(channels);
 
     let units_by_type_stats = units_by_type
         .iter()
         .map(|(ad_type, all)| {
-            let total_vol: BigNum = all.iter().map(|x| &x.deposit_amount).sum();
+            let total_vol = bignum::sum(all.iter().map(|x| &x.deposit_amount));
 
             let active = all
                 .iter()
                 .filter(|x| x.status.status_type == MarketStatusType::Active);
-            let total_active_vol: BigNum = active
-                .clone()
-                .map(|x| &x.deposit_amount - &x.status.balances_sum())
-                .sum();
+            let total_active_vol = bignum::sum(active.clone().map(|x| x.unspent().0));
 
-            let all_by_impression: BigNum = active
-                .clone()
-                .map(|x| &x.deposit_amount * &x.spec.min_per_impression)
-                .sum();
+            let all_by_impression = bignum::sum(
+                active
+                    .clone()
+                    .map(|x| &x.deposit_amount * x.price_per_impression()),
+            );
 
-            let all_deposits: BigNum = active.clone().map(|x| &x.deposit_amount).sum();
+            let all_deposits = bignum::sum(active.clone().map(|x| &x.deposit_amount));
 
             let avg_weighted_per_impression: BigNum = if all_deposits == BigNum::from(0) {
                 BigNum::from(0)
@@ -81,11 +86,44 @@ pub fn ad_unit_stats_table(channels: &[&MarketChannel]) -> Node<Msg> {
                         tr![
                             td![ad_type],
                             //td![dai_readable(&(avg_weighted_per_impression * &1000.into()))],
-                            td![dai_readable(&total_active_vol)],
-                            td![dai_readable(&total_vol)],
+                            plain_amount_cell(&total_active_vol, settings),
+                            plain_amount_cell(&total_vol, settings),
                         ]
                     }
                 )
         )
         .collect::<Vec<Node<Msg>>>()]
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+    use serde_json::json;
+
+    const LIVE: &str = "QmcUVX7fvoLMM93uN2bD3wGTH8MXSxeL8hojYfL2Lhp7mR";
+    const ARCHIVED: &str = "QmYwcfTCE1SqHxLbTnaBx5bKoPpCw8jK2NsxcFLRM5AxQV";
+
+    #[test]
+    fn units_by_type_leaves_archived_units_out() {
+        let mut mixed = fixtures::channel_json("mixed");
+        let mut banner = fixtures::archived_ad_unit(ARCHIVED);
+        banner["type"] = json!("legacy_728x90");
+        mixed["spec"]["adUnits"] = json!([fixtures::ad_unit(LIVE), banner]);
+        let mut archived = fixtures::channel_json("archived");
+        archived["spec"]["adUnits"] = json!([fixtures::archived_ad_unit(ARCHIVED)]);
+        let channels = vec![
+            fixtures::parse(mixed),
+            fixtures::parse(archived),
+            fixtures::channel("live"),
+        ];
+
+        let by_type = units_by_type(&channels.iter().collect::<Vec<_>>());
+        assert_eq!(by_type.len(), 1);
+        let ids = by_type["legacy_300x250"]
+            .iter()
+            .map(|channel| channel.id.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec!["mixed", "live"]);
+    }
+}
@@ -0,0 +1,37 @@
+// The status footer ticks every second. Rendering it through seed would re-diff the whole
+// channel table on every tick, so instead it lives outside the app's root element and is
+// written directly, the same way as the health marker; the seed view never sees it.
+
+const FOOTER_ID: &str = "status-footer";
+
+// Each part is (text, class name); an empty class name means none
+pub fn write(parts: &[(String, &str)]) {
+    let document = seed::document();
+    let footer = match document.get_element_by_id(FOOTER_ID) {
+        Some(footer) => footer,
+        None => {
+            let footer = match document.create_element("footer") {
+                Ok(footer) => footer,
+                Err(_) => return,
+            };
+            footer.set_id(FOOTER_ID);
+            footer.set_class_name("status-footer");
+            match document.body() {
+                Some(body) => {
+                    body.append_child(&footer).ok();
+                }
+                None => return,
+            }
+            footer
+        }
+    };
+    // Replace the children rather than setting innerHTML, so the text never needs escaping
+    footer.set_text_content(None);
+    for (text, class_name) in parts {
+        if let Ok(span) = document.create_element("span") {
+            span.set_class_name(class_name);
+            span.set_text_content(Some(text));
+            footer.append_child(&span).ok();
+        }
+    }
+}
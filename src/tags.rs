@@ -0,0 +1,123 @@
+// Targeting tags of the ad units, indexed so the table can be filtered by tag
+// Built once per load, since it means walking every ad unit of every channel
+use super::types::MarketChannel;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+#[derive(Serialize, Default, Clone, Debug)]
+pub struct TagIndex {
+    // Tag -> channel id -> the best score the tag has on any of the channel's ad units
+    tags: BTreeMap<String, HashMap<String, f64>>,
+}
+
+impl TagIndex {
+    pub fn build(channels: &[MarketChannel]) -> Self {
+        let mut tags = BTreeMap::<String, HashMap<String, f64>>::new();
+        for channel in channels {
+            for unit in &channel.spec.ad_units {
+                for targeting in &unit.targeting {
+                    let score = score(&targeting.score);
+                    let best = tags
+                        .entry(targeting.tag.clone())
+                        .or_insert_with(HashMap::new)
+                        .entry(channel.id.clone())
+                        .or_insert(score);
+                    *best = best.max(score);
+                }
+            }
+        }
+        TagIndex { tags }
+    }
+
+    // Every tag in alphabetical order, with how many channels have it
+    pub fn tags(&self) -> Vec<(&str, usize)> {
+        self.tags
+            .iter()
+            .map(|(tag, channels)| (tag.as_str(), channels.len()))
+            .collect()
+    }
+
+    pub fn max_score(&self, tag: &str) -> Option<f64> {
+        self.tags
+            .get(tag)?
+            .values()
+            .cloned()
+            .fold(None, |max, score| Some(max.map_or(score, |max: f64| max.max(score))))
+    }
+
+    // Whether any of the channel's ad units has the tag with at least `min_score`
+    pub fn matches(&self, tag: &str, min_score: f64, channel_id: &str) -> bool {
+        self.tags
+            .get(tag)
+            .and_then(|channels| channels.get(channel_id))
+            .map_or(false, |score| *score >= min_score)
+    }
+}
+
+// Scores are plain numbers in the JSON; going through serde doesn't depend on how the domain
+// type wraps them
+fn score<T: Serialize>(score: &T) -> f64 {
+    serde_json::to_value(score)
+        .ok()
+        .and_then(|value| value.as_f64())
+        .unwrap_or(0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+    use serde_json::{json, Value};
+
+    // A channel with one ad unit per entry, each targeting the given tags and scores
+    fn targeting(id: &str, units: &[&[(&str, u8)]]) -> MarketChannel {
+        let mut json = fixtures::channel_json(id);
+        json["spec"]["adUnits"] = units
+            .iter()
+            .enumerate()
+            .map(|(i, tags)| {
+                let mut unit = fixtures::ad_unit(&format!("Qm{}{}", id, i));
+                unit["targeting"] = tags
+                    .iter()
+                    .map(|(tag, score)| json!({ "tag": tag, "score": score }))
+                    .collect::<Value>();
+                unit
+            })
+            .collect::<Value>();
+        fixtures::parse(json)
+    }
+
+    fn index() -> TagIndex {
+        TagIndex::build(&[
+            targeting("a", &[&[("crypto", 40), ("games", 10)], &[("crypto", 80)]]),
+            targeting("b", &[&[("crypto", 20)]]),
+            targeting("c", &[]),
+        ])
+    }
+
+    #[test]
+    fn tags_counts_channels_not_ad_units() {
+        assert_eq!(index().tags(), vec![("crypto", 2), ("games", 1)]);
+        assert_eq!(TagIndex::default().tags(), vec![]);
+    }
+
+    #[test]
+    fn keeps_the_best_score_of_a_channel() {
+        let index = index();
+        assert_eq!(index.max_score("crypto"), Some(80.0));
+        assert_eq!(index.max_score("games"), Some(10.0));
+        assert_eq!(index.max_score("news"), None);
+        assert!(index.matches("crypto", 80.0, "a"));
+        assert!(!index.matches("crypto", 80.5, "a"));
+        assert!(index.matches("crypto", 20.0, "b"));
+        assert!(!index.matches("crypto", 21.0, "b"));
+    }
+
+    #[test]
+    fn matches_nothing_for_unknown_tags_or_channels() {
+        let index = index();
+        assert!(!index.matches("news", 0.0, "a"));
+        assert!(!index.matches("crypto", 0.0, "c"));
+        assert!(!index.matches("crypto", 0.0, "unknown"));
+    }
+}
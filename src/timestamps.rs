@@ -0,0 +1,86 @@
+// Timestamps are shown either in UTC or in the browser's timezone, depending on the settings
+use chrono::{DateTime, FixedOffset, TimeZone, Utc};
+
+// Minutes east of UTC of the browser's timezone at the given instant
+// (it differs across DST changes, so it can't be computed once)
+pub fn browser_offset_minutes(t: &DateTime<Utc>) -> i32 {
+    let date = js_sys::Date::new(&(t.timestamp_millis() as f64).into());
+    // getTimezoneOffset is UTC minus local time, so the sign is flipped
+    -(date.get_timezone_offset() as i32)
+}
+
+pub fn with_offset(t: &DateTime<Utc>, offset_minutes: i32) -> DateTime<FixedOffset> {
+    FixedOffset::east(offset_minutes * 60).from_utc_datetime(&t.naive_utc())
+}
+
+// e.g. "UTC", "UTC+2", "UTC-3:30"
+pub fn zone_label(offset_minutes: i32) -> String {
+    let sign = if offset_minutes < 0 { '-' } else { '+' };
+    let (hours, minutes) = (offset_minutes.abs() / 60, offset_minutes.abs() % 60);
+    match (hours, minutes) {
+        (0, 0) => "UTC".to_owned(),
+        (_, 0) => format!("UTC{}{}", sign, hours),
+        _ => format!("UTC{}{}:{:02}", sign, hours, minutes),
+    }
+}
+
+// The zone the browser is in at the given instant, e.g. "UTC+2"
+pub fn browser_zone_label(t: &DateTime<Utc>) -> String {
+    zone_label(browser_offset_minutes(t))
+}
+
+// `fmt` is a chrono format string; the zone is appended to it
+pub fn format(t: &DateTime<Utc>, local: bool, fmt: &str) -> String {
+    let offset = if local { browser_offset_minutes(t) } else { 0 };
+    format_with_offset(t, offset, fmt)
+}
+
+pub fn format_with_offset(t: &DateTime<Utc>, offset_minutes: i32, fmt: &str) -> String {
+    format!(
+        "{} {}",
+        with_offset(t, offset_minutes).format(fmt),
+        zone_label(offset_minutes)
+    )
+}
+
+// Down to the second, for tooltips
+pub fn exact(t: &DateTime<Utc>, local: bool) -> String {
+    format(t, local, "%Y-%m-%d %H:%M:%S")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FMT: &str = "%Y-%m-%d %H:%M";
+
+    #[test]
+    fn offsets_carry_across_midnight() {
+        let t = Utc.ymd(2019, 6, 30).and_hms(23, 30, 0);
+        assert_eq!(format_with_offset(&t, 0, FMT), "2019-06-30 23:30 UTC");
+        assert_eq!(format_with_offset(&t, 120, FMT), "2019-07-01 01:30 UTC+2");
+        assert_eq!(format_with_offset(&t, 30, FMT), "2019-07-01 00:00 UTC+0:30");
+
+        let t = Utc.ymd(2019, 7, 1).and_hms(2, 0, 0);
+        assert_eq!(format_with_offset(&t, -300, FMT), "2019-06-30 21:00 UTC-5");
+        assert_eq!(format_with_offset(&t, -120, FMT), "2019-07-01 00:00 UTC-2");
+    }
+
+    #[test]
+    fn offsets_carry_across_the_year() {
+        let t = Utc.ymd(2019, 12, 31).and_hms(18, 45, 0);
+        assert_eq!(format_with_offset(&t, 330, FMT), "2020-01-01 00:15 UTC+5:30");
+        let t = Utc.ymd(2020, 1, 1).and_hms(1, 0, 0);
+        assert_eq!(format_with_offset(&t, -210, FMT), "2019-12-31 21:30 UTC-3:30");
+    }
+
+    #[test]
+    fn zone_labels() {
+        assert_eq!(zone_label(0), "UTC");
+        assert_eq!(zone_label(60), "UTC+1");
+        assert_eq!(zone_label(-60), "UTC-1");
+        assert_eq!(zone_label(345), "UTC+5:45");
+        assert_eq!(zone_label(-30), "UTC-0:30");
+        assert_eq!(zone_label(840), "UTC+14");
+    }
+}
@@ -1,16 +1,22 @@
-use std::collections::HashMap;
+use std::cell::Cell;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::ops::Deref;
+
+use super::bignum;
+use super::labels::Labels;
 
 use adex_domain::{BigNum, ChannelSpec};
 use chrono::serde::ts_milliseconds;
-use chrono::{DateTime, Utc};
-use serde::Deserialize;
+use chrono::{DateTime, Duration, Utc};
+use serde::{ser, Deserialize, Deserializer, Serialize, Serializer};
 
 // Volume response from the validator
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct AnalyticsResp {
     pub aggr: Vec<AnalyticsDataPoint>,
 }
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct AnalyticsDataPoint {
     pub value: BigNum,
     #[serde(with = "ts_milliseconds")]
@@ -18,12 +24,77 @@ pub struct AnalyticsDataPoint {
 }
 
 // Etherscan API
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct EtherscanBalResp {
     pub result: BigNum,
 }
 
+// One page of campaigns from the market, before it's parsed
+#[derive(Clone, Debug)]
+pub enum PageResponse {
+    // 304: same as what we got last time
+    NotModified,
+    Body { etag: Option<String>, body: String },
+    Failed(FetchError),
+}
+
+impl PageResponse {
+    // `body` is what came of reading the response body, which can fail when the connection drops
+    pub fn from_response(
+        status: u16,
+        status_text: &str,
+        etag: Option<String>,
+        body: Result<String, String>,
+    ) -> Self {
+        match body {
+            _ if status == 304 => PageResponse::NotModified,
+            _ if status >= 400 => {
+                PageResponse::Failed(FetchError::Http(status, status_text.to_owned()))
+            }
+            Ok(body) => PageResponse::Body { etag, body },
+            Err(reason) => PageResponse::Failed(FetchError::Network(reason)),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum FetchError {
+    // The request never got a response: offline, DNS, CORS etc.
+    Network(String),
+    // Status code and text, e.g. 503 "Service Unavailable"
+    Http(u16, String),
+    // We got a response, but not in the shape we expect
+    Deserialize(String),
+    Timeout,
+}
+
+impl FetchError {
+    // Parse errors and 4xx won't fix themselves, so there's no point retrying them
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            FetchError::Network(_) | FetchError::Timeout => true,
+            FetchError::Http(status, _) => *status >= 500,
+            FetchError::Deserialize(_) => false,
+        }
+    }
+}
+
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FetchError::Network(reason) => write!(f, "could not reach the market ({})", reason),
+            FetchError::Http(status, text) if text.is_empty() => {
+                write!(f, "market returned HTTP {}", status)
+            }
+            FetchError::Http(status, text) => write!(f, "market returned HTTP {} {}", status, text),
+            FetchError::Deserialize(reason) => write!(f, "response format changed: {}", reason),
+            FetchError::Timeout => write!(f, "market did not respond in time"),
+        }
+    }
+}
+
 // Model
+#[derive(Serialize)]
 pub enum Loadable<T> {
     Loading,
     Ready(T),
@@ -33,12 +104,25 @@ impl<T> Default for Loadable<T> {
         Loadable::Loading
     }
 }
+impl<T> Loadable<T> {
+    pub fn is_loading(&self) -> bool {
+        match self {
+            Loadable::Loading => true,
+            Loadable::Ready(_) => false,
+        }
+    }
+}
 
-#[derive(Clone, Copy)]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
 pub enum ChannelSort {
     Deposit,
     Status,
     Created,
+    PublisherCount,
+    Price,
+    Expiry,
+    Age,
+    Fees,
 }
 
 impl Default for ChannelSort {
@@ -47,8 +131,280 @@ impl Default for ChannelSort {
     }
 }
 
+// Like chrono's `ts_seconds`, for a timestamp that may be null or missing
+mod ts_seconds_option {
+    use chrono::serde::ts_seconds;
+    use chrono::{DateTime, Utc};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Serialize, Deserialize)]
+    struct Seconds(#[serde(with = "ts_seconds")] DateTime<Utc>);
+
+    pub fn serialize<S: Serializer>(t: &Option<DateTime<Utc>>, serializer: S) -> Result<S::Ok, S::Error> {
+        t.map(Seconds).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<DateTime<Utc>>, D::Error> {
+        Ok(Option::<Seconds>::deserialize(deserializer)?.map(|Seconds(t)| t))
+    }
+}
+
+// A channel's spec, along with how many validators the market really listed
+// The domain type always has a leader and a follower, so a spec listing fewer would fail the whole
+// page; the missing ones are filled in without an id or a URL while it's read (see `padded`), the
+// channel shows up as misconfigured, and they're left out again when it's written back as JSON
+#[derive(Clone, Debug)]
+pub struct MarketSpec {
+    spec: ChannelSpec,
+    pub validator_count: usize,
+}
+
+impl Deref for MarketSpec {
+    type Target = ChannelSpec;
+
+    fn deref(&self) -> &ChannelSpec {
+        &self.spec
+    }
+}
+
+impl<'de> Deserialize<'de> for MarketSpec {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let validator_count = Cell::new(0);
+        let spec = ChannelSpec::deserialize(padded::spec(deserializer, &validator_count))?;
+        Ok(MarketSpec {
+            spec,
+            validator_count: validator_count.get(),
+        })
+    }
+}
+
+impl Serialize for MarketSpec {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.validator_count >= 2 {
+            return self.spec.serialize(serializer);
+        }
+        let mut spec = serde_json::to_value(&self.spec).map_err(ser::Error::custom)?;
+        if let Some(validators) = spec.get_mut("validators").and_then(|v| v.as_array_mut()) {
+            validators.truncate(self.validator_count);
+        }
+        spec.serialize(serializer)
+    }
+}
+
+// Reads a spec as it comes, only stepping in for the value of "validators": once the listed ones
+// run out, placeholders are handed out until there are two
+mod padded {
+    use serde::de::value::MapDeserializer;
+    use serde::de::{DeserializeSeed, Deserializer, IntoDeserializer, MapAccess, SeqAccess, Visitor};
+    use serde::forward_to_deserialize_any;
+    use std::cell::Cell;
+    use std::fmt;
+
+    const PLACEHOLDER: [(&str, &str); 3] = [("id", ""), ("url", ""), ("fee", "0")];
+
+    // `count` is set to the number of validators listed
+    pub fn spec<D>(deserializer: D, count: &Cell<usize>) -> Padded<D> {
+        Padded {
+            inner: deserializer,
+            count,
+            part: Part::Spec,
+        }
+    }
+
+    #[derive(Clone, Copy)]
+    enum Part {
+        Spec,
+        Validators,
+    }
+
+    pub struct Padded<'a, T> {
+        inner: T,
+        count: &'a Cell<usize>,
+        part: Part,
+    }
+
+    impl<'a, T> Padded<'a, T> {
+        fn wrap<U>(&self, inner: U) -> Padded<'a, U> {
+            Padded {
+                inner,
+                count: self.count,
+                part: self.part,
+            }
+        }
+    }
+
+    impl<'de, 'a, D: Deserializer<'de>> Deserializer<'de> for Padded<'a, D> {
+        type Error = D::Error;
+
+        fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, D::Error> {
+            let visitor = self.wrap(visitor);
+            self.inner.deserialize_any(visitor)
+        }
+
+        fn deserialize_struct<V: Visitor<'de>>(
+            self,
+            name: &'static str,
+            fields: &'static [&'static str],
+            visitor: V,
+        ) -> Result<V::Value, D::Error> {
+            let visitor = self.wrap(visitor);
+            self.inner.deserialize_struct(name, fields, visitor)
+        }
+
+        fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, D::Error> {
+            let visitor = self.wrap(visitor);
+            self.inner.deserialize_seq(visitor)
+        }
+
+        fn deserialize_tuple<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value, D::Error> {
+            let visitor = self.wrap(visitor);
+            self.inner.deserialize_tuple(len, visitor)
+        }
+
+        // Whatever the validators are wrapped in, the sequence inside is still theirs
+        fn deserialize_newtype_struct<V: Visitor<'de>>(
+            self,
+            _name: &'static str,
+            visitor: V,
+        ) -> Result<V::Value, D::Error> {
+            visitor.visit_newtype_struct(self)
+        }
+
+        forward_to_deserialize_any! {
+            bool i8 i16 i32 i64 u8 u16 u32 u64 f32 f64 char str string bytes byte_buf option unit
+            unit_struct tuple_struct map enum identifier ignored_any
+        }
+    }
+
+    impl<'de, 'a, V: Visitor<'de>> Visitor<'de> for Padded<'a, V> {
+        type Value = V::Value;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            self.inner.expecting(formatter)
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<V::Value, A::Error> {
+            match self.part {
+                Part::Spec => {
+                    let map = Fields {
+                        inner: map,
+                        count: self.count,
+                        validators_next: false,
+                    };
+                    self.inner.visit_map(map)
+                }
+                Part::Validators => self.inner.visit_map(map),
+            }
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<V::Value, A::Error> {
+            match self.part {
+                Part::Spec => self.inner.visit_seq(seq),
+                Part::Validators => {
+                    self.count.set(0);
+                    let seq = Validators {
+                        inner: seq,
+                        count: self.count,
+                        listed_ended: false,
+                        padded: 0,
+                    };
+                    self.inner.visit_seq(seq)
+                }
+            }
+        }
+    }
+
+    impl<'de, 'a, S: DeserializeSeed<'de>> DeserializeSeed<'de> for Padded<'a, S> {
+        type Value = S::Value;
+
+        fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<S::Value, D::Error> {
+            let deserializer = self.wrap(deserializer);
+            self.inner.deserialize(deserializer)
+        }
+    }
+
+    // The spec's fields, with the value of "validators" read through `Validators`
+    struct Fields<'a, A> {
+        inner: A,
+        count: &'a Cell<usize>,
+        validators_next: bool,
+    }
+
+    impl<'de, 'a, A: MapAccess<'de>> MapAccess<'de> for Fields<'a, A> {
+        type Error = A::Error;
+
+        fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, A::Error> {
+            match self.inner.next_key::<String>()? {
+                Some(key) => {
+                    self.validators_next = key == "validators";
+                    let key = IntoDeserializer::<'de, A::Error>::into_deserializer(key);
+                    seed.deserialize(key).map(Some)
+                }
+                None => Ok(None),
+            }
+        }
+
+        fn next_value_seed<S: DeserializeSeed<'de>>(&mut self, seed: S) -> Result<S::Value, A::Error> {
+            if self.validators_next {
+                let seed = Padded {
+                    inner: seed,
+                    count: self.count,
+                    part: Part::Validators,
+                };
+                self.inner.next_value_seed(seed)
+            } else {
+                self.inner.next_value_seed(seed)
+            }
+        }
+    }
+
+    struct Validators<'a, A> {
+        inner: A,
+        count: &'a Cell<usize>,
+        listed_ended: bool,
+        padded: usize,
+    }
+
+    impl<'de, 'a, A: SeqAccess<'de>> SeqAccess<'de> for Validators<'a, A> {
+        type Error = A::Error;
+
+        fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>, A::Error> {
+            // Kept if there's no listed validator left to read, for the placeholder
+            let mut seed = Some(seed);
+            if !self.listed_ended {
+                match self.inner.next_element_seed(Once(&mut seed))? {
+                    Some(validator) => {
+                        self.count.set(self.count.get() + 1);
+                        return Ok(Some(validator));
+                    }
+                    None => self.listed_ended = true,
+                }
+            }
+            match seed {
+                Some(seed) if self.count.get() + self.padded < 2 => {
+                    self.padded += 1;
+                    let placeholder = MapDeserializer::<_, A::Error>::new(PLACEHOLDER.iter().cloned());
+                    seed.deserialize(placeholder).map(Some)
+                }
+                _ => Ok(None),
+            }
+        }
+    }
+
+    // Hands the seed over only when there's something to read with it
+    struct Once<'s, T>(&'s mut Option<T>);
+
+    impl<'de, 's, T: DeserializeSeed<'de>> DeserializeSeed<'de> for Once<'s, T> {
+        type Value = T::Value;
+
+        fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<T::Value, D::Error> {
+            self.0.take().expect("a seed per element").deserialize(deserializer)
+        }
+    }
+}
+
 // Data structs specific to the market
-#[derive(Deserialize, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum MarketStatusType {
     Initializing,
     Waiting,
@@ -63,7 +419,62 @@ pub enum MarketStatusType {
     Exhausted,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+impl MarketStatusType {
+    pub const ALL: [MarketStatusType; 11] = [
+        MarketStatusType::Initializing,
+        MarketStatusType::Waiting,
+        MarketStatusType::Invalid,
+        MarketStatusType::Ready,
+        MarketStatusType::Active,
+        MarketStatusType::Offline,
+        MarketStatusType::Disconnected,
+        MarketStatusType::Unhealthy,
+        MarketStatusType::Withdraw,
+        MarketStatusType::Expired,
+        MarketStatusType::Exhausted,
+    ];
+
+    // Ready channels haven't had any events yet, so they don't count as active
+    pub fn is_active(&self) -> bool {
+        *self == MarketStatusType::Active
+    }
+
+    // Something is wrong with the validators, and they may be able to tell what
+    pub fn is_unhealthy(&self) -> bool {
+        match self {
+            MarketStatusType::Unhealthy
+            | MarketStatusType::Offline
+            | MarketStatusType::Disconnected => true,
+            _ => false,
+        }
+    }
+}
+
+// How many campaigns there are, in all and by status
+#[derive(Debug, Default, PartialEq)]
+pub struct Summary {
+    pub total: usize,
+    // Active only; Ready channels haven't had any events yet, see `is_active`
+    pub active: usize,
+    pub by_status: BTreeMap<MarketStatusType, usize>,
+}
+
+pub fn summarize<'a, I>(channels: I) -> Summary
+where
+    I: IntoIterator<Item = &'a MarketChannel>,
+{
+    channels.into_iter().fold(Summary::default(), |mut summary, channel| {
+        let status = &channel.status.status_type;
+        summary.total += 1;
+        if status.is_active() {
+            summary.active += 1;
+        }
+        *summary.by_status.entry(status.clone()).or_insert(0) += 1;
+        summary
+    })
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct MarketStatus {
     #[serde(rename = "name")]
@@ -73,15 +484,112 @@ pub struct MarketStatus {
     pub balances: HashMap<String, BigNum>,
     #[serde(with = "ts_milliseconds")]
     pub last_checked: DateTime<Utc>,
+    // Set once `balances` has been cut down to the biggest earners, see `trim`
+    #[serde(skip)]
+    pub trimmed: Option<TrimmedBalances>,
+}
+
+// What was worked out from the full balances before they were dropped
+#[derive(Clone, Debug)]
+pub struct TrimmedBalances {
+    pub paid: BigNum,
+    pub earners: usize,
+    pub concentration: f64,
 }
 
 impl MarketStatus {
     pub fn balances_sum(&self) -> BigNum {
-        self.balances.iter().map(|(_, v)| v).sum()
+        match &self.trimmed {
+            Some(trimmed) => trimmed.paid.clone(),
+            None => bignum::sum(self.balances.values()),
+        }
+    }
+
+    pub fn earner_count(&self) -> usize {
+        self.trimmed
+            .as_ref()
+            .map_or(self.balances.len(), |trimmed| trimmed.earners)
+    }
+
+    // Keeps only the `keep` biggest earners, remembering the totals of all of them
+    // Long-running channels have thousands of earners, and most views only need the totals
+    pub fn trim(&mut self, keep: usize) {
+        if self.trimmed.is_some() || self.balances.len() <= keep {
+            return;
+        }
+        let trimmed = TrimmedBalances {
+            paid: self.balances_sum(),
+            earners: self.balances.len(),
+            concentration: earner_concentration(self),
+        };
+        let mut balances = self.balances.drain().collect::<Vec<_>>();
+        balances.sort_by(|(_, a), (_, b)| b.cmp(a));
+        balances.truncate(keep);
+        self.balances = balances.into_iter().collect();
+        self.balances.shrink_to_fit();
+        self.trimmed = Some(trimmed);
     }
 }
 
-#[derive(Deserialize, Clone, Debug)]
+// Per-channel figures derived from the balances, computed once per load
+#[derive(Serialize, Clone, Debug)]
+pub struct ChannelStats {
+    pub paid: BigNum,
+    pub earners: usize,
+    pub top_earner: Option<(String, BigNum)>,
+    pub concentration: f64,
+}
+
+impl ChannelStats {
+    // The top earner is always among the ones a trimmed status keeps
+    pub fn from_status(status: &MarketStatus) -> Self {
+        let mut top_earner: Option<(&String, &BigNum)> = None;
+        for (addr, amount) in status.balances.iter() {
+            if top_earner.map_or(true, |(_, top)| amount > top) {
+                top_earner = Some((addr, amount));
+            }
+        }
+        ChannelStats {
+            paid: status.balances_sum(),
+            earners: status.earner_count(),
+            top_earner: top_earner.map(|(addr, amount)| (addr.clone(), amount.clone())),
+            concentration: earner_concentration(status),
+        }
+    }
+
+    // Share of the top earner in the total paid, in percent
+    pub fn top_earner_share(&self) -> Option<f64> {
+        let (_, top) = self.top_earner.as_ref()?;
+        if self.paid == BigNum::from(0) {
+            return None;
+        }
+        let basis_points = (top * &BigNum::from(10_000)).div_floor(&self.paid);
+        Some(basis_points.to_f64()? / 100.0)
+    }
+}
+
+// Herfindahl index of the payouts: the sum of each earner's squared share, from 1 / earners
+// (evenly spread) up to 1.0 (a single earner); 0.0 when nothing was paid
+pub fn earner_concentration(status: &MarketStatus) -> f64 {
+    if let Some(trimmed) = &status.trimmed {
+        return trimmed.concentration;
+    }
+    let paid = status.balances_sum();
+    if paid == BigNum::from(0) {
+        return 0.0;
+    }
+    status
+        .balances
+        .values()
+        .filter_map(|amount| {
+            let basis_points = (amount * &BigNum::from(10_000)).div_floor(&paid);
+            basis_points.to_f64()
+        })
+        .map(|basis_points| (basis_points / 10_000.0).powi(2))
+        .sum()
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct MarketChannel {
     pub id: String,
@@ -89,5 +597,539 @@ pub struct MarketChannel {
     pub deposit_asset: String,
     pub deposit_amount: BigNum,
     pub status: MarketStatus,
-    pub spec: ChannelSpec,
+    // Missing on some older channels, which then never expire as far as the explorer knows
+    #[serde(default, with = "ts_seconds_option")]
+    pub valid_until: Option<DateTime<Utc>>,
+    pub spec: MarketSpec,
+}
+
+impl MarketChannel {
+    // The price advertisers bid per impression, in the deposit asset's smallest unit
+    // The market spec only carries the minimum, which is what gets paid out
+    pub fn price_per_impression(&self) -> &BigNum {
+        &self.spec.min_per_impression
+    }
+
+    // What the leader and the follower take out of the deposit, together
+    pub fn validator_fees(&self) -> BigNum {
+        let validators = &self.spec.validators;
+        &validators.leader().fee + &validators.follower().fee
+    }
+
+    // What the creator can still withdraw: deposit minus everything paid out
+    // Balances can exceed the deposit due to fee accounting quirks, in which case it's clamped to 0
+    pub fn unspent(&self) -> (BigNum, bool) {
+        bignum::saturating_sub(&self.deposit_amount, &self.status.balances_sum())
+    }
+
+    // None once the channel is past its validity, or if it has none
+    pub fn time_until_expiry(&self, now_seconds: i64) -> Option<Duration> {
+        let left = self.valid_until?.timestamp() - now_seconds;
+        if left > 0 {
+            Some(Duration::seconds(left))
+        } else {
+            None
+        }
+    }
+
+    // Channels opened with the same creator, ad units and title are considered the same campaign
+    pub fn fingerprint(&self) -> String {
+        let mut units = self
+            .spec
+            .ad_units
+            .iter()
+            .map(|unit| unit.ipfs.as_str())
+            .collect::<Vec<_>>();
+        units.sort();
+        format!(
+            "{}|{}|{}",
+            self.creator.to_lowercase(),
+            units.join(","),
+            self.spec.title.as_ref().map_or("", |title| title.trim())
+        )
+    }
+
+    // What to call the channel wherever a person reads it; see `name` for the order of preference
+    pub fn display_name(&self, labels: &Labels) -> String {
+        self.name(labels).unwrap_or_else(|| self.id_prefix())
+    }
+
+    // The campaign title, else the title of its first ad unit, else the creator's label with the
+    // id prefix; None if there's none of those, and blank titles don't count
+    pub fn name(&self, labels: &Labels) -> Option<String> {
+        let non_blank = |title: &Option<String>| {
+            title
+                .as_ref()
+                .map(|title| title.trim())
+                .filter(|title| !title.is_empty())
+                .map(str::to_owned)
+        };
+        non_blank(&self.spec.title)
+            .or_else(|| non_blank(&self.spec.ad_units.get(0)?.title))
+            .or_else(|| {
+                labels
+                    .name(&self.creator)
+                    .map(|creator| format!("{} {}", creator, self.id_prefix()))
+            })
+    }
+
+    pub fn id_prefix(&self) -> String {
+        self.id.chars().take(6).collect()
+    }
+}
+
+// The headline figures, kept from one refresh to the next to show what changed
+#[derive(Serialize, Clone, Debug)]
+pub struct Totals {
+    pub deposit: BigNum,
+    pub paid: BigNum,
+    pub unspent: BigNum,
+    // At each channel's minimum price; channels without one are left out
+    pub impressions: BigNum,
+}
+
+impl Totals {
+    pub fn of(channels: &[MarketChannel]) -> Self {
+        Totals {
+            deposit: bignum::sum(channels.iter().map(|c| &c.deposit_amount)),
+            paid: bignum::sum(channels.iter().map(|c| c.status.balances_sum())),
+            unspent: bignum::sum(channels.iter().map(|c| c.unspent().0)),
+            impressions: bignum::sum(
+                channels
+                    .iter()
+                    .filter(|c| *c.price_per_impression() != BigNum::from(0))
+                    .map(|c| c.status.balances_sum().div_floor(c.price_per_impression())),
+            ),
+        }
+    }
+}
+
+// The latest messages a validator exchanged about a channel, from /channel/<id>/validator-messages
+// Only the fields that help explain an unhealthy channel are kept; all of them are optional,
+// since each message type carries different ones
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidatorMessagesResp {
+    pub validator_messages: Vec<ValidatorMessage>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ValidatorMessage {
+    pub from: String,
+    pub received: Option<DateTime<Utc>>,
+    pub msg: ValidatorMessageBody,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidatorMessageBody {
+    // e.g. "Heartbeat", "NewState", "ApproveState", "RejectState"
+    #[serde(rename = "type")]
+    pub message_type: String,
+    pub timestamp: Option<DateTime<Utc>>,
+    // ApproveState: whether the follower considers the channel healthy
+    pub is_healthy: Option<bool>,
+    // RejectState: why the follower rejected the leader's state
+    pub reason: Option<String>,
+}
+
+// What we could find out from the leader about an unhealthy channel
+#[derive(Serialize, Clone, Debug)]
+pub enum ValidatorReport {
+    Loading,
+    Loaded(ValidatorMessagesResp),
+    // Often the very reason the channel is offline
+    Unreachable(String),
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+pub enum ValidatorRole {
+    Leader,
+    Follower,
+}
+
+// The latest Heartbeat a validator signed for a channel, as its own sentry reports it
+#[derive(Serialize, Clone, Debug)]
+pub enum Heartbeat {
+    Loading,
+    Signed(DateTime<Utc>),
+    // The validator answered, but has no heartbeat for the channel
+    Missing,
+    Unavailable(String),
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct Heartbeats {
+    pub leader: Heartbeat,
+    pub follower: Heartbeat,
+}
+
+impl Heartbeats {
+    pub fn get_mut(&mut self, role: ValidatorRole) -> &mut Heartbeat {
+        match role {
+            ValidatorRole::Leader => &mut self.leader,
+            ValidatorRole::Follower => &mut self.follower,
+        }
+    }
+}
+
+// Event counts of a channel from /channel/<id>/events-aggregates, one entry per period the
+// validator aggregated; only the counts are kept
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct EventAggregates {
+    pub events: Vec<EventAggregate>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct EventAggregate {
+    pub created: DateTime<Utc>,
+    // Event type, e.g. "IMPRESSION" or "CLICK"
+    pub events: HashMap<String, AggregateCounts>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AggregateCounts {
+    // Earner -> how many events
+    #[serde(default)]
+    pub event_counts: HashMap<String, BigNum>,
+}
+
+// The recent activity of a channel, loaded from its leader when asked for
+#[derive(Serialize, Clone, Debug)]
+pub enum ChannelActivity {
+    Loading,
+    Loaded(EventAggregates),
+    // Why there's no chart, e.g. the validator wants authentication
+    Unavailable(String),
+}
+
+// Data structs for publisher ad slots
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AdSlotTag {
+    pub tag: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AdSlot {
+    pub owner: String,
+    #[serde(rename = "type")]
+    pub slot_type: String,
+    pub ipfs: String,
+    #[serde(with = "ts_milliseconds")]
+    pub created: DateTime<Utc>,
+    #[serde(default)]
+    pub tags: Vec<AdSlotTag>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+    use serde_json::json;
+
+    #[test]
+    fn fingerprint_ignores_creator_case_unit_order_and_title_padding() {
+        let mut a = fixtures::channel_json("0x01");
+        a["spec"]["adUnits"] = json!([fixtures::ad_unit("QmA"), fixtures::ad_unit("QmB")]);
+        a["spec"]["title"] = json!("Summer sale");
+        let mut b = fixtures::channel_json("0x02");
+        b["creator"] = json!(fixtures::CREATOR.to_uppercase().replace("0X", "0x"));
+        b["spec"]["adUnits"] = json!([fixtures::ad_unit("QmB"), fixtures::ad_unit("QmA")]);
+        b["spec"]["title"] = json!("  Summer sale ");
+
+        let a = fixtures::parse(a);
+        let b = fixtures::parse(b);
+        assert_eq!(a.fingerprint(), b.fingerprint());
+        assert_eq!(
+            a.fingerprint(),
+            format!("{}|QmA,QmB|Summer sale", fixtures::CREATOR.to_lowercase())
+        );
+    }
+
+    #[test]
+    fn fingerprint_tells_campaigns_apart() {
+        let base = fixtures::channel("0x01");
+
+        let mut other_title = fixtures::channel_json("0x02");
+        other_title["spec"]["title"] = json!("Winter sale");
+        let mut other_units = fixtures::channel_json("0x03");
+        other_units["spec"]["adUnits"] = json!([fixtures::ad_unit("QmOther")]);
+        let mut other_creator = fixtures::channel_json("0x04");
+        other_creator["creator"] = json!(fixtures::LEADER);
+
+        for other in vec![other_title, other_units, other_creator] {
+            assert_ne!(base.fingerprint(), fixtures::parse(other).fingerprint());
+        }
+    }
+
+    fn failure(resp: PageResponse) -> FetchError {
+        match resp {
+            PageResponse::Failed(reason) => reason,
+            other => panic!("expected a failure, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn responses_map_to_pages_or_errors() {
+        match PageResponse::from_response(200, "OK", Some("\"v1\"".to_owned()), Ok("[]".to_owned())) {
+            PageResponse::Body { etag, body } => {
+                assert_eq!(etag.as_ref().map(String::as_str), Some("\"v1\""));
+                assert_eq!(body, "[]");
+            }
+            other => panic!("expected a body, got {:?}", other),
+        }
+        match PageResponse::from_response(304, "Not Modified", None, Ok(String::new())) {
+            PageResponse::NotModified => {}
+            other => panic!("expected not modified, got {:?}", other),
+        }
+        match failure(PageResponse::from_response(502, "Bad Gateway", None, Ok(String::new()))) {
+            FetchError::Http(502, text) => assert_eq!(text, "Bad Gateway"),
+            other => panic!("expected HTTP 502, got {:?}", other),
+        }
+        match failure(PageResponse::from_response(200, "OK", None, Err("reset".to_owned()))) {
+            FetchError::Network(reason) => assert_eq!(reason, "reset"),
+            other => panic!("expected a network error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn only_transient_errors_are_retried() {
+        assert!(FetchError::Network("offline".to_owned()).is_retryable());
+        assert!(FetchError::Timeout.is_retryable());
+        assert!(FetchError::Http(500, String::new()).is_retryable());
+        assert!(FetchError::Http(503, String::new()).is_retryable());
+        assert!(!FetchError::Http(404, String::new()).is_retryable());
+        assert!(!FetchError::Http(429, String::new()).is_retryable());
+        assert!(!FetchError::Deserialize("missing field `id`".to_owned()).is_retryable());
+    }
+
+    #[test]
+    fn errors_read_as_what_went_wrong() {
+        assert_eq!(FetchError::Http(502, String::new()).to_string(), "market returned HTTP 502");
+        assert_eq!(
+            FetchError::Http(503, "Service Unavailable".to_owned()).to_string(),
+            "market returned HTTP 503 Service Unavailable"
+        );
+        assert_eq!(FetchError::Timeout.to_string(), "market did not respond in time");
+        assert_eq!(
+            FetchError::Deserialize("missing field `id`".to_owned()).to_string(),
+            "response format changed: missing field `id`"
+        );
+    }
+
+    #[test]
+    fn valid_until_may_be_missing() {
+        let channel = fixtures::channel("0x01");
+        assert_eq!(channel.valid_until.map(|t| t.timestamp()), Some(fixtures::VALID_UNTIL));
+        assert_eq!(
+            channel.time_until_expiry(fixtures::VALID_UNTIL - 60),
+            Some(Duration::seconds(60))
+        );
+        assert_eq!(channel.time_until_expiry(fixtures::VALID_UNTIL), None);
+
+        let mut null = fixtures::channel_json("0x02");
+        null["validUntil"] = json!(null);
+        let mut missing = fixtures::channel_json("0x03");
+        missing.as_object_mut().unwrap().remove("validUntil");
+        for json in vec![null, missing] {
+            let channel = fixtures::parse(json);
+            assert!(channel.valid_until.is_none());
+            assert_eq!(channel.time_until_expiry(0), None);
+        }
+    }
+
+    #[test]
+    fn valid_until_round_trips_as_seconds() {
+        let channel = fixtures::channel("0x01");
+        let json = serde_json::to_value(&channel).unwrap();
+        assert_eq!(json["validUntil"], json!(fixtures::VALID_UNTIL));
+    }
+
+    fn with_status(id: &str, status: &str) -> MarketChannel {
+        let mut json = fixtures::channel_json(id);
+        json["status"]["name"] = json!(status);
+        fixtures::parse(json)
+    }
+
+    #[test]
+    fn summarize_counts_campaigns_by_status() {
+        let channels = vec![
+            with_status("a", "Active"),
+            with_status("b", "Active"),
+            with_status("c", "Ready"),
+            with_status("d", "Expired"),
+            with_status("e", "Active"),
+        ];
+        let summary = summarize(&channels);
+        assert_eq!(summary.total, 5);
+        assert_eq!(summary.active, 3);
+        let by_status = summary.by_status.into_iter().collect::<Vec<_>>();
+        assert_eq!(
+            by_status,
+            vec![
+                (MarketStatusType::Ready, 1),
+                (MarketStatusType::Active, 3),
+                (MarketStatusType::Expired, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn summarize_nothing() {
+        assert_eq!(summarize(&Vec::<MarketChannel>::new()), Summary::default());
+    }
+
+    #[test]
+    fn fingerprint_without_title() {
+        let mut json = fixtures::channel_json("0x01");
+        json["spec"]["title"] = json!(null);
+        json["spec"]["adUnits"] = json!([]);
+        let channel = fixtures::parse(json);
+        assert_eq!(channel.fingerprint(), format!("{}||", fixtures::CREATOR.to_lowercase()));
+    }
+
+    // A channel named by nothing but what the test sets
+    fn unnamed() -> serde_json::Value {
+        let mut json = fixtures::channel_json("0x1234abcd");
+        json["spec"]["title"] = json!(null);
+        json["spec"]["adUnits"][0]["title"] = json!(null);
+        json
+    }
+
+    fn creator_labeled(name: &str) -> Labels {
+        let mut labels = Labels::default();
+        labels.set_user(
+            std::iter::once((fixtures::CREATOR.to_owned(), name.to_owned())).collect(),
+        );
+        labels
+    }
+
+    #[test]
+    fn named_by_the_campaign_title_first() {
+        let mut json = unnamed();
+        json["spec"]["title"] = json!(" Summer sale ");
+        json["spec"]["adUnits"][0]["title"] = json!("Banner");
+        let channel = fixtures::parse(json);
+        let labels = creator_labeled("Acme");
+        assert_eq!(channel.name(&labels), Some("Summer sale".to_owned()));
+        assert_eq!(channel.display_name(&labels), "Summer sale");
+    }
+
+    #[test]
+    fn then_by_the_first_ad_unit_title() {
+        let mut json = unnamed();
+        json["spec"]["title"] = json!("   ");
+        json["spec"]["adUnits"][0]["title"] = json!("Banner");
+        let channel = fixtures::parse(json);
+        assert_eq!(channel.display_name(&creator_labeled("Acme")), "Banner");
+    }
+
+    #[test]
+    fn then_by_the_creator_label_and_the_id() {
+        let mut json = unnamed();
+        json["spec"]["adUnits"][0]["title"] = json!("");
+        let channel = fixtures::parse(json);
+        assert_eq!(channel.name(&creator_labeled("Acme")), Some("Acme 0x1234".to_owned()));
+    }
+
+    #[test]
+    fn else_only_by_the_id() {
+        let mut json = unnamed();
+        json["spec"]["adUnits"] = json!([]);
+        let channel = fixtures::parse(json);
+        assert_eq!(channel.name(&Labels::default()), None);
+        assert_eq!(channel.display_name(&Labels::default()), "0x1234");
+    }
+
+    // Roughly what the balances of a status take on the heap: the map's slots, the addresses and
+    // the digits of the amounts
+    fn balances_heap_bytes(status: &MarketStatus) -> usize {
+        let slot = std::mem::size_of::<(String, BigNum)>() + 1;
+        let entries = status
+            .balances
+            .iter()
+            .map(|(addr, amount)| {
+                let bits = bignum::to_decimal_string(amount).len() * 10 / 3;
+                addr.capacity() + (bits + 63) / 64 * 8
+            })
+            .sum::<usize>();
+        status.balances.capacity() * slot + entries
+    }
+
+    fn status_with_earners(id: &str, earners: u64) -> MarketStatus {
+        let mut status = fixtures::channel(id).status;
+        status.balances = (0..earners)
+            .map(|e| (format!("0x{:040x}", e), BigNum::from((e + 1) * 1_000_000_000_000_000)))
+            .collect();
+        status
+    }
+
+    #[test]
+    fn trimming_keeps_the_totals_the_earner_count_and_the_top_earners() {
+        let earners = 50;
+        let mut status = status_with_earners("0x01", earners);
+        let paid = status.balances_sum();
+        status.trim(10);
+        assert_eq!(status.balances.len(), 10);
+        assert_eq!(status.balances_sum(), paid);
+        assert_eq!(status.earner_count(), earners as usize);
+        // The biggest earner is kept
+        assert!(status.balances.contains_key(&format!("0x{:040x}", earners - 1)));
+    }
+
+    // A measurement rather than a check; run with `cargo test -- --ignored --nocapture`
+    #[test]
+    #[ignore]
+    fn trimming_frees_most_of_the_balances() {
+        // 100 long-running channels with 2,000 earners each
+        let (channel_count, earners) = (100, 2000u64);
+        let mut statuses = (0..channel_count)
+            .map(|i| status_with_earners(&format!("0x{:02x}", i), earners))
+            .collect::<Vec<_>>();
+        let before = statuses.iter().map(balances_heap_bytes).sum::<usize>();
+        for status in statuses.iter_mut() {
+            status.trim(10);
+        }
+        let after = statuses.iter().map(balances_heap_bytes).sum::<usize>();
+        println!(
+            "balances of {} channels with {} earners: {} KiB before trimming, {} KiB after",
+            channel_count,
+            earners,
+            before / 1024,
+            after / 1024
+        );
+        assert!(after * 100 < before, "{} of {} bytes left", after, before);
+    }
+
+    fn with_validators(count: usize) -> serde_json::Value {
+        let mut json = fixtures::channel_json("0x01");
+        json["spec"]["validators"].as_array_mut().unwrap().truncate(count);
+        json
+    }
+
+    #[test]
+    fn a_spec_keeps_the_validators_it_listed() {
+        for count in 0..=2 {
+            let json = with_validators(count);
+            let channel = fixtures::parse(json.clone());
+            assert_eq!(channel.spec.validator_count, count);
+            // The placeholders aren't written back, so the raw JSON shows what came in
+            let written = serde_json::to_value(&channel).unwrap();
+            assert_eq!(written["spec"]["validators"], json["spec"]["validators"], "{}", count);
+        }
+    }
+
+    #[test]
+    fn missing_validators_are_filled_in_without_an_id_or_a_url() {
+        let listed = fixtures::channel("0x02");
+        let channel = fixtures::parse(with_validators(1));
+        assert_eq!(channel.spec.validators.leader().url, listed.spec.validators.leader().url);
+        assert_eq!(channel.spec.validators.follower().url, "");
+        assert_eq!(channel.spec.validators.follower().fee, BigNum::from(0));
+
+        let channel = fixtures::parse(with_validators(0));
+        assert_eq!(channel.spec.validators.leader().url, "");
+        assert_eq!(channel.spec.validators.follower().url, "");
+    }
 }
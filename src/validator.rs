@@ -0,0 +1,145 @@
+// Validator URLs in channel specs are written by hand, so the same validator shows up as
+// "https://tom.adex.network", "https://TOM.adex.network/" or "http://tom.adex.network:80"
+// Everything that displays, links to or groups by a validator goes through here
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct NormalizedUrl {
+    // Lowercase hostname without scheme, port or path; the raw URL if it can't be parsed
+    pub host: String,
+    // Scheme, lowercase host, non-default port and path, without a trailing slash
+    // None unless it's an http(s) URL with a sane host, so it's safe to build links on
+    pub base: Option<String>,
+}
+
+pub fn normalize_url(url: &str) -> NormalizedUrl {
+    let url = url.trim();
+    let invalid = || NormalizedUrl {
+        host: url.to_owned(),
+        base: None,
+    };
+    let lower = url.to_lowercase();
+    let (scheme, default_port) = if lower.starts_with("https://") {
+        ("https", "443")
+    } else if lower.starts_with("http://") {
+        ("http", "80")
+    } else {
+        return invalid();
+    };
+    let rest = &url[scheme.len() + "://".len()..];
+    if rest.contains(char::is_whitespace) {
+        return invalid();
+    }
+
+    let (authority, path) = match rest.find(|c: char| c == '/' || c == '?' || c == '#') {
+        Some(i) => (&rest[..i], &rest[i..]),
+        None => (rest, ""),
+    };
+    let authority = authority.to_lowercase();
+    let (host, port) = match authority.rfind(':') {
+        Some(i) => (&authority[..i], Some(&authority[i + 1..])),
+        None => (authority.as_str(), None),
+    };
+    let valid_host = !host.is_empty()
+        && host
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-');
+    let valid_port = port.map_or(true, |port| {
+        !port.is_empty() && port.chars().all(|c| c.is_ascii_digit())
+    });
+    if !valid_host || !valid_port {
+        return invalid();
+    }
+
+    let port = match port {
+        Some(port) if port != default_port => format!(":{}", port),
+        _ => String::new(),
+    };
+    // Queries and fragments make no sense on a base that gets paths appended
+    let path = path
+        .split(|c: char| c == '?' || c == '#')
+        .next()
+        .unwrap_or("")
+        .trim_end_matches('/');
+    NormalizedUrl {
+        host: host.to_owned(),
+        base: Some(format!("{}://{}{}{}", scheme, host, port, path)),
+    }
+}
+
+// Shorthand for the places that only need something to show
+pub fn display_host(url: &str) -> String {
+    normalize_url(url).host
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn base(url: &str) -> Option<String> {
+        normalize_url(url).base
+    }
+
+    #[test]
+    fn spellings_of_the_same_validator_agree() {
+        let spellings = [
+            "https://tom.adex.network",
+            "https://tom.adex.network/",
+            "https://TOM.adex.network",
+            "HTTPS://tom.adex.network",
+            " https://tom.adex.network ",
+            "https://tom.adex.network:443",
+            "https://tom.adex.network/?query#fragment",
+        ];
+        for url in spellings.iter() {
+            let normalized = normalize_url(url);
+            assert_eq!(normalized.host, "tom.adex.network", "{}", url);
+            assert_eq!(normalized.base, Some("https://tom.adex.network".to_owned()), "{}", url);
+        }
+    }
+
+    #[test]
+    fn ports_and_paths_are_kept_when_they_matter() {
+        assert_eq!(base("http://tom.adex.network:80"), Some("http://tom.adex.network".to_owned()));
+        assert_eq!(
+            base("http://localhost:8005"),
+            Some("http://localhost:8005".to_owned())
+        );
+        assert_eq!(
+            base("https://tom.adex.network:80"),
+            Some("https://tom.adex.network:80".to_owned())
+        );
+        assert_eq!(
+            base("https://example.com/validator/"),
+            Some("https://example.com/validator".to_owned())
+        );
+        assert_eq!(normalize_url("http://localhost:8005").host, "localhost");
+    }
+
+    #[test]
+    fn unusable_urls_have_no_base() {
+        let invalid = [
+            "",
+            "tom.adex.network",
+            "ftp://tom.adex.network",
+            "javascript:alert(1)",
+            "https://",
+            "https://:443",
+            "https://tom adex.network",
+            "https://tom.adex.network:",
+            "https://tom.adex.network:https",
+            "https://user@tom.adex.network",
+            "https://<script>/",
+        ];
+        for url in invalid.iter() {
+            let normalized = normalize_url(url);
+            assert_eq!(normalized.base, None, "{}", url);
+            assert_eq!(normalized.host, url.trim(), "{}", url);
+        }
+    }
+
+    #[test]
+    fn display_host_is_the_host() {
+        assert_eq!(display_host("https://Jerry.AdEx.network/"), "jerry.adex.network");
+        assert_eq!(display_host("not a url"), "not a url");
+    }
+}
@@ -0,0 +1,49 @@
+// Why a channel is unhealthy, as far as its leader can tell: shown on the detail page of
+// Unhealthy, Offline and Disconnected channels
+use super::{time_cell, types, Msg};
+
+use seed::prelude::*;
+use types::{ValidatorMessage, ValidatorReport};
+
+pub fn validator_report(now_seconds: i64, report: &ValidatorReport, local_time: bool) -> Node<Msg> {
+    div![
+        class!["validator-report"],
+        h3!["What the leader says"],
+        match report {
+            ValidatorReport::Loading => p!["Asking the leader..."],
+            ValidatorReport::Unreachable(reason) => p![
+                class!["warning"],
+                format!("The leader could not be reached ({}), which may be why the channel is unhealthy", reason)
+            ],
+            ValidatorReport::Loaded(resp) if resp.validator_messages.is_empty() => {
+                p!["The leader has no messages about this channel"]
+            }
+            ValidatorReport::Loaded(resp) => table![
+                tr![td!["From"], td!["Type"], td!["Sent"], td!["Details"]],
+                resp.validator_messages
+                    .iter()
+                    .map(|message| message_row(now_seconds, message, local_time))
+                    .collect::<Vec<_>>()
+            ],
+        }
+    ]
+}
+
+fn message_row(now_seconds: i64, message: &ValidatorMessage, local_time: bool) -> Node<Msg> {
+    let body = &message.msg;
+    let details = match (body.is_healthy, &body.reason) {
+        (_, Some(reason)) => format!("Rejected: {}", reason),
+        (Some(true), None) => "Follower reports healthy".to_owned(),
+        (Some(false), None) => "Follower reports unhealthy".to_owned(),
+        (None, None) => String::new(),
+    };
+    tr![
+        td![message.from.clone()],
+        td![body.message_type.clone()],
+        match body.timestamp.as_ref().or_else(|| message.received.as_ref()) {
+            Some(sent) => time_cell(now_seconds, sent, local_time),
+            None => td!["—"],
+        },
+        td![details],
+    ]
+}